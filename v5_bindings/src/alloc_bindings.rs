@@ -29,6 +29,8 @@ unsafe fn alloc_error_handler(layout: Layout) -> !{
 #[panic_handler]
 unsafe fn panic_handler(panic_info: &PanicInfo) -> !{
     printf(str_to_char_ptr(format!("{}", format!("Panic! Info: {:?}", panic_info).bg(red()).fg(white())).as_str()).as_ptr());
+    #[cfg(feature = "queue_dump")]
+    crate::sync::queue_dump::dump_queues();
     loop {}
 }
 
@@ -0,0 +1,147 @@
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+use alloc::string::String;
+use core::fmt::{Display, Formatter};
+use core::sync::atomic::{AtomicBool, Ordering};
+use core::time::Duration;
+
+use v5_traits::stream::{SendTimeoutStream, ReceiveTimoutStream};
+use v5_traits::UniversalFunctions;
+
+use crate::raw::vex_os::api::vexSystemHighResTimeGet;
+use crate::sync::queue::Queue;
+use crate::task::Task;
+
+/// Latencies are counted in buckets of this width, percentiles are reported as the upper edge of their bucket
+const BUCKET_WIDTH: Duration = Duration::from_micros(10);
+/// Covers 0 to 40.96ms, slower messages go in the last bucket and only show in max
+const BUCKETS: usize = 4096;
+const SEND_TIMEOUT: Duration = Duration::from_millis(10);
+
+#[derive(Clone, Debug)]
+pub struct BenchResult{
+    pub name: String,
+    pub messages: u64,
+    pub duration: Duration,
+    /// Send to receive latency percentiles 50, 90, 99 and max over every message, the percentiles are rounded up to BUCKET_WIDTH
+    pub latency_percentiles: [Duration; 4],
+}
+impl BenchResult{
+    pub fn messages_per_second(&self) -> f64{
+        self.messages as f64 / self.duration.as_secs_f64()
+    }
+}
+impl Display for BenchResult{
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        write!(
+            f,
+            "[bench] {}: {} messages in {}ms, {:.0} msg/s, latency p50: {}us, p90: {}us, p99: {}us, max: {}us",
+            self.name,
+            self.messages,
+            self.duration.as_millis(),
+            self.messages_per_second(),
+            self.latency_percentiles[0].as_micros(),
+            self.latency_percentiles[1].as_micros(),
+            self.latency_percentiles[2].as_micros(),
+            self.latency_percentiles[3].as_micros(),
+        )
+    }
+}
+
+/// Counts every message's latency so percentiles cover the whole run in fixed memory
+struct LatencyHistogram{
+    buckets: Vec<u32>,
+    count: u64,
+    max: Duration,
+}
+impl LatencyHistogram{
+    fn new() -> Self{
+        Self{ buckets: vec![0; BUCKETS], count: 0, max: Duration::default() }
+    }
+
+    fn record(&mut self, latency: Duration){
+        let index = (latency.as_nanos() / BUCKET_WIDTH.as_nanos()) as usize;
+        self.buckets[index.min(BUCKETS - 1)] += 1;
+        self.count += 1;
+        self.max = self.max.max(latency);
+    }
+
+    fn percentile(&self, percent: u64) -> Duration{
+        if percent >= 100{
+            return self.max;
+        }
+        let target = self.count * percent / 100;
+        let mut seen = 0;
+        for (index, &count) in self.buckets.iter().enumerate(){
+            seen += count as u64;
+            if seen > target{
+                return (BUCKET_WIDTH * (index as u32 + 1)).min(self.max);
+            }
+        }
+        self.max
+    }
+}
+
+fn high_res_time() -> Duration{
+    Duration::from_micros(unsafe { vexSystemHighResTimeGet() })
+}
+
+/// Sends timestamps from a producer task to the current task for duration and measures throughput and latency
+/// sender and receiver may be the same stream
+pub fn bench_stream<UF, S, R>(uf: &UF, name: impl Display, sender: Arc<S>, receiver: &R, duration: Duration) -> BenchResult
+    where UF: UniversalFunctions,
+          S: 'static + SendTimeoutStream<SData=u64> + Send + Sync,
+          R: ReceiveTimoutStream<RData=u64>{
+    let name = format!("{}", name);
+    let running = Arc::new(AtomicBool::new(true));
+    let producer_done = Arc::new(AtomicBool::new(false));
+    {
+        let uf = uf.clone();
+        let running = running.clone();
+        let producer_done = producer_done.clone();
+        Task::new(None, None, format!("bench_{}_producer", name), move |_|{
+            while running.load(Ordering::SeqCst){
+                sender.send_timeout(high_res_time().as_micros() as u64, SEND_TIMEOUT, &uf);
+            }
+            producer_done.store(true, Ordering::SeqCst);
+        }, ());
+    }
+
+    let mut latencies = LatencyHistogram::new();
+    let start = high_res_time();
+    let end = start + duration;
+    while high_res_time() < end{
+        if let Some(sent) = receiver.receive_timeout(SEND_TIMEOUT, uf){
+            latencies.record(high_res_time().checked_sub(Duration::from_micros(sent)).unwrap_or_default());
+        }
+    }
+    let elapsed = high_res_time() - start;
+    running.store(false, Ordering::SeqCst);
+    while !producer_done.load(Ordering::SeqCst){
+        if receiver.receive_timeout(SEND_TIMEOUT, uf).is_none(){
+            Task::delay_yield();
+        }
+    }
+    while receiver.receive_timeout(Duration::default(), uf).is_some(){}
+
+    BenchResult{
+        name,
+        messages: latencies.count,
+        duration: elapsed,
+        latency_percentiles: [latencies.percentile(50), latencies.percentile(90), latencies.percentile(99), latencies.percentile(100)],
+    }
+}
+
+/// Benchmarks PROS queues of a few lengths and logs the results at info level
+/// There is no lock free RingStream in the tree yet to compare against, once one lands give it a bench_stream run here
+pub fn run_benchmarks(uf: &impl UniversalFunctions) -> Vec<BenchResult>{
+    let duration = Duration::from_secs(2);
+    let mut out = Vec::new();
+    for &length in &[1, 16, 256]{
+        let queue = Arc::new(Queue::new(length));
+        let result = bench_stream(uf, format!("queue_{}", length), queue.clone(), queue.as_ref(), duration);
+        uf.log_info(|| &result);
+        out.push(result);
+    }
+    out
+}
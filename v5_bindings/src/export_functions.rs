@@ -1,17 +1,27 @@
-use crate::robot::port::Port;
+use crate::robot::peripherals::Peripherals;
 use crate::robot::Robot;
 use crate::user_functions::*;
+use crate::task::Task;
+use core::time::Duration;
+use v5_traits::sync_cell::set_busy_wait_hook;
 
 static mut ROBOT: Option<Robot> = None;
 
 #[no_mangle]
 extern "C" fn rust_initialize() {
-    unsafe { ROBOT = Some(rust_user_initialize(Port::get_all())) };
+    // Delays so a task waiting for a busy SyncCell lets a lower priority task holding it finish
+    set_busy_wait_hook(|| Task::delay(Duration::from_millis(1)));
+    let peripherals = Peripherals::take().expect("Peripherals taken before rust_initialize");
+    unsafe { ROBOT = Some(rust_user_initialize(peripherals)) };
     #[cfg(feature = "v5_test")]
         {
             use crate::test::*;
             test_runner(unsafe { get_tests(ROBOT.as_ref().unwrap()) });
         }
+    #[cfg(feature = "v5_bench")]
+        {
+            crate::bench::run_benchmarks(&crate::V5UniversalFunctions);
+        }
 }
 
 #[no_mangle]
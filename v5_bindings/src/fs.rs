@@ -0,0 +1,99 @@
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use cty::{c_char, c_int, c_void};
+use v5_traits::error::Error;
+
+use crate::raw::pros::misc::usd_is_installed;
+use crate::raw::str_to_char_ptr;
+
+const READ_CHUNK: usize = 512;
+
+/// A file on the SD card, paths start with /usd/
+/// Closed when dropped
+#[derive(Debug)]
+pub struct File{
+    file: *mut c_void,
+    path: String,
+}
+impl File{
+    /// Opens path for reading
+    pub fn open(path: &str) -> Result<Self, FileError>{
+        Self::open_mode(path, "rb")
+    }
+
+    /// Opens path for writing, replacing anything already there
+    pub fn create(path: &str) -> Result<Self, FileError>{
+        Self::open_mode(path, "wb")
+    }
+
+    fn open_mode(path: &str, mode: &str) -> Result<Self, FileError>{
+        if unsafe { usd_is_installed() } == 0{
+            return Err(FileError::NoCard);
+        }
+        let file = unsafe { fopen(str_to_char_ptr(path).as_ptr(), str_to_char_ptr(mode).as_ptr()) };
+        if file.is_null(){
+            return Err(FileError::Open{ path: path.to_string() });
+        }
+        Ok(Self{ file, path: path.to_string() })
+    }
+
+    pub fn path(&self) -> &str{
+        &self.path
+    }
+
+    /// Reads everything from the current position to the end of the file
+    pub fn read_to_end(&mut self) -> Vec<u8>{
+        let mut out = Vec::new();
+        loop {
+            let start = out.len();
+            out.resize(start + READ_CHUNK, 0);
+            let read = unsafe { fread(out[start..].as_mut_ptr() as *mut c_void, 1, READ_CHUNK, self.file) };
+            out.truncate(start + read);
+            if read < READ_CHUNK{
+                return out;
+            }
+        }
+    }
+
+    /// Writes all of data and flushes it to the card
+    pub fn write_all(&mut self, data: &[u8]) -> Result<(), FileError>{
+        let written = unsafe { fwrite(data.as_ptr() as *const c_void, 1, data.len(), self.file) };
+        if written < data.len() || unsafe { fflush(self.file) } != 0{
+            return Err(FileError::Write{ path: self.path.clone(), written });
+        }
+        Ok(())
+    }
+}
+impl Drop for File{
+    fn drop(&mut self) {
+        unsafe { fclose(self.file) };
+    }
+}
+unsafe impl Send for File{}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum FileError{
+    /// No SD card is inserted
+    NoCard,
+    Open{ path: String },
+    /// Only written bytes made it to the file
+    Write{ path: String, written: usize },
+}
+impl Error for FileError{
+    fn is_recoverable(&self) -> bool {
+        true
+    }
+}
+impl From<FileError> for crate::error::Error{
+    fn from(from: FileError) -> Self {
+        Self{ msg: format!("File error: {:?}", from) }
+    }
+}
+
+extern "C"{
+    fn fopen(path: *const c_char, mode: *const c_char) -> *mut c_void;
+    fn fclose(file: *mut c_void) -> c_int;
+    fn fflush(file: *mut c_void) -> c_int;
+    fn fread(buffer: *mut c_void, size: usize, count: usize, file: *mut c_void) -> usize;
+    fn fwrite(buffer: *const c_void, size: usize, count: usize, file: *mut c_void) -> usize;
+}
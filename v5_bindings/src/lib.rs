@@ -30,12 +30,18 @@ mod export_functions;
 
 pub mod error;
 pub mod percent;
+pub mod scheduler;
 pub mod task;
+pub mod task_local;
 // Doesn't work
 // pub mod usb_serial;
 pub mod user_functions;
 pub mod util;
 
+#[cfg(feature = "v5_bench")]
+pub mod bench;
+#[cfg(feature = "filesystem")]
+pub mod fs;
 #[cfg(feature = "v5_test")]
 pub mod test;
 #[cfg(feature = "example_functions")]
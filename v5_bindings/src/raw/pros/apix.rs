@@ -17,10 +17,20 @@ extern "C" {
     pub fn queue_delete(queue: queue_t);
     pub fn queue_reset(queue: queue_t);
 
+    /// FreeRTOS queue functions PROS does not wrap
+    /// copy_position is QUEUE_SEND_TO_BACK, QUEUE_SEND_TO_FRONT or for length 1 queues QUEUE_OVERWRITE
+    pub fn xQueueGenericSend(queue: queue_t, item: *const c_void, ticks_to_wait: uint32_t, copy_position: c_long) -> c_long;
+    pub fn xQueueGenericSendFromISR(queue: queue_t, item: *const c_void, higher_priority_task_woken: *mut c_long, copy_position: c_long) -> c_long;
+    pub fn xQueueReceiveFromISR(queue: queue_t, buffer: *mut c_void, higher_priority_task_woken: *mut c_long) -> c_long;
+
     pub fn serctl(action: uint32_t, extra_arg: *mut c_void);
     pub fn fdctl(file: c_int, action: uint32_t, extra_arg: *mut c_void);
 }
 
+pub const QUEUE_SEND_TO_BACK: c_long = 0;
+pub const QUEUE_SEND_TO_FRONT: c_long = 1;
+pub const QUEUE_OVERWRITE: c_long = 2;
+
 pub static SERCTL_ACTIVATE: uint32_t = 10;
 pub static SERCTL_DEACTIVATE: uint32_t = 11;
 pub static SERCTL_BLKWRITE: uint32_t = 12;
@@ -53,6 +53,8 @@ extern "C"{
     pub fn task_notify_ext(task: task_t, value: uint32_t, action: notify_action_e_t, prev_value: *mut uint32_t) -> uint32_t;
     pub fn task_notify_take(clear_on_exit: bool, timeout: uint32_t) -> uint32_t;
     pub fn task_notify_clear(task: task_t) -> bool;
+    pub fn pvTaskGetThreadLocalStoragePointer(task: task_t, index: int32_t) -> *mut c_void;
+    pub fn vTaskSetThreadLocalStoragePointer(task: task_t, index: int32_t, value: *mut c_void);
     pub fn mutex_create() -> mutex_t;
     pub fn mutex_take(mutex: mutex_t, timeout: uint32_t) -> bool;
     pub fn mutex_give(mutex: mutex_t) -> bool;
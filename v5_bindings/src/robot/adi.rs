@@ -0,0 +1,180 @@
+use crate::raw::pros::adi::*;
+use crate::raw::pros::api::PROS_ERR;
+use crate::robot::port_registry::{PORT_REGISTRY, PortKind, PortInUse};
+use v5_traits::error::Error;
+
+/// The amount of three wire ports on the brain
+pub const ADI_PORT_COUNT: u8 = 8;
+
+/// A three wire port, taken from Peripherals
+/// Ports are numbered from 0 with the zero_based_ports feature and from 1 otherwise, like smart ports
+#[derive(Debug)]
+pub struct AdiPort{
+    number: u8,
+}
+impl AdiPort{
+    /// index is from 0 regardless of zero_based_ports
+    pub(crate) fn new(index: u8) -> Self{
+        #[cfg(not(feature = "zero_based_ports"))]
+        let number = index + 1;
+        #[cfg(feature = "zero_based_ports")]
+        let number = index;
+        Self{ number }
+    }
+
+    pub const fn number(&self) -> u8{
+        self.number
+    }
+
+    /// PROS numbers three wire ports from 1
+    fn pros_port(&self) -> u8{
+        #[cfg(not(feature = "zero_based_ports"))]
+        return self.number;
+        #[cfg(feature = "zero_based_ports")]
+        return self.number + 1;
+    }
+}
+
+/// A three wire port configured as a digital input
+#[derive(Debug)]
+pub struct AdiDigitalIn{
+    port: AdiPort,
+}
+impl AdiDigitalIn{
+    /// Panics if the port is already bound, use try_new to handle that
+    pub fn new(port: AdiPort) -> Self{
+        match Self::try_new(port){
+            Ok(input) => input,
+            Err(error) => panic!("Could not create digital input: {:?}", error),
+        }
+    }
+
+    pub fn try_new(port: AdiPort) -> Result<Self, AdiError>{
+        configure(&port, adi_port_config_e::E_ADI_DIGITAL_IN)?;
+        Ok(Self{ port })
+    }
+
+    pub fn port(&self) -> &AdiPort{
+        &self.port
+    }
+
+    pub fn is_high(&self) -> Result<bool, AdiError>{
+        match unsafe { adi_port_get_value(self.port.pros_port()) }{
+            PROS_ERR => Err(AdiError::Pros{ port: self.port.number() }),
+            value => Ok(value != 0),
+        }
+    }
+
+    pub fn is_low(&self) -> Result<bool, AdiError>{
+        self.is_high().map(|high| !high)
+    }
+}
+/// For drivers written against embedded-hal, errors are the PROS errors of is_high
+#[cfg(feature = "embedded-hal")]
+impl embedded_hal::digital::v2::InputPin for AdiDigitalIn{
+    type Error = AdiError;
+
+    fn is_high(&self) -> Result<bool, AdiError> {
+        AdiDigitalIn::is_high(self)
+    }
+
+    fn is_low(&self) -> Result<bool, AdiError> {
+        AdiDigitalIn::is_low(self)
+    }
+}
+impl Drop for AdiDigitalIn{
+    fn drop(&mut self) {
+        PORT_REGISTRY.release(PortKind::Adi, self.port.number());
+    }
+}
+
+/// A three wire port configured as a digital output, starts low
+#[derive(Debug)]
+pub struct AdiDigitalOut{
+    port: AdiPort,
+}
+impl AdiDigitalOut{
+    /// Panics if the port is already bound, use try_new to handle that
+    pub fn new(port: AdiPort) -> Self{
+        match Self::try_new(port){
+            Ok(output) => output,
+            Err(error) => panic!("Could not create digital output: {:?}", error),
+        }
+    }
+
+    pub fn try_new(port: AdiPort) -> Result<Self, AdiError>{
+        configure(&port, adi_port_config_e::E_ADI_DIGITAL_OUT)?;
+        let out = Self{ port };
+        out.set(false)?;
+        Ok(out)
+    }
+
+    pub fn port(&self) -> &AdiPort{
+        &self.port
+    }
+
+    pub fn set(&self, high: bool) -> Result<(), AdiError>{
+        match unsafe { adi_port_set_value(self.port.pros_port(), high as i32) }{
+            PROS_ERR => Err(AdiError::Pros{ port: self.port.number() }),
+            _ => Ok(()),
+        }
+    }
+
+    pub fn set_high(&self) -> Result<(), AdiError>{
+        self.set(true)
+    }
+
+    pub fn set_low(&self) -> Result<(), AdiError>{
+        self.set(false)
+    }
+}
+/// For drivers written against embedded-hal, errors are the PROS errors of set
+#[cfg(feature = "embedded-hal")]
+impl embedded_hal::digital::v2::OutputPin for AdiDigitalOut{
+    type Error = AdiError;
+
+    fn set_low(&mut self) -> Result<(), AdiError> {
+        AdiDigitalOut::set_low(self)
+    }
+
+    fn set_high(&mut self) -> Result<(), AdiError> {
+        AdiDigitalOut::set_high(self)
+    }
+}
+impl Drop for AdiDigitalOut{
+    fn drop(&mut self) {
+        PORT_REGISTRY.release(PortKind::Adi, self.port.number());
+    }
+}
+
+/// Claims port and sets its PROS configuration, releasing it again if that fails
+fn configure(port: &AdiPort, config: adi_port_config_e) -> Result<(), AdiError>{
+    PORT_REGISTRY.claim(PortKind::Adi, port.number())?;
+    if unsafe { adi_port_set_config(port.pros_port(), config) } == PROS_ERR{
+        PORT_REGISTRY.release(PortKind::Adi, port.number());
+        return Err(AdiError::Pros{ port: port.number() });
+    }
+    Ok(())
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum AdiError{
+    InUse(PortInUse),
+    /// PROS returned an error for port, usually because it is configured as something else
+    Pros{ port: u8 },
+}
+impl Error for AdiError{
+    fn is_recoverable(&self) -> bool {
+        true
+    }
+}
+impl From<PortInUse> for AdiError{
+    fn from(from: PortInUse) -> Self {
+        Self::InUse(from)
+    }
+}
+impl From<AdiError> for crate::error::Error{
+    fn from(from: AdiError) -> Self {
+        Self{ msg: format!("ADI error: {:?}", from) }
+    }
+}
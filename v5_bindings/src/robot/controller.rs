@@ -1,4 +1,7 @@
+use alloc::sync::Arc;
+use alloc::vec::Vec;
 use core::fmt::Display;
+use core::time::Duration;
 
 pub use Id::kControllerMaster as Master;
 pub use Id::kControllerPartner as Partner;
@@ -11,6 +14,8 @@ use crate::raw::vex_os::api::*;
 use crate::raw::vex_os::api_types::{V5_ControllerIndex, V5_ControllerIndexFinal};
 pub use crate::raw::vex_os::api_types::V5_ControllerId as Id;
 pub use crate::raw::vex_os::api_types::V5_ControllerStatus as Status;
+use crate::sync::queue::Queue;
+use crate::task::Task;
 
 pub struct Controller{
     id: Id,
@@ -52,6 +57,55 @@ impl Controller{
     pub fn battery_level(&self) -> i32{
         unsafe { vexControllerGet(self.id.into(), V5_ControllerIndex::BatteryLevel) }
     }
+
+    /// Spawns a task that polls this controller every poll_interval and sends each change into the returned queue
+    /// A battery event is sent whenever battery_capacity crosses one of battery_thresholds
+    /// Events are dropped if the queue is full so a slow reader never stalls polling
+    pub fn event_stream(&self, poll_interval: Duration, battery_thresholds: Vec<i32>, queue_length: u32) -> Arc<Queue<ControllerEvent>>{
+        let queue = Arc::new(Queue::new(queue_length));
+        let task_queue = queue.clone();
+        let id = self.id;
+        Task::new(None, None, format!("controller_{:?}_events", id), move |_|{
+            let send = |event|{
+                let _ = task_queue.append(event, Some(Duration::new(0, 0)));
+            };
+            let controller = Controller::new(id);
+            let mut status = Offline;
+            let mut capacity = controller.battery_capacity();
+            loop {
+                let new_status = controller.status();
+                if new_status != status{
+                    status = new_status;
+                    send(if status == Offline { ControllerEvent::Disconnected } else { ControllerEvent::Connected(status) });
+                }
+                if status != Offline{
+                    let new_capacity = controller.battery_capacity();
+                    for &threshold in &battery_thresholds{
+                        if capacity >= threshold && new_capacity < threshold{
+                            send(ControllerEvent::BatteryBelow(threshold));
+                        }
+                        else if capacity < threshold && new_capacity >= threshold{
+                            send(ControllerEvent::BatteryAbove(threshold));
+                        }
+                    }
+                    capacity = new_capacity;
+                }
+                Task::delay(poll_interval);
+            }
+        }, ());
+        queue
+    }
+}
+
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub enum ControllerEvent{
+    /// The controller connected or changed to a different connection type
+    Connected(Status),
+    Disconnected,
+    /// Battery capacity fell below the threshold
+    BatteryBelow(i32),
+    /// Battery capacity rose to or above the threshold
+    BatteryAbove(i32),
 }
 
 #[derive(Copy, Clone, Eq, PartialEq, Debug)]
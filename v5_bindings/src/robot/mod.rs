@@ -1,6 +1,9 @@
+pub mod adi;
 pub mod controller;
 pub mod motor;
+pub mod peripherals;
 pub mod port;
+pub mod port_registry;
 pub mod serial;
 pub mod vision;
 
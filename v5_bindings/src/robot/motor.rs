@@ -1,14 +1,33 @@
 use crate::raw::vex_os::api::*;
-use crate::raw::vex_os::api_types::V5MotorControlMode;
+use crate::raw::vex_os::api_types::{V5MotorControlMode, V5_DeviceType};
 use crate::robot::port::Port;
+use crate::robot::port_registry::{PORT_REGISTRY, PortKind, PortInUse};
+use crate::scheduler::Scheduler;
+use crate::sync::queue::Queue;
+use crate::task::Task;
+use crate::V5UniversalFunctions;
+use alloc::sync::Arc;
 use core::time::Duration;
+use v5_traits::UniversalFunctions;
 
 pub struct Motor {
     port: Port,
+    /// False for the read only copies used by telemetry tasks, they do not own the port's claim
+    registered: bool,
 }
 
 impl Motor {
+    /// Panics if another device wrapper is bound to port, use try_new to handle that
     pub fn new(port: Port, reverse: bool, brake: bool) -> Self {
+        match Self::try_new(port, reverse, brake) {
+            Ok(motor) => motor,
+            Err(error) => panic!("Could not create motor: {:?}", error),
+        }
+    }
+
+    /// Returns Err if another device wrapper is bound to port
+    pub fn try_new(port: Port, reverse: bool, brake: bool) -> Result<Self, PortInUse> {
+        PORT_REGISTRY.claim(PortKind::Smart, port.number())?;
         unsafe {
             vexDeviceMotorReverseFlagSet(port.device(), reverse);
             vexDeviceMotorModeSet(
@@ -16,9 +35,10 @@ impl Motor {
                 if brake { V5MotorControlMode::kMotorControlModeBRAKE } else { V5MotorControlMode::kMotorControlModeOFF }
             );
         }
-        Self {
-            port
-        }
+        Ok(Self {
+            port,
+            registered: true,
+        })
     }
 
     pub fn set_current_limit(&self, limit: i32) {
@@ -64,6 +84,45 @@ impl Motor {
     pub fn reset_position(&self){
         unsafe { vexDeviceMotorPositionReset(self.port.device()) }
     }
+    /// Actual velocity in rpm
+    pub fn velocity(&self) -> f64{
+        unsafe { vexDeviceMotorActualVelocityGet(self.port.device()) }
+    }
+    pub fn is_connected(&self) -> bool{
+        self.port.device_type() == V5_DeviceType::kDeviceTypeMotorSensor
+    }
+    pub fn state(&self) -> MotorState{
+        MotorState{
+            position: self.position(),
+            velocity: self.velocity(),
+            current: self.get_current(),
+            temperature: self.get_temperature(),
+        }
+    }
+
+    /// Spawns a task that sends the motor's state every period into the returned queue
+    /// Samples are dropped when the queue is full and skipped while the motor is disconnected,
+    /// disconnecting and reconnecting are logged as warnings
+    pub fn telemetry_stream(&self, period: Duration, queue_length: u32) -> Arc<Queue<MotorState>>{
+        let queue = Arc::new(Queue::new(queue_length));
+        let task_queue = queue.clone();
+        let motor = Motor{ port: self.port.duplicate(), registered: false };
+        Task::new(None, None, format!("motor_{}_telemetry", motor.port.number()), move |_|{
+            let mut scheduler = Scheduler::new(V5UniversalFunctions);
+            let mut connected = true;
+            scheduler.add_task(period, move ||{
+                if motor.is_connected() != connected{
+                    connected = !connected;
+                    V5UniversalFunctions.log_warn(|| format!("Motor on port {} {}", motor.port.number(), if connected { "reconnected" } else { "disconnected" }));
+                }
+                if connected{
+                    let _ = task_queue.append(motor.state(), Some(Duration::new(0, 0)));
+                }
+            });
+            scheduler.run()
+        }, ());
+        queue
+    }
 
     /// Sets motor voltage in mV from -12000 to 12000
     pub fn set_voltage(&self, voltage: i32) {
@@ -83,3 +142,22 @@ impl Motor {
         }
     }
 }
+impl Drop for Motor {
+    fn drop(&mut self) {
+        if self.registered {
+            PORT_REGISTRY.release(PortKind::Smart, self.port.number());
+        }
+    }
+}
+
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct MotorState{
+    /// Raw encoder ticks
+    pub position: i32,
+    /// Actual velocity in rpm
+    pub velocity: f64,
+    /// Current in mA
+    pub current: i32,
+    /// Temperature in C
+    pub temperature: f64,
+}
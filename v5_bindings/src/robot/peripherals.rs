@@ -0,0 +1,51 @@
+use core::sync::atomic::{AtomicBool, Ordering};
+
+use init_with::InitWith;
+
+use crate::robot::adi::{AdiPort, ADI_PORT_COUNT};
+use crate::robot::controller::{Controller, Master, Partner};
+use crate::robot::port::Port;
+
+static TAKEN: AtomicBool = AtomicBool::new(false);
+
+/// Owns every port and controller, device wrappers take their port by value so each port can only be driven by one wrapper
+/// Only one Peripherals can exist, take returns it the first time and None after.
+/// rust_initialize takes it and passes it to rust_user_initialize, so user code gets its ports from there
+pub struct Peripherals{
+    #[cfg(not(feature = "zero_based_ports"))]
+    pub smart_ports: [Option<Port>; 22],
+    #[cfg(feature = "zero_based_ports")]
+    pub smart_ports: [Port; 21],
+    pub adi_ports: [AdiPort; ADI_PORT_COUNT as usize],
+    pub master_controller: Controller,
+    pub partner_controller: Controller,
+}
+impl Peripherals{
+    /// Returns Some the first time it is called and None after
+    pub fn take() -> Option<Self>{
+        if TAKEN.swap(true, Ordering::SeqCst){
+            None
+        }
+        else{
+            Some(unsafe { Self::steal() })
+        }
+    }
+
+    /// True if take has returned the peripherals
+    pub fn is_taken() -> bool{
+        TAKEN.load(Ordering::SeqCst)
+    }
+
+    /// Creates the peripherals regardless of whether they were taken
+    /// # Safety
+    /// Creates a second handle to every port, nothing may use a port through both.
+    /// The taken peripherals always exist once rust_user_initialize is called
+    pub unsafe fn steal() -> Self{
+        Self{
+            smart_ports: Port::get_all(),
+            adi_ports: <[AdiPort; ADI_PORT_COUNT as usize]>::init_with_indices(|i| AdiPort::new(i as u8)),
+            master_controller: Controller::new(Master),
+            partner_controller: Controller::new(Partner),
+        }
+    }
+}
@@ -1,7 +1,7 @@
 use init_with::InitWith;
 
-use crate::raw::vex_os::api::vexDeviceGetByIndex;
-use crate::raw::vex_os::api_types::V5_DeviceT;
+use crate::raw::vex_os::api::{vexDeviceGetByIndex, vexDeviceGetStatus};
+use crate::raw::vex_os::api_types::{V5_DeviceT, V5_DeviceType};
 
 pub type PortType = V5_DeviceT;
 
@@ -12,7 +12,7 @@ pub struct Port {
 }
 impl Port {
     #[cfg(not(feature = "zero_based_ports"))]
-    pub(crate) fn get_all() -> [Option<Self>; 22] {
+    pub(super) fn get_all() -> [Option<Self>; 22] {
         <[Option<Self>; 22]>::init_with_indices(|i| {
             if i == 0 {
                 None
@@ -26,7 +26,7 @@ impl Port {
     }
 
     #[cfg(feature = "zero_based_ports")]
-    pub(crate) fn get_all() -> [Self; 21] {
+    pub(super) fn get_all() -> [Self; 21] {
         <[Self; 21]>::init_with_indices(|i| { Self{ number: i as u8, device: unsafe { vexDeviceGetByIndex(i as u32) } } })
     }
 
@@ -37,6 +37,22 @@ impl Port {
     pub const fn number(&self) -> u8{
         self.number
     }
+
+    /// The type of device currently plugged into this port, kDeviceTypeNoSensor if nothing is
+    pub fn device_type(&self) -> V5_DeviceType{
+        let mut types = [V5_DeviceType::kDeviceTypeNoSensor; 32];
+        unsafe { vexDeviceGetStatus(types.as_mut_ptr()) };
+        #[cfg(not(feature = "zero_based_ports"))]
+        let index = self.number as usize - 1;
+        #[cfg(feature = "zero_based_ports")]
+        let index = self.number as usize;
+        types[index]
+    }
+
+    /// A second handle to the same port for read only use such as telemetry tasks
+    pub(crate) fn duplicate(&self) -> Self{
+        Self{ number: self.number, device: self.device }
+    }
 }
 unsafe impl Send for Port{}
 unsafe impl Sync for Port{}
@@ -0,0 +1,99 @@
+use core::sync::atomic::{AtomicU32, Ordering};
+
+use v5_traits::error::Error;
+
+/// Tracks which ports are bound to a device wrapper
+pub static PORT_REGISTRY: PortRegistry = PortRegistry::new();
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum PortKind{
+    Smart,
+    Adi,
+}
+
+/// The smart and ADI ports currently bound to a device wrapper
+/// Device constructors claim their port and release it when dropped so two wrappers can not drive the same port.
+/// Claims are bits in atomics rather than a locked set so the registry can be a static without runtime initialization
+#[derive(Debug)]
+pub struct PortRegistry{
+    smart: AtomicU32,
+    adi: AtomicU32,
+}
+impl PortRegistry{
+    const fn new() -> Self{
+        Self{ smart: AtomicU32::new(0), adi: AtomicU32::new(0) }
+    }
+
+    fn claims(&self, kind: PortKind) -> &AtomicU32{
+        match kind {
+            PortKind::Smart => &self.smart,
+            PortKind::Adi => &self.adi,
+        }
+    }
+
+    fn bit(number: u8) -> u32{
+        assert!(number < 32, "Port number {} out of range", number);
+        1 << number
+    }
+
+    /// Binds port number of kind, returns Err if it is already bound
+    pub fn claim(&self, kind: PortKind, number: u8) -> Result<(), PortInUse>{
+        let bit = Self::bit(number);
+        if self.claims(kind).fetch_or(bit, Ordering::SeqCst) & bit != 0{
+            Err(PortInUse{ kind, number })
+        }
+        else{
+            Ok(())
+        }
+    }
+
+    /// Unbinds port number of kind so another wrapper can claim it
+    pub fn release(&self, kind: PortKind, number: u8){
+        self.claims(kind).fetch_and(!Self::bit(number), Ordering::SeqCst);
+    }
+
+    pub fn is_claimed(&self, kind: PortKind, number: u8) -> bool{
+        self.claims(kind).load(Ordering::SeqCst) & Self::bit(number) != 0
+    }
+}
+
+/// A device wrapper was created on a port that is already bound to another
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct PortInUse{
+    pub kind: PortKind,
+    pub number: u8,
+}
+impl Error for PortInUse{
+    fn is_recoverable(&self) -> bool {
+        true
+    }
+}
+impl From<PortInUse> for crate::error::Error{
+    fn from(from: PortInUse) -> Self {
+        Self{ msg: format!("{:?} port {} is already in use", from.kind, from.number) }
+    }
+}
+
+#[cfg(feature = "v5_test")]
+pub mod test{
+    use alloc::boxed::Box;
+    use alloc::string::ToString;
+    use core::time::Duration;
+
+    use crate::robot::port_registry::{PortRegistry, PortKind, PortInUse};
+    use crate::test::{assert, TestItem, TestType};
+
+    pub fn port_registry_test() -> TestItem{
+        TestItem::new("port_registry_test".to_string(), TestType::Parallel(Box::new(|| {
+            let registry = PortRegistry::new();
+            assert(registry.claim(PortKind::Smart, 3).is_ok(), "First claim failed!".to_string())?;
+            let second = registry.claim(PortKind::Smart, 3);
+            assert(second == Err(PortInUse{ kind: PortKind::Smart, number: 3 }), format!("Second claim invalid! Should be: Err, is: {:?}", second))?;
+            assert(registry.claim(PortKind::Adi, 3).is_ok(), "ADI claim conflicted with smart port!".to_string())?;
+            registry.release(PortKind::Smart, 3);
+            assert(!registry.is_claimed(PortKind::Smart, 3), "Port still claimed after release!".to_string())?;
+            assert(registry.claim(PortKind::Smart, 3).is_ok(), "Claim after release failed!".to_string())?;
+            Ok(())
+        }), Duration::from_secs(1)))
+    }
+}
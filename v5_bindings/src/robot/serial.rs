@@ -1,4 +1,5 @@
 use crate::robot::port::Port;
+use crate::robot::port_registry::{PORT_REGISTRY, PortKind, PortInUse};
 use crate::raw::vex_os::api::*;
 use v5_traits::stream::*;
 use alloc::vec::Vec;
@@ -7,13 +8,28 @@ pub struct Serial{
     port: Port,
 }
 impl Serial{
+    /// Panics if another device wrapper is bound to port, use try_new to handle that
     pub fn new(port: Port, baud_rate: BaudRate) -> Self{
+        match Self::try_new(port, baud_rate){
+            Ok(serial) => serial,
+            Err(error) => panic!("Could not create serial: {:?}", error),
+        }
+    }
+
+    /// Returns Err if another device wrapper is bound to port
+    pub fn try_new(port: Port, baud_rate: BaudRate) -> Result<Self, PortInUse>{
+        PORT_REGISTRY.claim(PortKind::Smart, port.number())?;
         unsafe {
             vexDeviceGenericSerialEnable(port.device(), 0);
             vexDeviceGenericSerialBaudrate(port.device(), baud_rate as i32);
             vexDeviceGenericSerialFlush(port.device());
         }
-        Self{ port }
+        Ok(Self{ port })
+    }
+}
+impl Drop for Serial{
+    fn drop(&mut self) {
+        PORT_REGISTRY.release(PortKind::Smart, self.port.number());
     }
 }
 impl SendStream for Serial{
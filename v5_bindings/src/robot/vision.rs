@@ -13,6 +13,7 @@ pub use crate::raw::vex_os::api_types::V5_DeviceVisionObject as Object;
 pub use crate::raw::vex_os::api_types::V5_DeviceVisionRgb as ColorCode;
 pub use crate::raw::vex_os::api_types::V5VisionWifiMode as WifiMode;
 use crate::robot::port::Port;
+use crate::robot::port_registry::{PORT_REGISTRY, PortKind, PortInUse};
 
 #[derive(Copy, Clone, Debug)]
 pub struct Signature {
@@ -76,12 +77,22 @@ pub struct Vision {
 }
 
 impl Vision {
+    /// Panics if another device wrapper is bound to port, use try_new to handle that
     pub fn new(port: Port) -> Self {
+        match Self::try_new(port) {
+            Ok(vision) => vision,
+            Err(error) => panic!("Could not create vision: {:?}", error),
+        }
+    }
+
+    /// Returns Err if another device wrapper is bound to port
+    pub fn try_new(port: Port) -> Result<Self, PortInUse> {
+        PORT_REGISTRY.claim(PortKind::Smart, port.number())?;
         let out = Self {
             port
         };
         unsafe { vexDeviceVisionLedModeSet(out.port.device(), V5VisionLedMode::kVisionLedModeManual) };
-        out
+        Ok(out)
     }
 
     pub fn object_count(&self) -> usize {
@@ -150,3 +161,8 @@ impl Vision {
         unsafe { vexDeviceVisionWifiModeSet(self.port.device(), mode) }
     }
 }
+impl Drop for Vision {
+    fn drop(&mut self) {
+        PORT_REGISTRY.release(PortKind::Smart, self.port.number());
+    }
+}
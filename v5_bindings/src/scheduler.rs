@@ -0,0 +1,75 @@
+use alloc::boxed::Box;
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+use core::sync::atomic::{AtomicU32, Ordering};
+use core::time::Duration;
+
+use v5_traits::UniversalFunctions;
+
+use crate::task::Task;
+
+struct ScheduledTask{
+    period: Duration,
+    next_run: Duration,
+    function: Box<dyn FnMut() + Send>,
+    overruns: Arc<AtomicU32>,
+}
+
+/// Runs closures at fixed rates on a single task
+/// Run times are kept on a fixed schedule from when run is called so they do not drift,
+/// a closure that takes longer than its period is logged as an overrun and its missed runs are skipped
+pub struct Scheduler<UF> where UF: UniversalFunctions{
+    uf: UF,
+    tasks: Vec<ScheduledTask>,
+}
+impl<UF> Scheduler<UF> where UF: UniversalFunctions{
+    pub fn new(uf: UF) -> Self{
+        Self{ uf, tasks: Vec::new() }
+    }
+
+    /// Adds a closure to be run every period
+    /// Returns the amount of times the closure took longer than its period, readable from other tasks while run is running
+    pub fn add_task(&mut self, period: Duration, function: impl 'static + FnMut() + Send) -> Arc<AtomicU32>{
+        let overruns = Arc::new(AtomicU32::new(0));
+        self.tasks.push(ScheduledTask{ period, next_run: Duration::default(), function: Box::new(function), overruns: overruns.clone() });
+        overruns
+    }
+
+    /// Runs the added closures forever on the current task
+    pub fn run(&mut self) -> !{
+        let start = self.uf.system_time();
+        for task in &mut self.tasks{
+            task.next_run = start;
+        }
+        loop {
+            let next_run = match self.tasks.iter().map(|task| task.next_run).min(){
+                None => {
+                    Task::delay(Duration::from_millis(100));
+                    continue;
+                },
+                Some(next_run) => next_run,
+            };
+            let now = self.uf.system_time();
+            if next_run > now{
+                Task::delay_until(now, next_run - now);
+            }
+
+            for (index, task) in self.tasks.iter_mut().enumerate(){
+                let run_start = self.uf.system_time();
+                if task.next_run > run_start{
+                    continue;
+                }
+                (task.function)();
+                let run_end = self.uf.system_time();
+                task.next_run += task.period;
+                if run_end - run_start > task.period{
+                    task.overruns.fetch_add(1, Ordering::SeqCst);
+                    self.uf.log_warn(|| format!("Scheduler task {} overran its period of {}ms, took {}ms", index, task.period.as_millis(), (run_end - run_start).as_millis()));
+                }
+                while task.next_run <= run_end && task.period > Duration::default(){
+                    task.next_run += task.period;
+                }
+            }
+        }
+    }
+}
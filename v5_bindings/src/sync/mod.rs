@@ -1,10 +1,16 @@
+use alloc::boxed::Box;
 use core::time::Duration;
 
+use v5_traits::sync_cell::SyncCell;
+
 use crate::raw::pros::rtos::TIMEOUT_MAX;
+use crate::sync::queue::QueueCreateError;
 
 pub mod lock;
 pub mod mutex;
 pub mod queue;
+#[cfg(feature = "queue_dump")]
+pub mod queue_dump;
 
 fn option_to_timeout(timeout: Option<Duration>) -> u32{
     match timeout {
@@ -12,3 +18,19 @@ fn option_to_timeout(timeout: Option<Duration>) -> u32{
         Some(duration) => duration.as_millis() as u32,
     }
 }
+
+static ALLOC_FAILURE_HANDLER: SyncCell<fn(QueueCreateError)> = SyncCell::empty();
+
+/// Sets the function called whenever PROS fails to allocate a queue, replacing any previous one
+/// It is called before the failure is reported so it can log or free memory,
+/// Queue::try_new then returns Err, Queue::new panics and the queue creators retry with a single item queue,
+/// calling it again with QueueCreateError::Degraded if they do
+pub fn set_alloc_failure_handler(handler: fn(QueueCreateError)){
+    ALLOC_FAILURE_HANDLER.swap(Some(Box::new(handler)));
+}
+
+fn alloc_failed(error: QueueCreateError){
+    if let Some(handler) = ALLOC_FAILURE_HANDLER.clone_inner(){
+        handler(error);
+    }
+}
@@ -1,68 +1,194 @@
 use crate::raw::pros::apix::*;
+use core::any::type_name;
+use core::fmt::{Debug, Formatter};
 use core::marker::PhantomData;
-use core::mem::{size_of, forget, MaybeUninit};
+use core::mem::{size_of, forget, needs_drop, ManuallyDrop, MaybeUninit};
+use core::ops::{Deref, DerefMut};
+use core::sync::atomic::{AtomicBool, AtomicU32, Ordering};
 use core::time::Duration;
 use cty::c_void;
-use crate::sync::option_to_timeout;
-use v5_traits::stream::{SendStream, SendTimeoutStream, ReceiveStream, ReceiveTimoutStream, MessageStreamCreator};
+use crate::sync::{option_to_timeout, alloc_failed};
+use crate::error::NumericError;
+use crate::task::Task;
+use crate::system_time;
+use v5_traits::stream::{SendStream, SendTimeoutStream, ReceiveStream, ReceiveTimoutStream, BoundedStream, ClosableStream, Closed, MessageStreamCreator};
 use v5_traits::UniversalFunctions;
+use v5_traits::error::Error;
 use alloc::sync::Arc;
+use alloc::vec::Vec;
+#[cfg(feature = "filesystem")]
+use v5_traits::codec::{Codec, DecodeError, EncodeError};
+#[cfg(feature = "filesystem")]
+use crate::fs::{File, FileError};
 
 /// A queue that allows the sending of data across thread boundaries
-/// Sends data of type T
-#[derive(Debug)]
+/// Sends data of type T, zero sized T such as () take a byte each so Queue<()> works as a signalling channel
 pub struct Queue<T> where T: 'static + Send{
     queue: queue_t,
     max_length: u32,
+    next_ticket: AtomicU32,
+    now_serving: AtomicU32,
+    closed: AtomicBool,
+    waiting_send: AtomicU32,
+    waiting_receive: AtomicU32,
     phantom: PhantomData<T>,
 }
+/// The bytes PROS stores per item, PROS can not store zero byte items so zero sized T use one byte
+const fn item_size<T>() -> usize{
+    if size_of::<T>() == 0 { 1 } else { size_of::<T>() }
+}
+/// The byte sent for every zero sized item
+static ZERO_SIZED_ITEM: u8 = 0;
+/// Pointer that PROS copies item_size::<T>() bytes of item from
+fn item_ptr<T>(item: &T) -> *const c_void{
+    if size_of::<T>() == 0{
+        &ZERO_SIZED_ITEM as *const u8 as *const c_void
+    }
+    else{
+        item as *const T as *const c_void
+    }
+}
+/// Calls receive with a buffer of item_size::<T>() bytes and returns the item if receive returns true
+/// # Safety
+/// receive must only return true once it has written a valid T to the buffer
+unsafe fn receive_item<T>(receive: impl FnOnce(*mut c_void) -> bool) -> Option<T>{
+    let mut out = MaybeUninit::<T>::uninit();
+    let mut zero_sized_out = 0u8;
+    let buffer = if size_of::<T>() == 0{
+        &mut zero_sized_out as *mut u8 as *mut c_void
+    }
+    else{
+        out.as_mut_ptr() as *mut c_void
+    };
+    if receive(buffer){
+        Some(out.assume_init())
+    }
+    else{
+        None
+    }
+}
+
 impl<T> Queue<T> where T: 'static + Send{
     /// Creates a new queue that can store up to max_length messages
+    /// Panics if PROS could not allocate the queue, use try_new to handle that
     pub fn new(max_length: u32) -> Self{
-        Self{
-            queue: unsafe{queue_create(max_length, size_of::<T>() as u32)},
+        match Self::try_new(max_length){
+            Ok(queue) => queue,
+            Err(error) => panic!("Could not create queue: {:?}", error),
+        }
+    }
+
+    /// Creates a new queue that can store up to max_length messages
+    /// Returns Err if PROS could not allocate the queue after calling the handler set by set_alloc_failure_handler
+    pub fn try_new(max_length: u32) -> Result<Self, QueueCreateError>{
+        let queue = unsafe{queue_create(max_length, item_size::<T>() as u32)};
+        if queue.is_null(){
+            let error = QueueCreateError::AllocationFailed{ max_length, item_size: item_size::<T>() };
+            alloc_failed(error);
+            return Err(error);
+        }
+        Ok(Self{
+            queue,
             max_length,
+            next_ticket: AtomicU32::new(0),
+            now_serving: AtomicU32::new(0),
+            closed: AtomicBool::new(false),
+            waiting_send: AtomicU32::new(0),
+            waiting_receive: AtomicU32::new(0),
             phantom: Default::default(),
-        }
+        })
     }
 
     /// Prepends data to the front of the queue
     /// Will wait up to timeout for a spot in the queue
-    /// Returns Ok if sent or Err if queue full and timeout reached
-    pub fn prepend(&self, item: T, timeout: Option<Duration>) -> Result<(), T>{
-        if unsafe { queue_prepend(self.queue, &item as *const T as *const c_void, option_to_timeout(timeout)) }{
+    /// Returns Ok if sent or Err holding item if queue full and timeout reached
+    pub fn prepend(&self, item: T, timeout: Option<Duration>) -> Result<(), SendError<T>>{
+        if self.wait_counted(&self.waiting_send, timeout, |ticks| unsafe { queue_prepend(self.queue, item_ptr(&item), ticks) }){
             forget(item);
             Ok(())
         }
         else{
-            Err(item)
+            Err(SendError{ value: item, kind: SendErrorKind::Full })
         }
     }
     /// Appends to the queue
     /// Will wait up to timeout for a spot in the queue
-    /// Returns Ok if sent or Err if queue full and timeout reached
-    pub fn append(&self, item: T, timeout: Option<Duration>) -> Result<(), T>{
-        if unsafe { queue_append(self.queue, &item as *const T as *const c_void, option_to_timeout(timeout)) }{
+    /// Returns Ok if sent or Err holding item if queue full and timeout reached
+    pub fn append(&self, item: T, timeout: Option<Duration>) -> Result<(), SendError<T>>{
+        if self.wait_counted(&self.waiting_send, timeout, |ticks| unsafe { queue_append(self.queue, item_ptr(&item), ticks) }){
             forget(item);
             Ok(())
         }
         else{
-            Err(item)
+            Err(SendError{ value: item, kind: SendErrorKind::Full })
         }
     }
 
     /// Pulls an item out of the queue
     /// Returns Some if item pulled or None if timeout reached
     pub fn queue_receive(&self, timeout: Option<Duration>) -> Option<T>{
-        let mut out = MaybeUninit::uninit();
-        if unsafe { queue_recv(self.queue, out.as_mut_ptr() as *mut c_void, option_to_timeout(timeout)) }{
-            Some(unsafe { out.assume_init() })
+        unsafe { receive_item(|out| self.wait_counted(&self.waiting_receive, timeout, |ticks| queue_recv(self.queue, out, ticks))) }
+    }
+
+    /// Appends each item in order, waiting as long as needed for space
+    /// on_progress is called with the amount appended so far after each item, for showing progress of large batches.
+    /// Every item is moved into the queue so none are dropped here
+    pub fn send_with_progress(&self, items: Vec<T>, mut on_progress: impl FnMut(usize)){
+        for (index, item) in items.into_iter().enumerate(){
+            if self.append(item, None).is_err(){
+                unreachable!("Queue append failed with no timeout");
+            }
+            on_progress(index + 1);
+        }
+    }
+
+    /// Appends to the queue from an interrupt handler, never blocks
+    /// Returns Ok if sent or Err holding item if the queue is full
+    /// # Safety
+    /// Must only be called from interrupt context, use append everywhere else.
+    /// A task woken by this runs at the next scheduler tick rather than when the interrupt returns
+    #[cfg(target_arch = "arm")]
+    pub unsafe fn append_from_isr(&self, item: T) -> Result<(), SendError<T>>{
+        let mut woken = 0;
+        if xQueueGenericSendFromISR(self.queue, item_ptr(&item), &mut woken, QUEUE_SEND_TO_BACK) != 0{
+            forget(item);
+            Ok(())
         }
         else{
-            None
+            Err(SendError{ value: item, kind: SendErrorKind::Full })
         }
     }
 
+    /// Receives from the queue from an interrupt handler, never blocks
+    /// Returns Some if item pulled or None if the queue is empty
+    /// # Safety
+    /// Must only be called from interrupt context, use queue_receive everywhere else.
+    /// A task woken by this runs at the next scheduler tick rather than when the interrupt returns
+    #[cfg(target_arch = "arm")]
+    pub unsafe fn receive_from_isr(&self) -> Option<T>{
+        let mut woken = 0;
+        receive_item(|out| xQueueReceiveFromISR(self.queue, out, &mut woken) != 0)
+    }
+
+    /// Receives an item like receive but hands items to receive_fair callers in the order they started waiting
+    /// FreeRTOS wakes the highest priority task waiting on a queue first and only uses waiting order between equal priorities,
+    /// so a busy high priority consumer starves lower priority ones. Each caller takes a ticket and only the oldest ticket
+    /// waits on the queue, the rest poll every millisecond which can add up to a millisecond of latency.
+    /// There is no timeout because an abandoned ticket would block every later caller,
+    /// and queue_receive on the same queue can take items ahead of any ticket
+    pub fn receive_fair(&self) -> T{
+        let ticket = self.next_ticket.fetch_add(1, Ordering::SeqCst);
+        while self.now_serving.load(Ordering::SeqCst) != ticket{
+            Task::delay(Duration::from_millis(1));
+        }
+        let out = match self.queue_receive(None){
+            None => unreachable!("Queue returned none with no timeout"),
+            Some(val) => val,
+        };
+        self.now_serving.fetch_add(1, Ordering::SeqCst);
+        out
+    }
+
     /// The amount of items in the queue
     pub fn len(&self) -> u32{
         unsafe { queue_get_waiting(self.queue) }
@@ -71,34 +197,396 @@ impl<T> Queue<T> where T: 'static + Send{
     pub fn max_len(&self) -> u32{
         self.max_length
     }
+    /// True if the queue has no items, another task may send one right after
+    pub fn is_empty(&self) -> bool{
+        self.len() == 0
+    }
+    /// True if the queue has max_len items, another task may receive one right after
+    pub fn is_full(&self) -> bool{
+        self.len() >= self.max_length
+    }
+
+    /// The amount of tasks blocked in append or prepend waiting for space, for spotting backpressure and deadlocks
+    /// PROS does not expose its waiting lists so this counts calls that found the queue full and are waiting,
+    /// sends through raw_handle or from interrupts are not counted
+    pub fn waiting_to_send(&self) -> u32{
+        self.waiting_send.load(Ordering::SeqCst)
+    }
+
+    /// The amount of tasks blocked receiving or peeking waiting for an item, counted like waiting_to_send
+    pub fn waiting_to_receive(&self) -> u32{
+        self.waiting_receive.load(Ordering::SeqCst)
+    }
+
+    /// Tries operation without waiting, then if timeout allows waits with it counted in waiting
+    /// operation is given the timeout in ticks and returns true on success
+    fn wait_counted(&self, waiting: &AtomicU32, timeout: Option<Duration>, mut operation: impl FnMut(u32) -> bool) -> bool{
+        if operation(0){
+            return true;
+        }
+        let ticks = option_to_timeout(timeout);
+        if ticks == 0{
+            return false;
+        }
+        waiting.fetch_add(1, Ordering::SeqCst);
+        let out = operation(ticks);
+        waiting.fetch_sub(1, Ordering::SeqCst);
+        out
+    }
 
-    /// Clears all items from the queue dropping each
+    /// The name of the item type for labelling queues in telemetry, only meant to be human readable
+    pub fn element_type_name(&self) -> &'static str{
+        type_name::<T>()
+    }
+
+    /// Clears all items from the queue dropping each, for Copy items reset does this without receiving each
     pub fn clear(&self){
         while let Some(item) = self.queue_receive(Some(Duration::new(0, 0))){
             drop(item);
         }
     }
+    /// Clears items from the queue dropping each until it is empty or uf's system time reaches deadline
+    /// Returns true if the queue was emptied
+    pub fn clear_until_empty(&self, deadline: Duration, uf: &impl UniversalFunctions) -> bool{
+        while uf.system_time() < deadline{
+            match self.queue_receive(Some(Duration::new(0, 0))){
+                None => return true,
+                Some(item) => drop(item),
+            }
+        }
+        self.is_empty()
+    }
+
+    /// Takes the item at the front of the queue and returns a guard that puts it back at the front when dropped
+    /// Will wait up to timeout for an item
+    /// The item is absent from the queue while the guard is held so concurrent receivers get the items behind it,
+    /// if they fill the freed spot dropping the guard waits for space to put it back
+    pub fn peek_guard(&self, timeout: Option<Duration>) -> Option<PeekGuard<'_, T>>{
+        self.queue_receive(timeout).map(|item| PeekGuard{ queue: self, item: Some(item) })
+    }
+
+    /// Clones the item at the front of the queue, leaving it queued
+    /// Will wait up to timeout for an item
+    /// queue_peek gives a bitwise copy that shares any heap memory with the queued original, it is held in a
+    /// ManuallyDrop so only the clone is ever dropped and the original still owns that memory
+    /// # Safety
+    /// No other task or interrupt may receive from the queue while this runs,
+    /// a receiver dropping the original during the clone would free memory the clone is reading.
+    /// For queues with several receivers use peek_guard instead
+    pub unsafe fn peek_cloned(&self, timeout: Option<Duration>) -> Option<T> where T: Clone{
+        let peeked: ManuallyDrop<T> = receive_item(|out| self.wait_counted(&self.waiting_receive, timeout, |ticks| queue_peek(self.queue, out, ticks)))?;
+        Some((*peeked).clone())
+    }
+
+    /// Returns an iterator that takes items from the front of the queue without blocking until it is empty
+    /// Items not yet taken when the iterator is dropped stay in the queue
+    pub fn drain(&self) -> Drain<'_, T>{
+        Drain{ queue: self }
+    }
+
+    /// Moves this queue's items into a new queue that can store up to new_max_length messages
+    /// PROS queues can not be resized in place so this deletes the old queue,
+    /// items beyond new_max_length are dropped and their amount is returned with the new queue
+    /// Panics if PROS could not allocate the new queue
+    pub fn resized(self, new_max_length: u32) -> (Queue<T>, usize){
+        let out = Self::new(new_max_length);
+        let mut dropped = 0;
+        while let Some(item) = self.queue_receive(Some(Duration::new(0, 0))){
+            if out.append(item, Some(Duration::new(0, 0))).is_err(){
+                dropped += 1;
+            }
+        }
+        (out, dropped)
+    }
+
+    /// The underlying PROS queue handle, an escape hatch for passing this queue to C code
+    /// # Safety
+    /// This queue still owns the handle and deletes it on drop, the handle must not be used after that or deleted elsewhere
+    /// Anything sent through the handle must be a valid T of size_of::<T>() bytes, or one byte for zero sized T, whose ownership is given to the queue,
+    /// anything received through the handle takes ownership of that T
+    pub unsafe fn raw_handle(&self) -> queue_t{
+        self.queue
+    }
+}
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum QueueCreateError{
+    /// PROS could not allocate storage for max_length items of item_size bytes
+    AllocationFailed{ max_length: u32, item_size: usize },
+    /// A queue creator could not allocate max_length items and created a queue of length items instead
+    Degraded{ max_length: u32, length: u32, item_size: usize },
+}
+impl Error for QueueCreateError{
+    fn is_recoverable(&self) -> bool {
+        true
+    }
+}
+impl From<QueueCreateError> for crate::error::Error{
+    fn from(from: QueueCreateError) -> Self {
+        Self{ msg: format!("Queue creation failed: {:?}", from) }
+    }
+}
+
+/// A send to a queue that did not go through, value is the item that was not sent
+#[derive(Debug)]
+pub struct SendError<T>{
+    pub value: T,
+    pub kind: SendErrorKind,
 }
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum SendErrorKind{
+    /// The queue was full until the timeout was reached
+    Full,
+}
+impl<T> Error for SendError<T> where T: 'static + Debug{
+    fn is_recoverable(&self) -> bool {
+        true
+    }
+}
+impl<T> From<SendError<T>> for crate::error::Error{
+    fn from(from: SendError<T>) -> Self {
+        Self{ msg: format!("Queue send failed: {:?}", from.kind) }
+    }
+}
+
+#[cfg(feature = "filesystem")]
+#[derive(Debug)]
+pub enum SnapshotError{
+    File(FileError),
+    /// An item could not be encoded, nothing was written and the items stay in the queue
+    Encode(EncodeError),
+    /// The file is corrupt, nothing was appended
+    Decode(DecodeError),
+    /// The queue stayed full, only loaded items were appended
+    QueueFull{ loaded: usize },
+}
+#[cfg(feature = "filesystem")]
+impl Error for SnapshotError{
+    fn is_recoverable(&self) -> bool {
+        true
+    }
+}
+#[cfg(feature = "filesystem")]
+impl From<FileError> for SnapshotError{
+    fn from(from: FileError) -> Self {
+        Self::File(from)
+    }
+}
+#[cfg(feature = "filesystem")]
+impl From<EncodeError> for SnapshotError{
+    fn from(from: EncodeError) -> Self {
+        Self::Encode(from)
+    }
+}
+#[cfg(feature = "filesystem")]
+impl From<DecodeError> for SnapshotError{
+    fn from(from: DecodeError) -> Self {
+        Self::Decode(from)
+    }
+}
+#[cfg(feature = "filesystem")]
+impl From<SnapshotError> for crate::error::Error{
+    fn from(from: SnapshotError) -> Self {
+        Self{ msg: format!("Queue snapshot failed: {:?}", from) }
+    }
+}
+
+/// The front item of a queue taken out by Queue::peek_guard
+/// Dereferences to the item and prepends it back to the queue on drop unless consumed
+#[derive(Debug)]
+pub struct PeekGuard<'a, T> where T: 'static + Send{
+    queue: &'a Queue<T>,
+    item: Option<T>,
+}
+impl<'a, T> PeekGuard<'a, T> where T: 'static + Send{
+    /// Keeps the item out of the queue and returns it
+    pub fn consume(mut self) -> T{
+        self.item.take().unwrap()
+    }
+}
+impl<'a, T> Deref for PeekGuard<'a, T> where T: 'static + Send{
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        self.item.as_ref().unwrap()
+    }
+}
+impl<'a, T> DerefMut for PeekGuard<'a, T> where T: 'static + Send{
+    fn deref_mut(&mut self) -> &mut T {
+        self.item.as_mut().unwrap()
+    }
+}
+impl<'a, T> Drop for PeekGuard<'a, T> where T: 'static + Send{
+    fn drop(&mut self) {
+        if let Some(item) = self.item.take(){
+            if self.queue.prepend(item, None).is_err(){
+                unreachable!("Queue prepend failed with no timeout");
+            }
+        }
+    }
+}
+
+/// Iterator over the items taken from a queue by Queue::drain
+#[derive(Debug)]
+pub struct Drain<'a, T> where T: 'static + Send{
+    queue: &'a Queue<T>,
+}
+impl<'a, T> Iterator for Drain<'a, T> where T: 'static + Send{
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        self.queue.queue_receive(Some(Duration::new(0, 0)))
+    }
+}
+
 impl<T> Queue<T> where T: 'static + Send + Copy{
+    /// Empties the queue in one PROS call without receiving each item
+    /// Only for Copy items as nothing is dropped, clear is the variant that drops items
+    pub fn reset(&self){
+        unsafe { queue_reset(self.queue) }
+    }
+
+    /// Appends items in order until one does not fit, returns the amount appended
+    /// Will wait up to timeout in total rather than per item for spots in the queue
+    pub fn append_slice(&self, items: &[T], timeout: Option<Duration>) -> usize{
+        let end_time = timeout.map(|timeout| system_time() + timeout);
+        for (index, &item) in items.iter().enumerate(){
+            let remaining = end_time.map(|end_time| end_time.checked_sub(system_time()).unwrap_or_default());
+            if self.append(item, remaining).is_err(){
+                return index;
+            }
+        }
+        items.len()
+    }
+
+    /// Creates a new queue that can store up to max_length messages filled with the items of slice in order
+    /// Returns Err if slice has more than max_length items
+    pub fn from_slice(slice: &[T], max_length: u32) -> Result<Self, NumericError<usize>>{
+        if slice.len() > max_length as usize{
+            return Err(NumericError::new(slice.len(), format!("slice length with max_length {}", max_length)));
+        }
+        let out = Self::new(max_length);
+        for &item in slice{
+            if out.append(item, Some(Duration::new(0, 0))).is_err(){
+                unreachable!("Queue full before max_length items appended");
+            }
+        }
+        Ok(out)
+    }
+
     /// Copies the item at the front of the queue if T implements copy
     /// Will wait up to timeout for an item
     /// Returns some with the copied item or None if timeout reached
     pub fn peek(&self, timeout: Option<Duration>) -> Option<T>{
-        let mut out = MaybeUninit::uninit();
-        if unsafe { queue_peek(self.queue, out.as_mut_ptr() as *mut c_void, option_to_timeout(timeout)) }{
-            Some(unsafe { out.assume_init() })
+        unsafe { receive_item(|out| self.wait_counted(&self.waiting_receive, timeout, |ticks| queue_peek(self.queue, out, ticks))) }
+    }
+
+    /// Copies up to the first n items in FIFO order if T implements copy
+    /// Will wait up to timeout for an item if the queue is empty, never waits for more items than are in the queue
+    /// The items are transiently removed and prepended back in reverse so they keep their place at the front,
+    /// other receivers can miss them during this and senders that fill the freed space make this wait to put them back
+    pub fn peek_n(&self, n: usize, timeout: Option<Duration>) -> Vec<T>{
+        if n == 0 || self.peek(timeout).is_none(){
+            return Vec::new();
         }
-        else{
-            None
+        let count = n.min(self.len() as usize);
+        let mut out = Vec::with_capacity(count);
+        for _ in 0..count{
+            match self.queue_receive(Some(Duration::new(0, 0))){
+                None => break,
+                Some(item) => out.push(item),
+            }
+        }
+        for &item in out.iter().rev(){
+            if self.prepend(item, None).is_err(){
+                unreachable!("Queue prepend failed with no timeout");
+            }
+        }
+        out
+    }
+}
+#[cfg(feature = "filesystem")]
+impl<T> Queue<T> where T: 'static + Send{
+    /// Writes the items in the queue to path in order, each as a little endian u32 length followed by its encoding
+    /// The items are transiently removed like peek_n, with keep or if encoding or writing fails they are prepended back in order,
+    /// otherwise they are removed. Returns the amount of items written
+    pub fn save_snapshot(&self, path: &str, codec: &impl Codec<T>, keep: bool) -> Result<usize, SnapshotError>{
+        let mut items = Vec::with_capacity(self.len() as usize);
+        for _ in 0..self.len(){
+            match self.queue_receive(Some(Duration::new(0, 0))){
+                None => break,
+                Some(item) => items.push(item),
+            }
+        }
+        let mut buffer = Vec::new();
+        let mut result = Ok(());
+        for item in &items{
+            let start = buffer.len();
+            buffer.extend_from_slice(&[0; 4]);
+            if let Err(error) = codec.encode(item, &mut buffer){
+                result = Err(error.into());
+                break;
+            }
+            let length = (buffer.len() - start - 4) as u32;
+            buffer[start..start + 4].copy_from_slice(&length.to_le_bytes());
+        }
+        let count = items.len();
+        let result = result.and_then(|_| File::create(path).and_then(|mut file| file.write_all(&buffer)).map_err(SnapshotError::from));
+        if keep || result.is_err(){
+            for item in items.into_iter().rev(){
+                if self.prepend(item, None).is_err(){
+                    unreachable!("Queue prepend failed with no timeout");
+                }
+            }
+        }
+        result.map(|_| count)
+    }
+
+    /// Appends the items saved at path by save_snapshot in order, waiting up to timeout for space for each
+    /// The whole file is decoded first so nothing is appended if it is corrupt. Returns the amount of items appended
+    pub fn load_snapshot(&self, path: &str, codec: &impl Codec<T>, timeout: Option<Duration>) -> Result<usize, SnapshotError>{
+        let data = File::open(path)?.read_to_end();
+        let mut items = Vec::new();
+        let mut rest = data.as_slice();
+        while !rest.is_empty(){
+            if rest.len() < 4{
+                return Err(DecodeError::WrongLength{ expected: 4, received: rest.len() }.into());
+            }
+            let length = u32::from_le_bytes([rest[0], rest[1], rest[2], rest[3]]) as usize;
+            rest = &rest[4..];
+            if rest.len() < length{
+                return Err(DecodeError::WrongLength{ expected: length, received: rest.len() }.into());
+            }
+            items.push(codec.decode(&rest[..length])?);
+            rest = &rest[length..];
+        }
+        let count = items.len();
+        for (loaded, item) in items.into_iter().enumerate(){
+            if self.append(item, timeout).is_err(){
+                return Err(SnapshotError::QueueFull{ loaded });
+            }
         }
+        Ok(count)
     }
 }
 impl<T> Drop for Queue<T> where T: 'static + Send{
+    /// Drops every remaining item then deletes the PROS queue
+    /// With the no_drop_clear feature the queue is deleted without receiving its items for faster teardown,
+    /// any items whose type needs Drop are leaked so only enable it if queued types are plain data
     fn drop(&mut self) {
+        #[cfg(not(feature = "no_drop_clear"))]
         self.clear();
         unsafe { queue_delete(self.queue) }
     }
 }
+impl<T> Debug for Queue<T> where T: 'static + Send{
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("Queue")
+            .field("type", &self.element_type_name())
+            .field("len", &self.len())
+            .field("max_len", &self.max_len())
+            .finish()
+    }
+}
 unsafe impl<T> Send for Queue<T> where T: 'static + Send{}
 unsafe impl<T> Sync for Queue<T> where T: 'static + Send{}
 impl<T> SendStream for Queue<T> where T: 'static + Send{
@@ -115,7 +603,7 @@ impl<T> SendTimeoutStream for Queue<T> where T: 'static + Send{
     fn send_timeout(&self, val: T, timeout: Duration, _uf: &impl UniversalFunctions) -> Option<T> {
         match self.append(val, Some(timeout)){
             Ok(_) => None,
-            Err(error) => Some(error)
+            Err(error) => Some(error.value)
         }
     }
 }
@@ -138,7 +626,174 @@ impl<T> ReceiveTimoutStream for Queue<T> where T: 'static + Send{
         self.queue_receive(Some(timeout))
     }
 }
+/// PROS can only wake a blocked receiver with an item, so receive_or_closed waits in slices of this and checks for close between them
+const CLOSE_CHECK_INTERVAL: Duration = Duration::from_millis(5);
+/// Closing does not stop sends, receive_or_closed returns items sent after close until the queue is empty
+impl<T> ClosableStream for Queue<T> where T: 'static + Send{
+    fn close(&self) {
+        self.closed.store(true, Ordering::SeqCst);
+    }
+
+    fn is_closed(&self) -> bool {
+        self.closed.load(Ordering::SeqCst)
+    }
+
+    /// A receiver blocked when the queue closes notices within CLOSE_CHECK_INTERVAL
+    fn receive_or_closed(&self) -> Result<T, Closed> {
+        loop {
+            let closed = self.is_closed();
+            if let Some(val) = self.queue_receive(Some(CLOSE_CHECK_INTERVAL)){
+                return Ok(val);
+            }
+            if closed{
+                return Err(Closed);
+            }
+        }
+    }
+}
+impl<T> BoundedStream for Queue<T> where T: 'static + Send{
+    fn capacity(&self) -> Option<usize> {
+        Some(self.max_len() as usize)
+    }
+}
 
+/// Moves up to max items from the front of from to the back of to, returns Ok with the amount moved
+/// Only items already in from are moved, waits up to timeout in total for space in to.
+/// If a send times out the item is put back at the front of from within what is left of timeout and no more items are moved,
+/// if another task filled from in the meantime the item is returned in Err with the amount moved before it
+pub fn transfer<T>(from: &Queue<T>, to: &Queue<T>, max: usize, timeout: Option<Duration>) -> Result<usize, (usize, T)> where T: 'static + Send{
+    let end_time = timeout.map(|timeout| system_time() + timeout);
+    let remaining = || end_time.map(|end_time| end_time.checked_sub(system_time()).unwrap_or_default());
+    let mut moved = 0;
+    while moved < max{
+        let item = match from.queue_receive(Some(Duration::new(0, 0))){
+            None => break,
+            Some(item) => item,
+        };
+        if let Err(error) = to.append(item, remaining()){
+            if let Err(error) = from.prepend(error.value, remaining()){
+                return Err((moved, error.value));
+            }
+            break;
+        }
+        moved += 1;
+    }
+    Ok(moved)
+}
+
+/// Appends a clone of item to each queue without blocking, returns the amount that accepted it
+/// Full queues are skipped, for continuous fan out use a BroadcastStream instead
+pub fn publish_all<T>(queues: &[&Queue<T>], item: T) -> usize where T: 'static + Send + Clone{
+    queues.iter().filter(|queue| queue.append(item.clone(), Some(Duration::new(0, 0))).is_ok()).count()
+}
+
+/// A single slot channel where each value replaces the last, for setpoints and other latest value wins data
+/// Setting never blocks. Plain data is overwritten in place atomically, values that need dropping
+/// are swapped by receiving and dropping the old value first so a concurrent get can briefly find it empty
+pub struct Mailbox<T> where T: 'static + Send{
+    queue: Queue<T>,
+}
+impl<T> Mailbox<T> where T: 'static + Send{
+    /// Panics if PROS could not allocate the queue
+    pub fn new() -> Self{
+        Self{ queue: Queue::new(1) }
+    }
+
+    /// Stores item, dropping any value that has not been got yet
+    pub fn set(&self, item: T){
+        if needs_drop::<T>(){
+            let mut item = item;
+            loop {
+                self.queue.clear();
+                match self.queue.append(item, Some(Duration::new(0, 0))){
+                    Ok(()) => return,
+                    Err(error) => item = error.value,
+                }
+            }
+        }
+        else{
+            unsafe { xQueueGenericSend(self.queue.queue, item_ptr(&item), 0, QUEUE_OVERWRITE) };
+            forget(item);
+        }
+    }
+
+    /// Takes the value, will wait up to timeout for one to be set
+    pub fn get(&self, timeout: Option<Duration>) -> Option<T>{
+        self.queue.queue_receive(timeout)
+    }
+
+    /// True if a value is waiting to be got
+    pub fn is_set(&self) -> bool{
+        !self.queue.is_empty()
+    }
+}
+impl<T> Default for Mailbox<T> where T: 'static + Send{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+impl<T> Debug for Mailbox<T> where T: 'static + Send{
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("Mailbox")
+            .field("queue", &self.queue)
+            .finish()
+    }
+}
+impl<T> SendStream for Mailbox<T> where T: 'static + Send{
+    type SData = T;
+
+    fn send(&self, val: T) {
+        self.set(val)
+    }
+}
+impl<T> SendTimeoutStream for Mailbox<T> where T: 'static + Send{
+    /// Never times out as set never blocks
+    fn send_timeout(&self, val: T, _timeout: Duration, _uf: &impl UniversalFunctions) -> Option<T> {
+        self.set(val);
+        None
+    }
+}
+impl<T> ReceiveStream for Mailbox<T> where T: 'static + Send{
+    type RData = T;
+
+    fn try_receive(&self) -> Option<T> {
+        self.get(Some(Duration::new(0, 0)))
+    }
+
+    fn receive(&self) -> T {
+        match self.get(None){
+            None => unreachable!("Mailbox returned none with no timeout"),
+            Some(val) => val,
+        }
+    }
+}
+impl<T> ReceiveTimoutStream for Mailbox<T> where T: 'static + Send{
+    fn receive_timeout(&self, timeout: Duration, _uf: &impl UniversalFunctions) -> Option<T> {
+        self.get(Some(timeout))
+    }
+}
+
+/// Falls back to a single item queue if max_length can not be allocated, panics if that fails too
+/// The fallback is reported to the alloc failure handler as Degraded so it is never silent
+fn try_new_or_single<T>(max_length: u32) -> Queue<T> where T: 'static + Send{
+    match Queue::try_new(max_length){
+        Ok(queue) => queue,
+        Err(_) => {
+            let queue = Queue::new(1);
+            alloc_failed(QueueCreateError::Degraded{ max_length, length: queue.max_len(), item_size: item_size::<T>() });
+            queue
+        },
+    }
+}
+
+/// The queue length that gives about buffer_bytes of storage for items of type T, counting zero sized items as one byte
+fn buffer_length<T>(buffer_bytes: usize) -> u32{
+    let length = buffer_bytes / size_of::<T>().max(1) + 1;
+    debug_assert!(length <= u32::MAX as usize, "Queue length {} for {} byte buffer does not fit in u32", length, buffer_bytes);
+    length as u32
+}
+
+/// Creates queues with about 1KB of buffer
 #[derive(Copy, Clone, Debug)]
 pub struct QueueCreator1k();
 impl<T> MessageStreamCreator<T> for QueueCreator1k where T: 'static + Send{
@@ -146,10 +801,11 @@ impl<T> MessageStreamCreator<T> for QueueCreator1k where T: 'static + Send{
     type Receiver = Arc<Queue<T>>;
 
     fn create_stream(&self) -> (Self::Sender, Self::Receiver) {
-        let queue = Arc::new(Queue::new(1 << 10 / size_of::<T>() + 1));
+        let queue = Arc::new(try_new_or_single(buffer_length::<T>(1 << 10)));
         (queue.clone(), queue)
     }
 }
+/// Creates queues with about 16KB of buffer
 #[derive(Copy, Clone, Debug)]
 pub struct QueueCreator16k();
 impl<T> MessageStreamCreator<T> for QueueCreator16k where T: 'static + Send{
@@ -157,18 +813,37 @@ impl<T> MessageStreamCreator<T> for QueueCreator16k where T: 'static + Send{
     type Receiver = Arc<Queue<T>>;
 
     fn create_stream(&self) -> (Self::Sender, Self::Receiver) {
-        let queue = Arc::new(Queue::new(1 << 14 / size_of::<T>() + 1));
+        let queue = Arc::new(try_new_or_single(buffer_length::<T>(1 << 14)));
+        (queue.clone(), queue)
+    }
+}
+/// Creates queues with about BYTES of buffer and room for at least one item
+#[derive(Copy, Clone, Debug)]
+pub struct QueueCreatorBytes<const BYTES: usize>();
+impl<T, const BYTES: usize> MessageStreamCreator<T> for QueueCreatorBytes<BYTES> where T: 'static + Send{
+    type Sender = Arc<Queue<T>>;
+    type Receiver = Arc<Queue<T>>;
+
+    fn create_stream(&self) -> (Self::Sender, Self::Receiver) {
+        let max_length = (BYTES / size_of::<T>().max(1)).max(1);
+        let queue = Arc::new(try_new_or_single(max_length as u32));
         (queue.clone(), queue)
     }
 }
 
 #[cfg(feature = "v5_test")]
 pub mod test{
-    use crate::sync::queue::Queue;
+    use crate::sync::queue::{Queue, Mailbox, SendErrorKind, QueueCreator1k, QueueCreator16k, QueueCreatorBytes, transfer, publish_all};
     use crate::test::{assert, TestItem, TestType};
+    use crate::task::Task;
+    use crate::system_time;
     use alloc::boxed::Box;
     use alloc::string::ToString;
+    use alloc::sync::Arc;
+    use alloc::vec::Vec;
+    use core::sync::atomic::{AtomicU32, AtomicU64, Ordering};
     use core::time::Duration;
+    use v5_traits::stream::{SendStream, ClosableStream, Closed, MessageStreamCreator};
 
     pub fn queue_test() -> TestItem{
         TestItem::new("queue_test".to_string(), TestType::Parallel(Box::new(|| {
@@ -177,7 +852,7 @@ pub mod test{
             assert(queue.max_len() == 100, format!("Queue max_length invalid! Should be: {}, is: {}", queue_length, queue.max_len()))?;
             assert(queue.len() == 0, format!("Queue length invalid at initialization! Should be: {}, is {}", 0, queue.len()))?;
             let insert_val = 1424;
-            if let Err(_) = queue.append(insert_val, Some(Duration::from_millis(100))){
+            if queue.append(insert_val, Some(Duration::from_millis(100))).is_err(){
                 return Err(format!("Could not insert {} into queue", insert_val));
             }
             assert(queue.len() == 1, format!("Queue length invalid! Should be: {}, is {}", 1, queue.len()))?;
@@ -185,6 +860,380 @@ pub mod test{
             assert(received.is_some(), format!("Could not pull from queue"))?;
             assert(received.unwrap() == insert_val, format!("Value from queue wrong! Should be: {}, is: {}", insert_val, received.unwrap()))?;
             assert(queue.len() == 0, format!("Queue length invalid after received! Should be: {}, is {}", 0, queue.len()))?;
+            let full = Queue::new(1);
+            assert(full.append(1, Some(Duration::new(0, 0))).is_ok(), "Could not insert into empty queue".to_string())?;
+            match full.append(2, Some(Duration::new(0, 0))){
+                Ok(()) => return Err("Append to full queue succeeded".to_string()),
+                Err(error) => assert(error.value == 2 && error.kind == SendErrorKind::Full, format!("Send error invalid! Should be: value 2 kind Full, is: {:?}", error))?,
+            }
+            Ok(())
+        }), Duration::from_secs(1)))
+    }
+
+    pub fn queue_mpmc_test() -> TestItem{
+        TestItem::new("queue_mpmc_test".to_string(), TestType::Parallel(Box::new(|| {
+            const PRODUCERS: u32 = 4;
+            const CONSUMERS: u32 = 4;
+            const PER_PRODUCER: u32 = 1000;
+            const TOTAL: u32 = PRODUCERS * PER_PRODUCER;
+
+            let queue = Arc::new(Queue::new(16));
+            let received_count = Arc::new(AtomicU32::new(0));
+            let received_sum = Arc::new(AtomicU64::new(0));
+            let received_square_sum = Arc::new(AtomicU64::new(0));
+            let finished_consumers = Arc::new(AtomicU32::new(0));
+
+            for producer in 0..PRODUCERS{
+                let queue = queue.clone();
+                Task::new(None, None, format!("queue_mpmc_producer_{}", producer), move |_|{
+                    for index in 0..PER_PRODUCER{
+                        queue.send(producer * PER_PRODUCER + index);
+                    }
+                }, ());
+            }
+            for consumer in 0..CONSUMERS{
+                let queue = queue.clone();
+                let received_count = received_count.clone();
+                let received_sum = received_sum.clone();
+                let received_square_sum = received_square_sum.clone();
+                let finished_consumers = finished_consumers.clone();
+                Task::new(None, None, format!("queue_mpmc_consumer_{}", consumer), move |_|{
+                    while received_count.load(Ordering::SeqCst) < TOTAL{
+                        if let Some(val) = queue.queue_receive(Some(Duration::from_millis(10))){
+                            received_sum.fetch_add(val as u64, Ordering::SeqCst);
+                            received_square_sum.fetch_add(val as u64 * val as u64, Ordering::SeqCst);
+                            received_count.fetch_add(1, Ordering::SeqCst);
+                        }
+                    }
+                    finished_consumers.fetch_add(1, Ordering::SeqCst);
+                }, ());
+            }
+
+            let end_time = system_time() + Duration::from_secs(8);
+            while finished_consumers.load(Ordering::SeqCst) < CONSUMERS && system_time() < end_time{
+                Task::delay(Duration::from_millis(10));
+            }
+
+            let expected_sum: u64 = (0..TOTAL as u64).sum();
+            let expected_square_sum: u64 = (0..TOTAL as u64).map(|val| val * val).sum();
+            assert(finished_consumers.load(Ordering::SeqCst) == CONSUMERS, format!("Consumers did not finish, received {} of {}", received_count.load(Ordering::SeqCst), TOTAL))?;
+            assert(received_count.load(Ordering::SeqCst) == TOTAL, format!("Received count invalid! Should be: {}, is: {}", TOTAL, received_count.load(Ordering::SeqCst)))?;
+            assert(received_sum.load(Ordering::SeqCst) == expected_sum, format!("Received sum invalid! Should be: {}, is: {}", expected_sum, received_sum.load(Ordering::SeqCst)))?;
+            assert(received_square_sum.load(Ordering::SeqCst) == expected_square_sum, format!("Received square sum invalid! Should be: {}, is: {}", expected_square_sum, received_square_sum.load(Ordering::SeqCst)))?;
+            assert(queue.is_empty(), format!("Queue length invalid after test! Should be: {}, is {}", 0, queue.len()))?;
+            Ok(())
+        }), Duration::from_secs(10)))
+    }
+
+    pub fn queue_peek_n_test() -> TestItem{
+        TestItem::new("queue_peek_n_test".to_string(), TestType::Parallel(Box::new(|| {
+            let queue = Queue::new(8);
+            assert(queue.peek_n(4, Some(Duration::from_millis(10))).is_empty(), "Peek of empty queue returned items".to_string())?;
+            for val in 0..5{
+                queue.send(val);
+            }
+            let peeked = queue.peek_n(3, None);
+            assert(peeked == vec![0, 1, 2], format!("Peeked values invalid! Should be: {:?}, is: {:?}", vec![0, 1, 2], peeked))?;
+            let peeked = queue.peek_n(10, None);
+            assert(peeked == vec![0, 1, 2, 3, 4], format!("Peeked values invalid! Should be: {:?}, is: {:?}", vec![0, 1, 2, 3, 4], peeked))?;
+            for val in 0..5{
+                let received = queue.queue_receive(Some(Duration::from_millis(10)));
+                assert(received == Some(val), format!("Value from queue wrong after peek! Should be: {:?}, is: {:?}", Some(val), received))?;
+            }
+            Ok(())
+        }), Duration::from_secs(1)))
+    }
+
+    pub fn queue_from_slice_test() -> TestItem{
+        TestItem::new("queue_from_slice_test".to_string(), TestType::Parallel(Box::new(|| {
+            assert(Queue::from_slice(&[1, 2, 3], 2).is_err(), "Queue created from slice longer than max_length".to_string())?;
+            let queue = match Queue::from_slice(&[1, 2, 3], 4){
+                Ok(queue) => queue,
+                Err(error) => return Err(format!("Could not create queue from slice: {:?}", error)),
+            };
+            assert(queue.len() == 3, format!("Queue length invalid! Should be: {}, is {}", 3, queue.len()))?;
+            assert(queue.max_len() == 4, format!("Queue max_length invalid! Should be: {}, is: {}", 4, queue.max_len()))?;
+            let peeked = queue.peek_n(3, None);
+            assert(peeked == vec![1, 2, 3], format!("Queue values invalid! Should be: {:?}, is: {:?}", vec![1, 2, 3], peeked))?;
+            Ok(())
+        }), Duration::from_secs(1)))
+    }
+
+    pub fn queue_peek_guard_test() -> TestItem{
+        TestItem::new("queue_peek_guard_test".to_string(), TestType::Parallel(Box::new(|| {
+            let queue = Queue::new(4);
+            queue.send("first".to_string());
+            queue.send("second".to_string());
+            match queue.peek_guard(Some(Duration::from_millis(10))){
+                None => return Err("Could not peek from queue".to_string()),
+                Some(guard) => assert(*guard == "first", format!("Peeked value wrong! Should be: {:?}, is: {:?}", "first", *guard))?,
+            }
+            assert(queue.len() == 2, format!("Queue length invalid after dropped guard! Should be: {}, is {}", 2, queue.len()))?;
+            let consumed = queue.peek_guard(Some(Duration::from_millis(10))).map(|guard| guard.consume());
+            assert(consumed.as_deref() == Some("first"), format!("Consumed value wrong! Should be: {:?}, is: {:?}", Some("first"), consumed))?;
+            let received = queue.queue_receive(Some(Duration::from_millis(10)));
+            assert(received.as_deref() == Some("second"), format!("Value from queue wrong after consume! Should be: {:?}, is: {:?}", Some("second"), received))?;
+            Ok(())
+        }), Duration::from_secs(1)))
+    }
+
+    pub fn queue_transfer_test() -> TestItem{
+        TestItem::new("queue_transfer_test".to_string(), TestType::Parallel(Box::new(|| {
+            let from = Queue::new(8);
+            let to = Queue::new(3);
+            for val in 0..5{
+                from.send(val);
+            }
+            to.send(100);
+            let moved = transfer(&from, &to, 4, Some(Duration::from_millis(10))).ok();
+            assert(moved == Some(2), format!("Moved count invalid! Should be: {:?}, is: {:?}", Some(2), moved))?;
+            let to_values = to.peek_n(3, None);
+            assert(to_values == vec![100, 0, 1], format!("Destination values invalid! Should be: {:?}, is: {:?}", vec![100, 0, 1], to_values))?;
+            let from_values = from.peek_n(8, None);
+            assert(from_values == vec![2, 3, 4], format!("Source values invalid! Should be: {:?}, is: {:?}", vec![2, 3, 4], from_values))?;
+            Ok(())
+        }), Duration::from_secs(1)))
+    }
+
+    pub fn queue_send_with_progress_test() -> TestItem{
+        TestItem::new("queue_send_with_progress_test".to_string(), TestType::Parallel(Box::new(|| {
+            let queue = Queue::new(4);
+            let mut progress = Vec::new();
+            queue.send_with_progress(vec![1, 2, 3], |count| progress.push(count));
+            assert(progress == vec![1, 2, 3], format!("Progress invalid! Should be: {:?}, is: {:?}", vec![1, 2, 3], progress))?;
+            let values = queue.peek_n(4, None);
+            assert(values == vec![1, 2, 3], format!("Queue values invalid! Should be: {:?}, is: {:?}", vec![1, 2, 3], values))?;
+            Ok(())
+        }), Duration::from_secs(1)))
+    }
+
+    pub fn queue_is_full_test() -> TestItem{
+        TestItem::new("queue_is_full_test".to_string(), TestType::Parallel(Box::new(|| {
+            let queue = Queue::new(2);
+            assert(queue.is_empty() && !queue.is_full(), format!("New queue state invalid! Should be: (true, false), is: ({}, {})", queue.is_empty(), queue.is_full()))?;
+            queue.send(1);
+            assert(!queue.is_empty() && !queue.is_full(), format!("Partial queue state invalid! Should be: (false, false), is: ({}, {})", queue.is_empty(), queue.is_full()))?;
+            queue.send(2);
+            assert(!queue.is_empty() && queue.is_full(), format!("Full queue state invalid! Should be: (false, true), is: ({}, {})", queue.is_empty(), queue.is_full()))?;
+            queue.queue_receive(None);
+            assert(!queue.is_empty() && !queue.is_full(), format!("Received queue state invalid! Should be: (false, false), is: ({}, {})", queue.is_empty(), queue.is_full()))?;
+            queue.queue_receive(None);
+            assert(queue.is_empty() && !queue.is_full(), format!("Emptied queue state invalid! Should be: (true, false), is: ({}, {})", queue.is_empty(), queue.is_full()))?;
+            Ok(())
+        }), Duration::from_secs(1)))
+    }
+
+    pub fn queue_reset_test() -> TestItem{
+        TestItem::new("queue_reset_test".to_string(), TestType::Parallel(Box::new(|| {
+            let queue = Queue::from_slice(&[1, 2, 3], 4).map_err(|error| format!("Could not create queue from slice: {:?}", error))?;
+            queue.reset();
+            assert(queue.is_empty(), format!("Queue length invalid after reset! Should be: {}, is: {}", 0, queue.len()))?;
+            queue.send(4);
+            let received = queue.queue_receive(Some(Duration::from_millis(100)));
+            assert(received == Some(4), format!("Value after reset invalid! Should be: {:?}, is: {:?}", Some(4), received))?;
+            Ok(())
+        }), Duration::from_secs(1)))
+    }
+
+    pub fn queue_append_slice_test() -> TestItem{
+        TestItem::new("queue_append_slice_test".to_string(), TestType::Parallel(Box::new(|| {
+            let queue = Queue::new(4);
+            let appended = queue.append_slice(&[0, 1, 2, 3, 4, 5, 6, 7, 8, 9], Some(Duration::from_millis(20)));
+            assert(appended == 4, format!("Appended count invalid! Should be: {}, is: {}", 4, appended))?;
+            let values = queue.peek_n(4, None);
+            assert(values == vec![0, 1, 2, 3], format!("Queue values invalid! Should be: {:?}, is: {:?}", vec![0, 1, 2, 3], values))?;
+            Ok(())
+        }), Duration::from_secs(1)))
+    }
+
+    pub fn queue_waiting_test() -> TestItem{
+        TestItem::new("queue_waiting_test".to_string(), TestType::Parallel(Box::new(|| {
+            let queue = Arc::new(Queue::new(1));
+            let receiver = queue.clone();
+            Task::new(None, None, "queue_waiting_receiver", move |_|{
+                receiver.queue_receive(None);
+            }, ());
+            Task::delay(Duration::from_millis(20));
+            assert(queue.waiting_to_receive() == 1, format!("Waiting to receive invalid! Should be: 1, is: {}", queue.waiting_to_receive()))?;
+            queue.send(1);
+            Task::delay(Duration::from_millis(20));
+            assert(queue.waiting_to_receive() == 0, format!("Waiting to receive invalid after send! Should be: 0, is: {}", queue.waiting_to_receive()))?;
+
+            queue.send(2);
+            let sender = queue.clone();
+            Task::new(None, None, "queue_waiting_sender", move |_|{
+                sender.send(3);
+            }, ());
+            Task::delay(Duration::from_millis(20));
+            assert(queue.waiting_to_send() == 1, format!("Waiting to send invalid! Should be: 1, is: {}", queue.waiting_to_send()))?;
+            queue.clear();
+            Task::delay(Duration::from_millis(20));
+            assert(queue.waiting_to_send() == 0, format!("Waiting to send invalid after receive! Should be: 0, is: {}", queue.waiting_to_send()))?;
+            Ok(())
+        }), Duration::from_secs(1)))
+    }
+
+    pub fn queue_peek_cloned_test() -> TestItem{
+        TestItem::new("queue_peek_cloned_test".to_string(), TestType::Parallel(Box::new(|| {
+            let queue = Queue::new(2);
+            queue.send("command".to_string());
+            let first = unsafe { queue.peek_cloned(Some(Duration::new(0, 0))) };
+            let second = unsafe { queue.peek_cloned(Some(Duration::new(0, 0))) };
+            assert(first.as_deref() == Some("command") && second == first, format!("Peeked items invalid! Should be: Some(\"command\") twice, are: {:?} and {:?}", first, second))?;
+            assert(queue.len() == 1, format!("Queue length invalid after peek! Should be: 1, is: {}", queue.len()))?;
+            drop(first);
+            drop(second);
+            let received = queue.queue_receive(Some(Duration::new(0, 0)));
+            assert(received.as_deref() == Some("command"), format!("Received item invalid! Should be: Some(\"command\"), is: {:?}", received))?;
+            Ok(())
+        }), Duration::from_secs(1)))
+    }
+
+    pub fn queue_close_test() -> TestItem{
+        TestItem::new("queue_close_test".to_string(), TestType::Parallel(Box::new(|| {
+            let queue = Arc::new(Queue::new(2));
+            queue.send(1);
+            let closer = queue.clone();
+            Task::new(None, None, "queue_close_closer", move |_|{
+                Task::delay(Duration::from_millis(50));
+                closer.close();
+            }, ());
+            let first = queue.receive_or_closed();
+            assert(first == Ok(1), format!("First receive invalid! Should be: Ok(1), is: {:?}", first))?;
+            let start = system_time();
+            let second = queue.receive_or_closed();
+            let waited = system_time() - start;
+            assert(second == Err(Closed), format!("Blocked receive invalid! Should be: Err(Closed), is: {:?}", second))?;
+            assert(waited >= Duration::from_millis(30), format!("Receive did not block until close! Waited: {:?}", waited))?;
+            assert(queue.receive_or_closed() == Err(Closed), "Receive after close did not report closed!".to_string())?;
+            Ok(())
+        }), Duration::from_secs(1)))
+    }
+
+    pub fn queue_zero_sized_test() -> TestItem{
+        TestItem::new("queue_zero_sized_test".to_string(), TestType::Parallel(Box::new(|| {
+            let queue = Queue::<()>::new(3);
+            for i in 0..3{
+                assert(queue.append((), Some(Duration::new(0, 0))).is_ok(), format!("Append {} failed!", i))?;
+            }
+            assert(queue.is_full(), format!("Queue not full! Should be: 3, is: {}", queue.len()))?;
+            for i in 0..3{
+                assert(queue.queue_receive(Some(Duration::new(0, 0))) == Some(()), format!("Receive {} failed!", i))?;
+            }
+            assert(queue.queue_receive(Some(Duration::new(0, 0))).is_none(), "Received from empty queue!".to_string())?;
+            Ok(())
+        }), Duration::from_secs(1)))
+    }
+
+    pub fn queue_drain_test() -> TestItem{
+        TestItem::new("queue_drain_test".to_string(), TestType::Parallel(Box::new(|| {
+            let queue = Queue::new(5);
+            for i in 0..5{
+                assert(queue.append(i.to_string(), Some(Duration::new(0, 0))).is_ok(), format!("Append {} failed!", i))?;
+            }
+            let mut drain = queue.drain();
+            let taken = [drain.next(), drain.next()];
+            assert(taken == [Some("0".to_string()), Some("1".to_string())], format!("Drained items invalid! Should be: [Some(\"0\"), Some(\"1\")], is: {:?}", taken))?;
+            assert(queue.len() == 3, format!("Queue length invalid! Should be: 3, is: {}", queue.len()))?;
+            let rest: Vec<_> = queue.drain().collect();
+            assert(rest == ["2", "3", "4"], format!("Remaining items invalid! Should be: [\"2\", \"3\", \"4\"], is: {:?}", rest))?;
+            assert(queue.is_empty(), "Queue not empty after drain!".to_string())?;
+            Ok(())
+        }), Duration::from_secs(1)))
+    }
+
+    pub fn mailbox_test() -> TestItem{
+        TestItem::new("mailbox_test".to_string(), TestType::Parallel(Box::new(|| {
+            let mailbox = Mailbox::new();
+            mailbox.set(1);
+            mailbox.set(2);
+            mailbox.set(3);
+            let received = mailbox.get(Some(Duration::from_millis(100)));
+            assert(received == Some(3), format!("Mailbox value invalid! Should be: {:?}, is: {:?}", Some(3), received))?;
+            assert(!mailbox.is_set(), "Mailbox still set after get!".to_string())?;
+            let mailbox = Mailbox::new();
+            mailbox.set("first".to_string());
+            mailbox.set("second".to_string());
+            mailbox.set("third".to_string());
+            let received = mailbox.get(Some(Duration::from_millis(100)));
+            assert(received.as_deref() == Some("third"), format!("Mailbox string invalid! Should be: {:?}, is: {:?}", Some("third"), received))?;
+            Ok(())
+        }), Duration::from_secs(1)))
+    }
+
+    pub fn queue_creator_test() -> TestItem{
+        TestItem::new("queue_creator_test".to_string(), TestType::Parallel(Box::new(|| {
+            let (small, _) = MessageStreamCreator::<u8>::create_stream(&QueueCreator1k());
+            assert(small.max_len() == 1025, format!("1k u8 queue length invalid! Should be: {}, is: {}", 1025, small.max_len()))?;
+            let (word, _) = MessageStreamCreator::<u32>::create_stream(&QueueCreator1k());
+            assert(word.max_len() == 257, format!("1k u32 queue length invalid! Should be: {}, is: {}", 257, word.max_len()))?;
+            let (large, _) = MessageStreamCreator::<[u8; 100]>::create_stream(&QueueCreator1k());
+            assert(large.max_len() == 11, format!("1k [u8; 100] queue length invalid! Should be: {}, is: {}", 11, large.max_len()))?;
+            let (word, _) = MessageStreamCreator::<u32>::create_stream(&QueueCreator16k());
+            assert(word.max_len() == 4097, format!("16k u32 queue length invalid! Should be: {}, is: {}", 4097, word.max_len()))?;
+            let (word, _) = MessageStreamCreator::<u32>::create_stream(&QueueCreatorBytes::<4096>());
+            assert(word.max_len() == 1024, format!("4096 byte u32 queue length invalid! Should be: {}, is: {}", 1024, word.max_len()))?;
+            let (empty, _) = MessageStreamCreator::<u32>::create_stream(&QueueCreatorBytes::<0>());
+            assert(empty.max_len() == 1, format!("0 byte queue length invalid! Should be: {}, is: {}", 1, empty.max_len()))?;
+            Ok(())
+        }), Duration::from_secs(1)))
+    }
+
+    pub fn queue_resized_test() -> TestItem{
+        TestItem::new("queue_resized_test".to_string(), TestType::Parallel(Box::new(|| {
+            let queue = Queue::from_slice(&[1, 2, 3], 3).map_err(|error| format!("Could not create queue from slice: {:?}", error))?;
+            let (grown, dropped) = queue.resized(8);
+            assert(dropped == 0 && grown.max_len() == 8, format!("Grown queue invalid! Should be: (0, 8), is: ({}, {})", dropped, grown.max_len()))?;
+            let (shrunk, dropped) = grown.resized(2);
+            assert(dropped == 1, format!("Dropped count invalid! Should be: {}, is: {}", 1, dropped))?;
+            let values = shrunk.peek_n(2, None);
+            assert(values == vec![1, 2], format!("Queue values invalid! Should be: {:?}, is: {:?}", vec![1, 2], values))?;
+            Ok(())
+        }), Duration::from_secs(1)))
+    }
+
+    pub fn queue_fair_test() -> TestItem{
+        TestItem::new("queue_fair_test".to_string(), TestType::Parallel(Box::new(|| {
+            const ITEMS: u32 = 20;
+
+            let queue = Arc::new(Queue::<Option<u32>>::new(1));
+            let high_count = Arc::new(AtomicU32::new(0));
+            let low_count = Arc::new(AtomicU32::new(0));
+            for (priority, count) in [(9, high_count.clone()), (7, low_count.clone())]{
+                let queue = queue.clone();
+                Task::new(Some(priority), None, format!("queue_fair_consumer_{}", priority), move |_|{
+                    while queue.receive_fair().is_some(){
+                        count.fetch_add(1, Ordering::SeqCst);
+                    }
+                }, ());
+            }
+            Task::delay(Duration::from_millis(10));
+            for item in 0..ITEMS{
+                queue.send(Some(item));
+                Task::delay(Duration::from_millis(5));
+            }
+            queue.send(None);
+            queue.send(None);
+            Task::delay(Duration::from_millis(10));
+
+            let high = high_count.load(Ordering::SeqCst);
+            let low = low_count.load(Ordering::SeqCst);
+            assert(high + low == ITEMS, format!("Received count invalid! Should be: {}, is: {}", ITEMS, high + low))?;
+            assert(high.abs_diff(low) <= 1, format!("Consumers not served fairly! High priority: {}, low priority: {}", high, low))?;
+            Ok(())
+        }), Duration::from_secs(1)))
+    }
+
+    pub fn queue_publish_all_test() -> TestItem{
+        TestItem::new("queue_publish_all_test".to_string(), TestType::Parallel(Box::new(|| {
+            let first = Queue::new(2);
+            let full = Queue::new(1);
+            let last = Queue::new(2);
+            full.send(0);
+            let accepted = publish_all(&[&first, &full, &last], 7);
+            assert(accepted == 2, format!("Accepted count invalid! Should be: {}, is: {}", 2, accepted))?;
+            let values = (first.peek_n(2, None), full.peek_n(2, None), last.peek_n(2, None));
+            assert(values == (vec![7], vec![0], vec![7]), format!("Queue values invalid! Should be: {:?}, is: {:?}", (vec![7], vec![0], vec![7]), values))?;
             Ok(())
         }), Duration::from_secs(1)))
     }
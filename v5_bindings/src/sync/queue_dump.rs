@@ -0,0 +1,62 @@
+use alloc::sync::{Arc, Weak};
+use alloc::vec::Vec;
+use core::sync::atomic::{AtomicBool, Ordering};
+
+use crate::console_println;
+use crate::sync::queue::Queue;
+use crate::task::Task;
+
+/// Guards REGISTERED_QUEUES, an atomic flag rather than a PROS mutex so the list can be a static without runtime initialization
+static LOCKED: AtomicBool = AtomicBool::new(false);
+static mut REGISTERED_QUEUES: Vec<Weak<dyn QueueStatus>> = Vec::new();
+
+/// The parts of a queue printed when dumping, implemented for every Queue<T>
+pub trait QueueStatus: Send + Sync{
+    fn len(&self) -> u32;
+    fn is_empty(&self) -> bool{
+        self.len() == 0
+    }
+    fn max_len(&self) -> u32;
+    fn element_type_name(&self) -> &'static str;
+}
+impl<T> QueueStatus for Queue<T> where T: 'static + Send{
+    fn len(&self) -> u32 {
+        Queue::len(self)
+    }
+
+    fn max_len(&self) -> u32 {
+        Queue::max_len(self)
+    }
+
+    fn element_type_name(&self) -> &'static str {
+        Queue::element_type_name(self)
+    }
+}
+
+/// Adds queue to the queues printed by the panic handler, it is only held weakly so dropped queues disappear from the list
+pub fn register_queue<T>(queue: &Arc<Queue<T>>) where T: 'static + Send{
+    let queue: Arc<dyn QueueStatus> = queue.clone();
+    let queue = Arc::downgrade(&queue);
+    while LOCKED.swap(true, Ordering::Acquire){
+        Task::delay_yield();
+    }
+    let queues = unsafe { &mut *core::ptr::addr_of_mut!(REGISTERED_QUEUES) };
+    queues.retain(|queue| queue.strong_count() > 0);
+    queues.push(queue);
+    LOCKED.store(false, Ordering::Release);
+}
+
+/// Prints the length, max length and element type of every registered queue still alive to the console
+/// Called by the panic handler, if the panic happened while registering the list is not printed rather than deadlocking
+pub fn dump_queues(){
+    if LOCKED.swap(true, Ordering::Acquire){
+        console_println("Queue list busy, not dumping queues");
+        return;
+    }
+    let queues = unsafe { &*core::ptr::addr_of!(REGISTERED_QUEUES) }.iter().filter_map(Weak::upgrade).collect::<Vec<_>>();
+    console_println(format!("Registered queues: {}", queues.len()));
+    for queue in queues{
+        console_println(format!("  Queue<{}>: {}/{}", queue.element_type_name(), queue.len(), queue.max_len()));
+    }
+    LOCKED.store(false, Ordering::Release);
+}
@@ -1,5 +1,6 @@
 use alloc::boxed::Box;
 use alloc::string::String;
+use alloc::sync::Arc;
 use core::time::Duration;
 
 use cstr_core::CStr;
@@ -8,6 +9,7 @@ use cty::*;
 use crate::{NotifyAction, State};
 use crate::raw::pros::rtos::*;
 use crate::raw::str_to_char_ptr;
+use crate::sync::queue::Queue;
 use v5_traits::task::{TaskFunction, TaskRunner};
 use core::fmt::Display;
 
@@ -36,6 +38,20 @@ impl Task{
         }}
     }
 
+    /// Runs function on a new task with default priority and stack, its return value can be received with join
+    pub fn spawn<F, R>(name: impl Display, function: F) -> JoinHandle<R>
+        where F: 'static + FnOnce() -> R + Send,
+              R: 'static + Send{
+        let result = Arc::new(Queue::new(1));
+        let task_result = result.clone();
+        let task = Self::new(None, None, name, move |_|{
+            if task_result.append(function(), None).is_err(){
+                unreachable!("Queue append failed with no timeout");
+            }
+        }, ());
+        JoinHandle{ task, result }
+    }
+
     pub fn delay(duration: Duration){
         unsafe {task_delay(duration.as_millis() as uint32_t)}
     }
@@ -86,6 +102,29 @@ impl Task{
     }
 }
 impl !Send for Task{}
+
+/// The result of a task made with Task::spawn
+/// The task posts its return value to a single item queue as it exits
+pub struct JoinHandle<R> where R: 'static + Send{
+    task: Task,
+    result: Arc<Queue<R>>,
+}
+impl<R> JoinHandle<R> where R: 'static + Send{
+    /// Waits up to timeout for the task to return
+    /// Returns None if timeout reached or the result was already taken by an earlier join
+    pub fn join(&self, timeout: Option<Duration>) -> Option<R>{
+        self.result.queue_receive(timeout)
+    }
+
+    /// True once the task has returned and its result has not been joined
+    pub fn is_finished(&self) -> bool{
+        !self.result.is_empty()
+    }
+
+    pub fn task(&self) -> &Task{
+        &self.task
+    }
+}
 struct TaskArg<F, T>
     where F: TaskFunction<T, ()>,
           T: 'static + Send{
@@ -111,3 +150,26 @@ impl<T> TaskRunner<T, ()> for ProsTaskRunner where T: 'static + Send{
         Task::new(None, None, name, task, task_argument)
     }
 }
+
+#[cfg(feature = "v5_test")]
+pub mod test{
+    use crate::task::Task;
+    use crate::test::{assert, TestItem, TestType};
+    use alloc::boxed::Box;
+    use alloc::string::ToString;
+    use core::time::Duration;
+
+    pub fn task_spawn_test() -> TestItem{
+        TestItem::new("task_spawn_test".to_string(), TestType::Parallel(Box::new(|| {
+            let handle = Task::spawn("task_spawn_test", ||{
+                Task::delay(Duration::from_millis(20));
+                (1..=10).sum::<u32>()
+            });
+            assert(handle.join(Some(Duration::from_millis(1))).is_none(), "Joined before task returned!".to_string())?;
+            let result = handle.join(Some(Duration::from_millis(500)));
+            assert(result == Some(55), format!("Task result invalid! Should be: {:?}, is: {:?}", Some(55), result))?;
+            assert(handle.join(Some(Duration::from_millis(1))).is_none(), "Result joined twice!".to_string())?;
+            Ok(())
+        }), Duration::from_secs(1)))
+    }
+}
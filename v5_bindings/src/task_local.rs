@@ -0,0 +1,102 @@
+use alloc::boxed::Box;
+use core::marker::PhantomData;
+use core::ptr::null_mut;
+use cty::c_void;
+
+use crate::raw::pros::rtos::{pvTaskGetThreadLocalStoragePointer, vTaskSetThreadLocalStoragePointer};
+
+/// Its address marks a slot whose value is currently borrowed by with, no box can be allocated there
+static BORROWED_MARKER: u8 = 0;
+
+fn borrowed() -> *mut c_void{
+    &BORROWED_MARKER as *const u8 as *mut c_void
+}
+
+/// A value that each task gets its own instance of, stored in a PROS task local storage slot
+/// The value is created with init the first time a task accesses it
+/// Values are boxed and the slot only holds the pointer, so a task deleted while holding a value leaks it,
+/// call take before a task finishes to drop its value
+/// PROS has 5 slots per task and uses slot 0 for its deletion notifications, each TaskLocal needs its own slot from 1 to 4
+pub struct TaskLocal<T> where T: 'static{
+    index: i32,
+    init: fn() -> T,
+    phantom_t: PhantomData<T>,
+}
+impl<T> TaskLocal<T> where T: 'static{
+    /// Panics if index is not from 1 to 4
+    /// # Safety
+    /// No other TaskLocal may use the slot index, with would read the other's value as a T
+    pub const unsafe fn new(index: i32, init: fn() -> T) -> Self{
+        assert!(matches!(index, 1..=4), "TaskLocal index must be from 1 to 4");
+        Self{ index, init, phantom_t: PhantomData }
+    }
+
+    /// Calls f with the current task's value, creating it first if needed
+    /// Panics if called again for the same TaskLocal from inside f
+    pub fn with<R>(&self, f: impl FnOnce(&mut T) -> R) -> R{
+        let mut value = match unsafe { pvTaskGetThreadLocalStoragePointer(null_mut(), self.index) }{
+            pointer if pointer == borrowed() => panic!("TaskLocal in slot {} already borrowed by this task", self.index),
+            pointer if pointer.is_null() => Box::new((self.init)()),
+            pointer => unsafe { Box::from_raw(pointer as *mut T) },
+        };
+        unsafe { vTaskSetThreadLocalStoragePointer(null_mut(), self.index, borrowed()) };
+        let out = f(&mut value);
+        unsafe { vTaskSetThreadLocalStoragePointer(null_mut(), self.index, Box::into_raw(value) as *mut c_void) };
+        out
+    }
+
+    /// Removes the current task's value, the next access creates a new one
+    pub fn take(&self) -> Option<T>{
+        let pointer = unsafe { pvTaskGetThreadLocalStoragePointer(null_mut(), self.index) };
+        if pointer.is_null(){
+            return None;
+        }
+        if pointer == borrowed(){
+            panic!("TaskLocal in slot {} taken while borrowed", self.index);
+        }
+        unsafe { vTaskSetThreadLocalStoragePointer(null_mut(), self.index, null_mut()) };
+        Some(*unsafe { Box::from_raw(pointer as *mut T) })
+    }
+}
+/// This ensures that this is sync because each task only ever accesses its own T
+unsafe impl<T> Sync for TaskLocal<T> where T: 'static{}
+
+#[cfg(feature = "v5_test")]
+pub mod test{
+    use crate::task_local::TaskLocal;
+    use crate::test::{assert, TestItem, TestType};
+    use crate::task::Task;
+    use alloc::boxed::Box;
+    use alloc::string::ToString;
+    use alloc::sync::Arc;
+    use core::sync::atomic::{AtomicU32, Ordering};
+    use core::time::Duration;
+
+    static COUNTER: TaskLocal<u32> = unsafe { TaskLocal::new(1, || 0) };
+    static UNIT: TaskLocal<()> = unsafe { TaskLocal::new(2, || ()) };
+
+    pub fn task_local_test() -> TestItem{
+        TestItem::new("task_local_test".to_string(), TestType::Parallel(Box::new(|| {
+            let other_count = Arc::new(AtomicU32::new(0));
+            let task_other_count = other_count.clone();
+            Task::new(None, None, "task_local_other", move |_|{
+                for _ in 0..5{
+                    COUNTER.with(|counter| *counter += 1);
+                }
+                task_other_count.store(COUNTER.take().unwrap_or_default(), Ordering::SeqCst);
+            }, ());
+            for _ in 0..3{
+                COUNTER.with(|counter| *counter += 1);
+            }
+            Task::delay(Duration::from_millis(100));
+            let count = COUNTER.take();
+            assert(count == Some(3), format!("Task local count invalid! Should be: {:?}, is: {:?}", Some(3), count))?;
+            assert(other_count.load(Ordering::SeqCst) == 5, format!("Other task local count invalid! Should be: {}, is: {}", 5, other_count.load(Ordering::SeqCst)))?;
+            assert(COUNTER.take().is_none(), "Task local not removed by take".to_string())?;
+            UNIT.with(|_| ());
+            UNIT.with(|_| ());
+            assert(UNIT.take().is_some(), "Zero sized task local lost".to_string())?;
+            Ok(())
+        }), Duration::from_secs(1)))
+    }
+}
@@ -3,11 +3,11 @@
 use ansi_rgb::{Foreground, orange};
 
 use v5_bindings::console_println;
-use v5_bindings::robot::port::Port;
+use v5_bindings::robot::peripherals::Peripherals;
 use v5_bindings::robot::Robot;
 
 #[no_mangle]
-extern "Rust" fn rust_user_initialize(_ports: [Port; 21]) -> Robot {
+extern "Rust" fn rust_user_initialize(_peripherals: Peripherals) -> Robot {
     console_println(&"Hello from rust! rust_user_initialize".fg(orange()));
     Robot::new()
 }
@@ -42,7 +42,10 @@ mod test {
     use v5_bindings::robot::Robot;
     use v5_bindings::test::TestItem;
     use v5_bindings::sync::lock::test::{mutex_test, rw_lock_test};
-    use v5_bindings::sync::queue::test::queue_test;
+    use v5_bindings::sync::queue::test::{queue_test, queue_mpmc_test, queue_peek_n_test, queue_from_slice_test, queue_peek_guard_test, queue_transfer_test, queue_publish_all_test, queue_resized_test, queue_fair_test, queue_send_with_progress_test, queue_creator_test, queue_is_full_test, queue_reset_test, queue_append_slice_test, mailbox_test, queue_drain_test, queue_zero_sized_test, queue_close_test, queue_peek_cloned_test, queue_waiting_test};
+    use v5_bindings::task_local::test::task_local_test;
+    use v5_bindings::task::test::task_spawn_test;
+    use v5_bindings::robot::port_registry::test::port_registry_test;
 
     // #[allow(improper_ctypes_definitions)]
     #[no_mangle]
@@ -52,6 +55,28 @@ mod test {
         out.push(mutex_test());
         out.push(rw_lock_test());
         out.push(queue_test());
+        out.push(queue_mpmc_test());
+        out.push(queue_peek_n_test());
+        out.push(queue_from_slice_test());
+        out.push(queue_peek_guard_test());
+        out.push(queue_transfer_test());
+        out.push(queue_publish_all_test());
+        out.push(queue_resized_test());
+        out.push(queue_fair_test());
+        out.push(queue_send_with_progress_test());
+        out.push(queue_creator_test());
+        out.push(queue_is_full_test());
+        out.push(queue_reset_test());
+        out.push(queue_append_slice_test());
+        out.push(mailbox_test());
+        out.push(queue_drain_test());
+        out.push(queue_zero_sized_test());
+        out.push(queue_close_test());
+        out.push(queue_peek_cloned_test());
+        out.push(queue_waiting_test());
+        out.push(task_local_test());
+        out.push(task_spawn_test());
+        out.push(port_registry_test());
 
         out
     }
@@ -0,0 +1,93 @@
+use v5_traits::stream::{ReceiveStream, ReceiveTimoutStream};
+use v5_traits::mutex::Mutex;
+use v5_traits::UniversalFunctions;
+use core::time::Duration;
+
+/// Polls a stream with try_receive, sleeping between empty polls for a delay that doubles from initial_backoff up to max_backoff
+/// The delay resets to initial_backoff whenever an item arrives so bursts are received quickly while idle streams use little cpu.
+/// For streams without a useful blocking receive, an item can wait up to max_backoff before it is received
+#[derive(Debug)]
+pub struct BackoffPollStream<UF, R, M> where UF: UniversalFunctions, R: ReceiveStream, M: Mutex<Inner=Duration>{
+    uf: UF,
+    stream: R,
+    initial_backoff: Duration,
+    max_backoff: Duration,
+    backoff: M,
+}
+impl<UF, R, M> BackoffPollStream<UF, R, M> where UF: UniversalFunctions, R: ReceiveStream, M: Mutex<Inner=Duration>{
+    pub fn new(uf: UF, stream: R, initial_backoff: Duration, max_backoff: Duration) -> Self{
+        Self{ uf, stream, initial_backoff, max_backoff, backoff: M::new(initial_backoff) }
+    }
+
+    pub fn stream(&self) -> &R{
+        &self.stream
+    }
+
+    pub fn initial_backoff(&self) -> Duration{
+        self.initial_backoff
+    }
+
+    pub fn max_backoff(&self) -> Duration{
+        self.max_backoff
+    }
+
+    /// Polls until an item arrives or end_time passes
+    fn poll(&self, backoff: &mut Duration, end_time: Option<Duration>, uf: &impl UniversalFunctions) -> Option<R::RData>{
+        loop {
+            if let Some(val) = self.stream.try_receive(){
+                *backoff = self.initial_backoff;
+                return Some(val);
+            }
+            let wait = match end_time{
+                None => *backoff,
+                Some(end_time) => (*backoff).min(end_time.checked_sub(uf.system_time())?),
+            };
+            uf.delay(wait);
+            *backoff = (*backoff * 2).min(self.max_backoff);
+        }
+    }
+}
+impl<UF, R, M> ReceiveStream for BackoffPollStream<UF, R, M> where UF: UniversalFunctions, R: ReceiveStream, M: Mutex<Inner=Duration>{
+    type RData = R::RData;
+
+    /// Polls once without sleeping or changing the backoff
+    fn try_receive(&self) -> Option<Self::RData> {
+        self.stream.try_receive()
+    }
+
+    fn receive(&self) -> Self::RData {
+        self.backoff.lock(|backoff| self.poll(backoff, None, &self.uf)).unwrap()
+    }
+}
+impl<UF, R, M> ReceiveTimoutStream for BackoffPollStream<UF, R, M> where UF: UniversalFunctions, R: ReceiveStream, M: Mutex<Inner=Duration>{
+    fn receive_timeout(&self, timeout: Duration, uf: &impl UniversalFunctions) -> Option<Self::RData> {
+        let end_time = uf.system_time() + timeout;
+        self.backoff.lock(|backoff| self.poll(backoff, Some(end_time), uf))
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod test{
+    use v5_traits::stream::std_impls::new_mpsc_channel;
+    use v5_traits::stream::{SendStream, ReceiveStream, ReceiveTimoutStream};
+    use parking_lot::Mutex;
+    use crate::backoff_poll_stream::BackoffPollStream;
+    use crate::test::Uf;
+    use core::time::Duration;
+
+    #[test]
+    fn backoff_poll_stream_test(){
+        let (sender, receiver) = new_mpsc_channel();
+        let stream = BackoffPollStream::<_, _, Mutex<_>>::new(Uf(), receiver, Duration::from_millis(1), Duration::from_millis(8));
+        assert_eq!(stream.receive_timeout(Duration::from_millis(30), &Uf()), None);
+
+        std::thread::scope(|scope|{
+            scope.spawn(||{
+                std::thread::sleep(Duration::from_millis(10));
+                sender.send_slice(&[1, 2]);
+            });
+            assert_eq!(stream.receive(), 1);
+        });
+        assert_eq!(stream.receive(), 2);
+    }
+}
@@ -0,0 +1,131 @@
+use v5_traits::stream::{SendStream, SendTimeoutStream, MessageStreamCreator};
+use v5_traits::mutex::Mutex;
+use v5_traits::UniversalFunctions;
+use core::marker::PhantomData;
+use core::sync::atomic::{AtomicUsize, Ordering};
+use core::time::Duration;
+use alloc::vec::Vec;
+
+/// What a broadcast does when a subscriber's stream is full
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum BroadcastPolicy{
+    /// The full subscriber misses the item and it is counted by dropped_count, the other subscribers still get it
+    DropForReceiver,
+    /// Waits for space in every subscriber so none miss items, one subscriber that stops receiving stalls the sender
+    BlockAll,
+}
+
+/// Sends a clone of every item to each subscriber
+/// Each subscriber gets its own stream from creator, policy decides whether a full subscriber blocks the send or misses the item.
+/// Subscriptions are never removed, with BlockAll every subscriber must keep receiving
+pub struct BroadcastStream<UF, T, C, M> where UF: UniversalFunctions, T: 'static + Send + Clone, C: MessageStreamCreator<T>, M: Mutex<Inner=Vec<C::Sender>>{
+    uf: UF,
+    creator: C,
+    policy: BroadcastPolicy,
+    subscribers: M,
+    dropped: AtomicUsize,
+    phantom_t: PhantomData<T>,
+}
+impl<UF, T, C, M> BroadcastStream<UF, T, C, M> where UF: UniversalFunctions, T: 'static + Send + Clone, C: MessageStreamCreator<T>, M: Mutex<Inner=Vec<C::Sender>>{
+    pub fn new(uf: UF, creator: C, policy: BroadcastPolicy) -> Self{
+        Self{ uf, creator, policy, subscribers: M::new(Vec::new()), dropped: AtomicUsize::new(0), phantom_t: Default::default() }
+    }
+
+    /// Returns a stream that receives every item sent after this call
+    /// Subscribing waits for a blocked send to finish
+    pub fn subscribe(&self) -> C::Receiver{
+        let (sender, receiver) = self.creator.create_stream();
+        self.subscribers.lock(|subscribers| subscribers.push(sender));
+        receiver
+    }
+
+    pub fn subscriber_count(&self) -> usize{
+        self.subscribers.lock(|subscribers| subscribers.len())
+    }
+
+    pub fn policy(&self) -> BroadcastPolicy{
+        self.policy
+    }
+
+    /// The amount of items subscribers missed because their stream was full, always 0 for BlockAll
+    pub fn dropped_count(&self) -> usize{
+        self.dropped.load(Ordering::SeqCst)
+    }
+
+    fn send_to(&self, subscriber: &C::Sender, val: T) where C::Sender: SendTimeoutStream{
+        match self.policy{
+            BroadcastPolicy::DropForReceiver => if subscriber.send_timeout(val, Duration::new(0, 0), &self.uf).is_some(){
+                self.dropped.fetch_add(1, Ordering::SeqCst);
+            },
+            BroadcastPolicy::BlockAll => subscriber.send(val),
+        }
+    }
+}
+impl<UF, T, C, M> SendStream for BroadcastStream<UF, T, C, M> where UF: UniversalFunctions, T: 'static + Send + Clone, C: MessageStreamCreator<T>, C::Sender: SendTimeoutStream, M: Mutex<Inner=Vec<C::Sender>>{
+    type SData = T;
+
+    /// Items sent with no subscribers are dropped without counting
+    fn send(&self, val: Self::SData) {
+        self.subscribers.lock(|subscribers|{
+            if let Some((last, rest)) = subscribers.split_last(){
+                for subscriber in rest{
+                    self.send_to(subscriber, val.clone());
+                }
+                self.send_to(last, val);
+            }
+        })
+    }
+}
+/// This ensures that this is sync if possible because no T is actually stored
+unsafe impl<UF, T, C, M> Sync for BroadcastStream<UF, T, C, M> where UF: UniversalFunctions + Sync, T: 'static + Send + Clone, C: MessageStreamCreator<T> + Sync, M: Mutex<Inner=Vec<C::Sender>> + Sync{}
+
+#[cfg(all(test, feature = "std"))]
+mod test{
+    use v5_traits::stream::std_impls::MPSCMessageCreator;
+    use v5_traits::stream::{SendStream, ReceiveStream, MessageStreamCreator};
+    use parking_lot::Mutex;
+    use crate::broadcast_stream::{BroadcastStream, BroadcastPolicy};
+    use crate::test::{Uf, TestQueue};
+    use alloc::sync::Arc;
+
+    /// Creates bounded streams so subscribers can fill up
+    struct TestQueueCreator(usize);
+    impl MessageStreamCreator<i32> for TestQueueCreator{
+        type Sender = Arc<TestQueue<i32>>;
+        type Receiver = Arc<TestQueue<i32>>;
+
+        fn create_stream(&self) -> (Self::Sender, Self::Receiver) {
+            let queue = Arc::new(TestQueue::new(self.0));
+            (queue.clone(), queue)
+        }
+    }
+
+    #[test]
+    fn broadcast_stream_test(){
+        let broadcast = BroadcastStream::<_, _, _, Mutex<_>>::new(Uf(), MPSCMessageCreator(), BroadcastPolicy::BlockAll);
+        broadcast.send(0);
+        let first = broadcast.subscribe();
+        let second = broadcast.subscribe();
+        broadcast.send_slice(&[1, 2]);
+
+        assert_eq!(broadcast.subscriber_count(), 2);
+        assert_eq!(first.receive_vec(2), vec![1, 2]);
+        assert_eq!(second.receive_vec(2), vec![1, 2]);
+        assert_eq!(first.try_receive(), None);
+        assert_eq!(broadcast.dropped_count(), 0);
+    }
+
+    #[test]
+    fn broadcast_drop_policy_test(){
+        let broadcast = BroadcastStream::<_, _, _, Mutex<_>>::new(Uf(), TestQueueCreator(2), BroadcastPolicy::DropForReceiver);
+        let full = broadcast.subscribe();
+        broadcast.send_slice(&[1, 2]);
+        let late = broadcast.subscribe();
+        broadcast.send_slice(&[3, 4]);
+
+        assert_eq!(full.receive_vec(2), vec![1, 2]);
+        assert_eq!(full.try_receive(), None);
+        assert_eq!(late.receive_vec(2), vec![3, 4]);
+        assert_eq!(broadcast.dropped_count(), 2);
+    }
+}
@@ -0,0 +1,122 @@
+use v5_traits::stream::SendStream;
+use v5_traits::mutex::Mutex;
+use v5_traits::UniversalFunctions;
+use alloc::vec::Vec;
+use core::mem::take;
+use core::time::Duration;
+
+/// The items a BufferedStream is holding and when the oldest was sent
+#[derive(Debug)]
+pub struct BufferedState<T>{
+    items: Vec<T>,
+    oldest: Duration,
+}
+impl<T> Default for BufferedState<T>{
+    fn default() -> Self {
+        Self{ items: Vec::new(), oldest: Duration::default() }
+    }
+}
+
+/// Holds sent items and forwards them to the inner stream as one send_vec once batch_size have been sent
+/// or a send finds the oldest held item has waited max_delay, flush forwards early and dropping flushes what is left.
+/// Larger batches save per transfer overhead on framed links at the cost of items waiting up to max_delay,
+/// the delay is only checked on send so a stream that goes quiet holds its items until the next send or flush
+#[derive(Debug)]
+pub struct BufferedStream<UF, S, M> where UF: UniversalFunctions, S: SendStream, M: Mutex<Inner=BufferedState<S::SData>>{
+    uf: UF,
+    stream: S,
+    batch_size: usize,
+    max_delay: Duration,
+    state: M,
+}
+impl<UF, S, M> BufferedStream<UF, S, M> where UF: UniversalFunctions, S: SendStream, M: Mutex<Inner=BufferedState<S::SData>>{
+    pub fn new(uf: UF, stream: S, batch_size: usize, max_delay: Duration) -> Self{
+        Self{ uf, stream, batch_size, max_delay, state: M::new(BufferedState::default()) }
+    }
+
+    pub fn stream(&self) -> &S{
+        &self.stream
+    }
+
+    pub fn batch_size(&self) -> usize{
+        self.batch_size
+    }
+
+    pub fn max_delay(&self) -> Duration{
+        self.max_delay
+    }
+
+    /// The amount of items held waiting for the batch to fill
+    pub fn buffered(&self) -> usize{
+        self.state.lock(|state| state.items.len())
+    }
+
+    /// Forwards every held item now
+    pub fn flush(&self){
+        self.state.lock(|state| self.forward(state))
+    }
+
+    /// Sends under the lock so batches from different tasks stay in order
+    fn forward(&self, state: &mut BufferedState<S::SData>){
+        if !state.items.is_empty(){
+            self.stream.send_vec(take(&mut state.items));
+        }
+    }
+}
+impl<UF, S, M> SendStream for BufferedStream<UF, S, M> where UF: UniversalFunctions, S: SendStream, M: Mutex<Inner=BufferedState<S::SData>>{
+    type SData = S::SData;
+
+    fn send(&self, val: Self::SData) {
+        let now = self.uf.system_time();
+        self.state.lock(|state|{
+            if state.items.is_empty(){
+                state.oldest = now;
+            }
+            state.items.push(val);
+            if state.items.len() >= self.batch_size || now.saturating_sub(state.oldest) >= self.max_delay{
+                self.forward(state);
+            }
+        })
+    }
+}
+impl<UF, S, M> Drop for BufferedStream<UF, S, M> where UF: UniversalFunctions, S: SendStream, M: Mutex<Inner=BufferedState<S::SData>>{
+    fn drop(&mut self) {
+        self.flush();
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod test{
+    use v5_traits::stream::{SendStream, ReceiveStream};
+    use v5_traits::mock::MockClock;
+    use parking_lot::Mutex;
+    use crate::buffered_stream::BufferedStream;
+    use crate::test::TestQueue;
+    use alloc::sync::Arc;
+    use core::time::Duration;
+
+    #[test]
+    fn buffered_stream_test(){
+        let clock = MockClock::default();
+        let queue = Arc::new(TestQueue::new(16));
+        let buffered = BufferedStream::<_, _, Mutex<_>>::new(clock.clone(), queue.clone(), 3, Duration::from_millis(20));
+        buffered.send_slice(&[1, 2]);
+        assert_eq!(queue.try_receive(), None);
+        assert_eq!(buffered.buffered(), 2);
+        buffered.send(3);
+        assert_eq!(queue.receive_vec(3), vec![1, 2, 3]);
+
+        buffered.send(4);
+        clock.advance(Duration::from_millis(20));
+        buffered.send(5);
+        assert_eq!(queue.receive_vec(2), vec![4, 5]);
+
+        buffered.send(6);
+        buffered.flush();
+        assert_eq!(queue.try_receive(), Some(6));
+        buffered.send(7);
+        drop(buffered);
+        assert_eq!(queue.try_receive(), Some(7));
+        assert_eq!(queue.try_receive(), None);
+    }
+}
@@ -0,0 +1,195 @@
+use v5_traits::stream::{SendStream, SendTimeoutStream, ReceiveStream, ReceiveTimoutStream, DuplexStream, DuplexTimeoutStream};
+use v5_traits::UniversalFunctions;
+use alloc::vec::Vec;
+use core::fmt::Debug;
+use core::sync::atomic::{AtomicUsize, Ordering};
+use core::time::Duration;
+use crc::crc16::checksum_x25;
+use crc::crc32::checksum_ieee;
+
+/// A checksum appended to each message by ChecksummedStream, stored as the last SIZE bytes big endian
+pub trait Checksum: Debug{
+    /// The size of the checksum in bytes, at most 8
+    const SIZE: usize;
+
+    fn checksum(data: &[u8]) -> u64;
+}
+
+/// CRC-16/X-25
+#[derive(Copy, Clone, Debug)]
+pub struct Crc16();
+impl Checksum for Crc16{
+    const SIZE: usize = 2;
+
+    fn checksum(data: &[u8]) -> u64 {
+        checksum_x25(data) as u64
+    }
+}
+
+/// CRC-32/IEEE
+#[derive(Copy, Clone, Debug)]
+pub struct Crc32();
+impl Checksum for Crc32{
+    const SIZE: usize = 4;
+
+    fn checksum(data: &[u8]) -> u64 {
+        checksum_ieee(data) as u64
+    }
+}
+
+/// A ChecksummedStream using a CRC, for radio telemetry where a corrupt frame is worse than a lost one
+/// For a byte stream put it on top of a framing stream such as CobsStream
+pub type CrcStream<S, C = Crc16> = ChecksummedStream<S, C>;
+
+/// Appends a checksum of type C to each message sent and verifies it on each message received
+/// Works on whole messages so it goes on top of a framing stream, received messages that fail verification
+/// or are too short to hold a checksum are dropped and counted by corrupt_count
+#[derive(Debug)]
+pub struct ChecksummedStream<S, C> where C: Checksum{
+    stream: S,
+    checksum: C,
+    corrupt: AtomicUsize,
+}
+impl<S, C> ChecksummedStream<S, C> where C: Checksum{
+    pub fn new(stream: S, checksum: C) -> Self{
+        Self{ stream, checksum, corrupt: AtomicUsize::new(0) }
+    }
+
+    pub fn stream(&self) -> &S{
+        &self.stream
+    }
+
+    pub fn checksum(&self) -> &C{
+        &self.checksum
+    }
+
+    /// The amount of received messages dropped for failing verification
+    pub fn corrupt_count(&self) -> usize{
+        self.corrupt.load(Ordering::SeqCst)
+    }
+
+    fn append_checksum(mut data: Vec<u8>) -> Vec<u8>{
+        let checksum = C::checksum(&data).to_be_bytes();
+        data.extend_from_slice(&checksum[checksum.len() - C::SIZE..]);
+        data
+    }
+
+    /// Removes and verifies the checksum, returns None and counts it if it does not match
+    fn verify(&self, mut data: Vec<u8>) -> Option<Vec<u8>>{
+        if data.len() < C::SIZE{
+            self.corrupt.fetch_add(1, Ordering::SeqCst);
+            return None;
+        }
+        let split = data.len() - C::SIZE;
+        let expected = C::checksum(&data[..split]).to_be_bytes();
+        if data[split..] != expected[expected.len() - C::SIZE..]{
+            self.corrupt.fetch_add(1, Ordering::SeqCst);
+            return None;
+        }
+        data.truncate(split);
+        Some(data)
+    }
+}
+impl<S, C> SendStream for ChecksummedStream<S, C> where S: SendStream<SData=Vec<u8>>, C: Checksum + Send + Sync{
+    type SData = Vec<u8>;
+
+    fn send(&self, val: Self::SData) {
+        self.stream.send(Self::append_checksum(val))
+    }
+}
+impl<S, C> SendTimeoutStream for ChecksummedStream<S, C> where S: SendTimeoutStream<SData=Vec<u8>>, C: Checksum + Send + Sync{
+    fn send_timeout(&self, val: Self::SData, timeout: Duration, uf: &impl UniversalFunctions) -> Option<Self::SData> {
+        let out = self.stream.send_timeout(Self::append_checksum(val), timeout, uf)?;
+        Some(out[..out.len() - C::SIZE].to_vec())
+    }
+}
+impl<S, C> ReceiveStream for ChecksummedStream<S, C> where S: ReceiveStream<RData=Vec<u8>>, C: Checksum{
+    type RData = Vec<u8>;
+
+    /// Drops corrupt messages until a valid one is found or none are available
+    fn try_receive(&self) -> Option<Self::RData> {
+        while let Some(data) = self.stream.try_receive(){
+            if let Some(data) = self.verify(data){
+                return Some(data);
+            }
+        }
+        None
+    }
+
+    fn receive(&self) -> Self::RData {
+        loop {
+            if let Some(data) = self.verify(self.stream.receive()){
+                return data;
+            }
+        }
+    }
+}
+impl<S, C> ReceiveTimoutStream for ChecksummedStream<S, C> where S: ReceiveTimoutStream<RData=Vec<u8>>, C: Checksum{
+    fn receive_timeout(&self, timeout: Duration, uf: &impl UniversalFunctions) -> Option<Self::RData> {
+        let end_time = uf.system_time() + timeout;
+        loop {
+            let data = self.stream.receive_timeout(end_time.checked_sub(uf.system_time()).unwrap_or_default(), uf)?;
+            if let Some(data) = self.verify(data){
+                return Some(data);
+            }
+            if uf.system_time() >= end_time{
+                return None;
+            }
+        }
+    }
+}
+impl<S, C> DuplexStream for ChecksummedStream<S, C> where S: DuplexStream<SData=Vec<u8>>, C: Checksum + Send + Sync{}
+impl<S, C> DuplexTimeoutStream for ChecksummedStream<S, C> where S: DuplexTimeoutStream<SData=Vec<u8>>, C: Checksum + Send + Sync{}
+
+#[cfg(all(test, feature = "std"))]
+mod test{
+    use v5_traits::stream::{SendStream, ReceiveStream};
+    use parking_lot::Mutex;
+    use crate::checksummed_stream::{ChecksummedStream, CrcStream, Crc16, Crc32};
+    use crate::cobs_stream::CobsStream;
+    use crate::test::TestQueue;
+    use alloc::sync::Arc;
+    use alloc::vec::Vec;
+
+    #[test]
+    fn checksummed_stream_test(){
+        let queue = Arc::new(TestQueue::new(8));
+        let stream = ChecksummedStream::new(queue.clone(), Crc32());
+        stream.send(vec![1, 2, 3]);
+        let mut corrupted = queue.receive();
+        assert_eq!(corrupted.len(), 7);
+        corrupted[1] ^= 1;
+        queue.send(corrupted);
+        queue.send(vec![9]);
+        stream.send(vec![4, 5]);
+
+        assert_eq!(stream.receive(), vec![4, 5]);
+        assert_eq!(stream.corrupt_count(), 2);
+        assert_eq!(stream.try_receive(), None);
+
+        let stream = ChecksummedStream::new(queue.clone(), Crc16());
+        stream.send(vec![]);
+        assert_eq!(queue.try_receive().map(|data| data.len()), Some(2));
+    }
+
+    #[test]
+    fn crc_stream_test(){
+        let bytes = Arc::new(TestQueue::new(64));
+        let link = CrcStream::new(CobsStream::<_, Mutex<_>>::new(bytes.clone()), Crc16());
+        link.send(vec![10, 20, 30]);
+        assert_eq!(link.receive(), vec![10, 20, 30]);
+        assert_eq!(link.corrupt_count(), 0);
+
+        link.send(vec![10, 20, 30]);
+        let mut frame = Vec::new();
+        while let Some(byte) = bytes.try_receive(){
+            frame.push(byte);
+        }
+        frame[2] ^= 0b100;
+        bytes.send_slice(&frame);
+        link.send(vec![40]);
+        assert_eq!(link.receive(), vec![40]);
+        assert_eq!(link.corrupt_count(), 1);
+        assert_eq!(link.try_receive(), None);
+    }
+}
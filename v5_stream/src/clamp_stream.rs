@@ -0,0 +1,105 @@
+use v5_traits::stream::{ReceiveStream, ReceiveTimoutStream};
+use v5_traits::UniversalFunctions;
+use core::sync::atomic::{AtomicUsize, Ordering};
+use core::time::Duration;
+
+/// Clamps received values to min..=max and counts how many had to be clamped
+/// Values that are not comparable such as NaN are replaced by fallback and counted as clamped
+#[derive(Debug)]
+pub struct ClampStream<R> where R: ReceiveStream, R::RData: PartialOrd + Copy{
+    stream: R,
+    min: R::RData,
+    max: R::RData,
+    fallback: R::RData,
+    clamped: AtomicUsize,
+}
+impl<R> ClampStream<R> where R: ReceiveStream, R::RData: PartialOrd + Copy{
+    /// Not comparable values are replaced by min
+    /// Panics if min is greater than max or they are not comparable
+    pub fn new(stream: R, min: R::RData, max: R::RData) -> Self{
+        Self::with_fallback(stream, min, max, min)
+    }
+
+    /// Not comparable values are replaced by fallback, such as 0 for a voltage so NaN stops the motor
+    /// Panics if min is greater than max or fallback is not within min..=max
+    pub fn with_fallback(stream: R, min: R::RData, max: R::RData, fallback: R::RData) -> Self{
+        assert!(min <= max, "ClampStream min must not be greater than max");
+        assert!(min <= fallback && fallback <= max, "ClampStream fallback must be within min..=max");
+        Self{ stream, min, max, fallback, clamped: AtomicUsize::new(0) }
+    }
+
+    pub fn stream(&self) -> &R{
+        &self.stream
+    }
+
+    /// The amount of received values that were outside min..=max or not comparable
+    pub fn clamped_count(&self) -> usize{
+        self.clamped.load(Ordering::SeqCst)
+    }
+
+    fn apply(&self, val: R::RData) -> R::RData{
+        let out = if val < self.min{
+            self.min
+        }
+        else if val > self.max{
+            self.max
+        }
+        else if val >= self.min{
+            return val;
+        }
+        else{
+            self.fallback
+        };
+        self.clamped.fetch_add(1, Ordering::SeqCst);
+        out
+    }
+}
+impl<R> ReceiveStream for ClampStream<R> where R: ReceiveStream, R::RData: PartialOrd + Copy{
+    type RData = R::RData;
+
+    fn try_receive(&self) -> Option<Self::RData> {
+        self.stream.try_receive().map(|val| self.apply(val))
+    }
+
+    fn receive(&self) -> Self::RData {
+        self.apply(self.stream.receive())
+    }
+}
+impl<R> ReceiveTimoutStream for ClampStream<R> where R: ReceiveTimoutStream, R::RData: PartialOrd + Copy{
+    fn receive_timeout(&self, timeout: Duration, uf: &impl UniversalFunctions) -> Option<Self::RData> {
+        self.stream.receive_timeout(timeout, uf).map(|val| self.apply(val))
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod test{
+    use v5_traits::stream::std_impls::new_mpsc_channel;
+    use v5_traits::stream::{SendStream, ReceiveStream};
+    use crate::clamp_stream::ClampStream;
+
+    #[test]
+    fn clamp_stream_test(){
+        let (sender, receiver) = new_mpsc_channel();
+        let voltages = ClampStream::new(receiver, -12000, 12000);
+        sender.send_slice(&[-20000, 0, 12000, 15000]);
+
+        assert_eq!(voltages.receive_vec(4), vec![-12000, 0, 12000, 12000]);
+        assert_eq!(voltages.clamped_count(), 2);
+    }
+
+    #[test]
+    fn clamp_stream_nan_test(){
+        let (sender, receiver) = new_mpsc_channel();
+        let powers = ClampStream::with_fallback(receiver, -1.0, 1.0, 0.0);
+        sender.send_slice(&[f64::NAN, 0.5, 2.0]);
+
+        assert_eq!(powers.receive_vec(3), vec![0.0, 0.5, 1.0]);
+        assert_eq!(powers.clamped_count(), 2);
+
+        let (sender, receiver) = new_mpsc_channel();
+        let powers = ClampStream::new(receiver, -1.0, 1.0);
+        sender.send(f64::NAN);
+        assert_eq!(powers.receive(), -1.0);
+        assert_eq!(powers.clamped_count(), 1);
+    }
+}
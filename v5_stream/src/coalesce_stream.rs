@@ -0,0 +1,111 @@
+use v5_traits::stream::{ReceiveStream, ReceiveTimoutStream};
+use v5_traits::mutex::Mutex;
+use v5_traits::UniversalFunctions;
+use core::time::Duration;
+use alloc::vec::Vec;
+
+/// Wraps a receive stream and skips items superseded by a newer item with the same key before they are received
+/// Each receive drains everything available from the stream into a buffer, a newer item replaces the buffered item with its key in place.
+/// Items are received in the order their key first entered the buffer, so different keys keep their relative order
+/// but an item can be received after newer items of other keys that arrived before it replaced an older one
+#[derive(Debug)]
+pub struct CoalesceStream<R, K, F, M> where R: ReceiveStream, K: PartialEq, F: Fn(&R::RData) -> K, M: Mutex<Inner=Vec<R::RData>>{
+    stream: R,
+    key: F,
+    buffer: M,
+}
+impl<R, K, F, M> CoalesceStream<R, K, F, M> where R: ReceiveStream, K: PartialEq, F: Fn(&R::RData) -> K, M: Mutex<Inner=Vec<R::RData>>{
+    pub fn new(stream: R, key: F) -> Self{
+        Self{ stream, key, buffer: M::new(Vec::new()) }
+    }
+
+    pub fn stream(&self) -> &R{
+        &self.stream
+    }
+
+    /// The amount of coalesced items waiting to be received
+    pub fn buffered(&self) -> usize{
+        self.buffer.lock(|buffer| buffer.len())
+    }
+
+    fn insert(&self, buffer: &mut Vec<R::RData>, val: R::RData){
+        let key = (self.key)(&val);
+        match buffer.iter_mut().find(|buffered| (self.key)(buffered) == key){
+            Some(buffered) => *buffered = val,
+            None => buffer.push(val),
+        }
+    }
+
+    /// Drains the stream into the buffer after adding first, then takes the oldest key
+    fn coalesce(&self, first: Option<R::RData>) -> Option<R::RData>{
+        self.buffer.lock(|buffer|{
+            if let Some(first) = first{
+                self.insert(buffer, first);
+            }
+            while let Some(val) = self.stream.try_receive(){
+                self.insert(buffer, val);
+            }
+            if buffer.is_empty(){
+                None
+            }
+            else{
+                Some(buffer.remove(0))
+            }
+        })
+    }
+}
+impl<R, K, F, M> ReceiveStream for CoalesceStream<R, K, F, M> where R: ReceiveStream, K: PartialEq, F: Fn(&R::RData) -> K, M: Mutex<Inner=Vec<R::RData>>{
+    type RData = R::RData;
+
+    fn try_receive(&self) -> Option<Self::RData> {
+        self.coalesce(None)
+    }
+
+    fn receive(&self) -> Self::RData {
+        loop {
+            if let Some(val) = self.coalesce(None){
+                return val;
+            }
+            let val = self.stream.receive();
+            if let Some(val) = self.coalesce(Some(val)){
+                return val;
+            }
+        }
+    }
+}
+impl<R, K, F, M> ReceiveTimoutStream for CoalesceStream<R, K, F, M> where R: ReceiveTimoutStream, K: PartialEq, F: Fn(&R::RData) -> K, M: Mutex<Inner=Vec<R::RData>>{
+    fn receive_timeout(&self, timeout: Duration, uf: &impl UniversalFunctions) -> Option<Self::RData> {
+        let end_time = uf.system_time() + timeout;
+        loop {
+            if let Some(val) = self.coalesce(None){
+                return Some(val);
+            }
+            let val = self.stream.receive_timeout(end_time.checked_sub(uf.system_time()).unwrap_or_default(), uf)?;
+            if let Some(val) = self.coalesce(Some(val)){
+                return Some(val);
+            }
+        }
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod test{
+    use v5_traits::stream::std_impls::new_mpsc_channel;
+    use v5_traits::stream::{SendStream, ReceiveStream};
+    use parking_lot::Mutex;
+    use crate::coalesce_stream::CoalesceStream;
+
+    #[test]
+    fn coalesce_stream_test(){
+        let (sender, receiver) = new_mpsc_channel();
+        let commands = CoalesceStream::<_, _, _, Mutex<_>>::new(receiver, |command: &(u8, i32)| command.0);
+        sender.send_slice(&[(1, 10), (2, 20), (1, 11), (3, 30), (1, 12)]);
+
+        assert_eq!(commands.receive(), (1, 12));
+        assert_eq!(commands.buffered(), 2);
+        sender.send((3, 31));
+        assert_eq!(commands.receive(), (2, 20));
+        assert_eq!(commands.receive(), (3, 31));
+        assert_eq!(commands.try_receive(), None);
+    }
+}
@@ -0,0 +1,177 @@
+use v5_traits::stream::{SendStream, ReceiveStream, ReceiveTimoutStream};
+use v5_traits::mutex::Mutex;
+use v5_traits::UniversalFunctions;
+use alloc::vec::Vec;
+use core::sync::atomic::{AtomicUsize, Ordering};
+use core::time::Duration;
+
+/// Frames messages over a byte stream with consistent overhead byte stuffing (COBS)
+/// Each frame is encoded without zero bytes and ended with a zero so the receiver always resynchronizes at the next zero,
+/// a lost or corrupted byte only loses the frame it is in. Frames that fail to decode are dropped and counted by corrupt_count.
+/// COBS only finds corruption that breaks the encoding, put a ChecksummedStream on top to find the rest
+#[derive(Debug)]
+pub struct CobsStream<S, M> where M: Mutex<Inner=Vec<u8>>{
+    stream: S,
+    buffer: M,
+    corrupt: AtomicUsize,
+}
+impl<S, M> CobsStream<S, M> where M: Mutex<Inner=Vec<u8>>{
+    pub fn new(stream: S) -> Self{
+        Self{ stream, buffer: M::new(Vec::new()), corrupt: AtomicUsize::new(0) }
+    }
+
+    pub fn stream(&self) -> &S{
+        &self.stream
+    }
+
+    /// The amount of received frames dropped for failing to decode
+    pub fn corrupt_count(&self) -> usize{
+        self.corrupt.load(Ordering::SeqCst)
+    }
+
+    /// The amount of bytes received towards the next frame
+    pub fn buffered(&self) -> usize{
+        self.buffer.lock(|buffer| buffer.len())
+    }
+
+    /// Adds byte to buffer, returns the decoded frame if byte ended one
+    fn push_byte(&self, buffer: &mut Vec<u8>, byte: u8) -> Option<Vec<u8>>{
+        if byte != 0{
+            buffer.push(byte);
+            return None;
+        }
+        if buffer.is_empty(){
+            return None;
+        }
+        let frame = decode(buffer);
+        buffer.clear();
+        if frame.is_none(){
+            self.corrupt.fetch_add(1, Ordering::SeqCst);
+        }
+        frame
+    }
+}
+impl<S, M> SendStream for CobsStream<S, M> where S: SendStream<SData=u8>, M: Mutex<Inner=Vec<u8>>{
+    type SData = Vec<u8>;
+
+    fn send(&self, val: Vec<u8>) {
+        self.stream.send_vec(encode(&val))
+    }
+}
+impl<S, M> ReceiveStream for CobsStream<S, M> where S: ReceiveStream<RData=u8>, M: Mutex<Inner=Vec<u8>>{
+    type RData = Vec<u8>;
+
+    fn try_receive(&self) -> Option<Vec<u8>> {
+        self.buffer.lock(|buffer|{
+            loop {
+                if let Some(frame) = self.push_byte(buffer, self.stream.try_receive()?){
+                    return Some(frame);
+                }
+            }
+        })
+    }
+
+    fn receive(&self) -> Vec<u8> {
+        self.buffer.lock(|buffer|{
+            loop {
+                if let Some(frame) = self.push_byte(buffer, self.stream.receive()){
+                    return frame;
+                }
+            }
+        })
+    }
+}
+impl<S, M> ReceiveTimoutStream for CobsStream<S, M> where S: ReceiveTimoutStream<RData=u8>, M: Mutex<Inner=Vec<u8>>{
+    fn receive_timeout(&self, timeout: Duration, uf: &impl UniversalFunctions) -> Option<Vec<u8>> {
+        let end_time = uf.system_time() + timeout;
+        self.buffer.lock(|buffer|{
+            loop {
+                let remaining = end_time.checked_sub(uf.system_time()).unwrap_or_default();
+                if let Some(frame) = self.push_byte(buffer, self.stream.receive_timeout(remaining, uf)?){
+                    return Some(frame);
+                }
+            }
+        })
+    }
+}
+
+/// COBS encodes data and appends the zero delimiter
+fn encode(data: &[u8]) -> Vec<u8>{
+    let mut out = Vec::with_capacity(data.len() + data.len() / 254 + 2);
+    let mut code_index = 0;
+    out.push(0);
+    for &byte in data{
+        if byte != 0{
+            out.push(byte);
+        }
+        if byte == 0 || out.len() - code_index == 0xFF{
+            out[code_index] = (out.len() - code_index) as u8;
+            code_index = out.len();
+            out.push(0);
+        }
+    }
+    out[code_index] = (out.len() - code_index) as u8;
+    out.push(0);
+    out
+}
+
+/// Decodes a COBS frame without its delimiter, returns None if it is not valid COBS
+fn decode(data: &[u8]) -> Option<Vec<u8>>{
+    let mut out = Vec::with_capacity(data.len());
+    let mut index = 0;
+    while index < data.len(){
+        let code = data[index] as usize;
+        if code == 0 || index + code > data.len(){
+            return None;
+        }
+        out.extend_from_slice(&data[index + 1..index + code]);
+        index += code;
+        if code < 0xFF && index < data.len(){
+            out.push(0);
+        }
+    }
+    Some(out)
+}
+
+#[cfg(all(test, feature = "std"))]
+mod test{
+    use v5_traits::stream::{SendStream, ReceiveStream};
+    use parking_lot::Mutex;
+    use crate::cobs_stream::{CobsStream, encode, decode};
+    use crate::test::TestQueue;
+    use alloc::sync::Arc;
+    use alloc::vec::Vec;
+
+    #[test]
+    fn cobs_round_trip_test(){
+        let long: Vec<u8> = (1..=255).chain(0..=10).collect();
+        for frame in [vec![], vec![0], vec![1, 0, 2], vec![0, 0], long]{
+            let encoded = encode(&frame);
+            assert!(!encoded[..encoded.len() - 1].contains(&0));
+            assert_eq!(decode(&encoded[..encoded.len() - 1]), Some(frame));
+        }
+
+        let bytes = Arc::new(TestQueue::new(64));
+        let link = CobsStream::<_, Mutex<_>>::new(bytes.clone());
+        link.send(vec![1, 0, 2]);
+        link.send(vec![]);
+        assert_eq!(link.receive(), vec![1, 0, 2]);
+        assert_eq!(link.receive(), vec![]);
+        assert_eq!(link.try_receive(), None);
+    }
+
+    #[test]
+    fn cobs_resync_test(){
+        let bytes = Arc::new(TestQueue::new(64));
+        let link = CobsStream::<_, Mutex<_>>::new(bytes.clone());
+        let mut first = encode(&[1, 2, 3]);
+        first[0] = 9;
+        bytes.send_slice(&[7, 7]);
+        bytes.send_slice(&first);
+        link.send(vec![4, 5, 6]);
+
+        assert_eq!(link.receive(), vec![4, 5, 6]);
+        assert_eq!(link.corrupt_count(), 1);
+        assert_eq!(link.buffered(), 0);
+    }
+}
@@ -0,0 +1,48 @@
+/// Chains adapters onto a stream without nesting their constructors
+/// Each stage is a constructor call with the stream left out, it is passed as the first argument:
+/// compose_stream!(receiver => ScaleStream::<_, f64>::new(0.5, 0.0) => DeadbandStream::new(0.1, false) => DedupStream::<_, Mutex<_>>::new())
+/// expands to DedupStream::<_, Mutex<_>>::new(DeadbandStream::new(ScaleStream::<_, f64>::new(receiver, 0.5, 0.0), 0.1, false)).
+/// Item types are checked by each adapter's own bounds so a mismatch is a compile error at that stage
+#[macro_export]
+macro_rules! compose_stream {
+    (@stage [$($constructor:tt)*] $stream:expr; ($($arg:expr),* $(,)?) => $($rest:tt)+) => {
+        $crate::compose_stream!(@stage [] $($constructor)*($stream, $($arg),*); $($rest)+)
+    };
+    (@stage [$($constructor:tt)*] $stream:expr; ($($arg:expr),* $(,)?)) => {
+        $($constructor)*($stream, $($arg),*)
+    };
+    (@stage [$($constructor:tt)*] $stream:expr; $next:tt $($rest:tt)*) => {
+        $crate::compose_stream!(@stage [$($constructor)* $next] $stream; $($rest)*)
+    };
+    ($source:expr => $($stages:tt)+) => {
+        $crate::compose_stream!(@stage [] $source; $($stages)+)
+    };
+    ($source:expr) => {
+        $source
+    };
+}
+
+#[cfg(all(test, feature = "std"))]
+mod test{
+    use v5_traits::stream::std_impls::new_mpsc_channel;
+    use v5_traits::stream::{SendStream, ReceiveStream};
+    use parking_lot::Mutex;
+    use crate::scale_stream::ScaleStream;
+    use crate::deadband_stream::DeadbandStream;
+    use crate::dedup_stream::DedupStream;
+
+    #[test]
+    fn compose_stream_test(){
+        let (sender, receiver) = new_mpsc_channel::<i32>();
+        let stream = compose_stream!(receiver
+            => ScaleStream::<_, f64>::new(0.5, 0.0)
+            => DeadbandStream::new(1.0, false)
+            => DedupStream::<_, Mutex<_>>::new()
+        );
+        sender.send_slice(&[1, 0, 4, 4, 6]);
+
+        assert_eq!(stream.receive_vec(3), vec![0.0, 2.0, 3.0]);
+        assert_eq!(stream.try_receive(), None);
+        assert_eq!(stream.stream().stream().stream().try_receive(), None);
+    }
+}
@@ -14,6 +14,15 @@ impl<S, R> ComposedStream<S, R> where S: SendStream, R: ReceiveStream<RData=S::S
     pub fn new(send_stream: S, receive_stream: R) -> Self{
         Self{ send_stream, receive_stream }
     }
+
+    /// Splits this back into the send and receive streams so either can be paired with a different stream
+    pub fn into_parts(self) -> (S, R){
+        (self.send_stream, self.receive_stream)
+    }
+
+    pub fn parts(&self) -> (&S, &R){
+        (&self.send_stream, &self.receive_stream)
+    }
 }
 impl<S, R> SendStream for ComposedStream<S, R> where S: SendStream, R: ReceiveStream<RData=S::SData>{
     type SData = S::SData;
@@ -30,6 +39,9 @@ impl<S, R> SendStream for ComposedStream<S, R> where S: SendStream, R: ReceiveSt
         self.send_stream.send_vec(data)
     }
 }
+/// Every method forwards to send_stream, including the slice and vec variants,
+/// so the sent count of send_slice_timeout and the leftover of send_vec_timeout are exactly those of send_stream.
+/// Leftovers are the unsent items in their original order and nested ComposedStreams pass the innermost leftover through unchanged
 impl<S, R> SendTimeoutStream for ComposedStream<S, R> where S: SendTimeoutStream, R: ReceiveStream<RData=S::SData>{
     fn send_timeout(&self, val: Self::SData, timeout: Duration, uf: &impl UniversalFunctions) -> Option<Self::SData> {
         self.send_stream.send_timeout(val, timeout, uf)
@@ -81,3 +93,44 @@ impl<S, R> ReceiveTimoutStream for ComposedStream<S, R> where S: SendStream, R:
 }
 impl<S, R> DuplexStream for ComposedStream<S, R> where S: SendStream, R: ReceiveStream<RData=S::SData>{}
 impl<S, R> DuplexTimeoutStream for ComposedStream<S, R> where S: SendTimeoutStream, R: ReceiveTimoutStream<RData=S::SData>{}
+
+#[cfg(all(test, feature = "std"))]
+mod test{
+    use v5_traits::stream::{SendStream, SendTimeoutStream, ReceiveStream};
+    use crate::composed_stream::ComposedStream;
+    use crate::test::{Uf, TestQueue};
+    use alloc::sync::Arc;
+    use core::time::Duration;
+
+    #[test]
+    fn composed_send_vec_timeout_test(){
+        let queue = Arc::new(TestQueue::new(3));
+        let inner = ComposedStream::new(queue.clone(), queue.clone());
+        let outer = ComposedStream::new(inner, queue.clone());
+
+        assert_eq!(outer.send_vec_timeout(vec![1, 2, 3, 4, 5], Duration::from_millis(10), &Uf()), Some(vec![4, 5]));
+        assert_eq!(outer.receive_vec(3), vec![1, 2, 3]);
+        assert_eq!(outer.send_vec_timeout(vec![6, 7], Duration::from_millis(10), &Uf()), None);
+        assert_eq!(outer.send_slice_timeout(&[8, 9], Duration::from_millis(10), &Uf()), 1);
+        assert_eq!(outer.receive_vec(3), vec![6, 7, 8]);
+        assert_eq!(outer.try_receive(), None);
+    }
+
+    #[test]
+    fn composed_into_parts_test(){
+        let first = Arc::new(TestQueue::new(4));
+        let second = Arc::new(TestQueue::new(4));
+        let link = ComposedStream::new(first.clone(), first.clone());
+        link.send(1);
+        assert_eq!(link.receive(), 1);
+        assert!(Arc::ptr_eq(link.parts().0, &first));
+
+        let (sender, _receiver) = link.into_parts();
+        let relinked = ComposedStream::new(sender, second.clone());
+        relinked.send(2);
+        second.send(3);
+        assert_eq!(relinked.receive(), 3);
+        assert_eq!(first.try_receive(), Some(2));
+        assert_eq!(relinked.try_receive(), None);
+    }
+}
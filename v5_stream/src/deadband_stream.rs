@@ -0,0 +1,76 @@
+use v5_traits::stream::{ReceiveStream, ReceiveTimoutStream};
+use v5_traits::UniversalFunctions;
+use core::time::Duration;
+
+/// Zeroes received values with a magnitude below threshold
+/// With rescale the values outside the deadband are mapped so the output starts at 0 at the threshold and reaches ±1 at ±1,
+/// rescaling assumes inputs are normalized to -1..=1 and threshold is below 1
+#[derive(Debug)]
+pub struct DeadbandStream<R> where R: ReceiveStream<RData=f64>{
+    stream: R,
+    threshold: f64,
+    rescale: bool,
+}
+impl<R> DeadbandStream<R> where R: ReceiveStream<RData=f64>{
+    pub fn new(stream: R, threshold: f64, rescale: bool) -> Self{
+        Self{ stream, threshold, rescale }
+    }
+
+    pub fn stream(&self) -> &R{
+        &self.stream
+    }
+
+    pub fn threshold(&self) -> f64{
+        self.threshold
+    }
+
+    fn apply(&self, val: f64) -> f64{
+        let magnitude = if val < 0.0 { -val } else { val };
+        if magnitude < self.threshold{
+            0.0
+        }
+        else if self.rescale{
+            let scaled = (magnitude - self.threshold) / (1.0 - self.threshold);
+            if val < 0.0 { -scaled } else { scaled }
+        }
+        else{
+            val
+        }
+    }
+}
+impl<R> ReceiveStream for DeadbandStream<R> where R: ReceiveStream<RData=f64>{
+    type RData = f64;
+
+    fn try_receive(&self) -> Option<f64> {
+        self.stream.try_receive().map(|val| self.apply(val))
+    }
+
+    fn receive(&self) -> f64 {
+        self.apply(self.stream.receive())
+    }
+}
+impl<R> ReceiveTimoutStream for DeadbandStream<R> where R: ReceiveTimoutStream<RData=f64>{
+    fn receive_timeout(&self, timeout: Duration, uf: &impl UniversalFunctions) -> Option<f64> {
+        self.stream.receive_timeout(timeout, uf).map(|val| self.apply(val))
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod test{
+    use v5_traits::stream::std_impls::new_mpsc_channel;
+    use v5_traits::stream::{SendStream, ReceiveStream};
+    use crate::deadband_stream::DeadbandStream;
+
+    #[test]
+    fn deadband_stream_test(){
+        let (sender, receiver) = new_mpsc_channel();
+        let deadband = DeadbandStream::new(receiver, 0.2, false);
+        sender.send_slice(&[0.1, -0.19, 0.5, -0.2]);
+        assert_eq!(deadband.receive_vec(4), vec![0.0, 0.0, 0.5, -0.2]);
+
+        let (sender, receiver) = new_mpsc_channel();
+        let rescaled = DeadbandStream::new(receiver, 0.5, true);
+        sender.send_slice(&[0.25, 0.5, 0.75, -1.0]);
+        assert_eq!(rescaled.receive_vec(4), vec![0.0, 0.0, 0.5, -1.0]);
+    }
+}
@@ -0,0 +1,135 @@
+use v5_traits::stream::{ReceiveStream, ReceiveTimoutStream};
+use v5_traits::mutex::Mutex;
+use v5_traits::UniversalFunctions;
+use core::time::Duration;
+
+/// Debounces a digital input, a state is only received once it has held for interval
+/// The latest differing input is held as a candidate with the time it arrived, any change resets it so flapping faster than interval never gets through.
+/// Works with inputs that only send on change, while a candidate is pending receiving waits on the inner stream no longer than it needs to become stable
+/// The first stable state is always received, after that only changes are
+#[derive(Debug)]
+pub struct DebounceStream<UF, R, M> where UF: UniversalFunctions, R: ReceiveTimoutStream<RData=bool>, M: Mutex<Inner=DebounceState>{
+    uf: UF,
+    stream: R,
+    interval: Duration,
+    state: M,
+}
+impl<UF, R, M> DebounceStream<UF, R, M> where UF: UniversalFunctions, R: ReceiveTimoutStream<RData=bool>, M: Mutex<Inner=DebounceState>{
+    pub fn new(uf: UF, stream: R, interval: Duration) -> Self{
+        Self{ uf, stream, interval, state: M::new(DebounceState{ stable: None, candidate: None }) }
+    }
+
+    pub fn stream(&self) -> &R{
+        &self.stream
+    }
+
+    /// The last received state, None until the first state is stable
+    pub fn stable(&self) -> Option<bool>{
+        self.state.lock(|state| state.stable)
+    }
+
+    /// Receives a stable state waiting up to end_time if given
+    fn debounced(&self, state: &mut DebounceState, end_time: Option<Duration>, uf: &impl UniversalFunctions) -> Option<bool>{
+        loop {
+            let now = uf.system_time();
+            let stable_at = state.pending().map(|since| since + self.interval);
+            if let Some(stable_at) = stable_at{
+                if now >= stable_at{
+                    state.stable = state.candidate.map(|(val, _)| val);
+                    return state.stable;
+                }
+            }
+            if end_time.is_some_and(|end_time| now >= end_time){
+                return None;
+            }
+            let wait = match (stable_at, end_time){
+                (None, None) => None,
+                (Some(time), None) | (None, Some(time)) => Some(time),
+                (Some(stable_at), Some(end_time)) => Some(stable_at.min(end_time)),
+            };
+            let received = match wait{
+                None => Some(self.stream.receive()),
+                Some(wait) => self.stream.receive_timeout(wait.checked_sub(now).unwrap_or_default(), uf),
+            };
+            if let Some(val) = received{
+                state.update(val, uf.system_time());
+            }
+        }
+    }
+}
+impl<UF, R, M> ReceiveStream for DebounceStream<UF, R, M> where UF: UniversalFunctions, R: ReceiveTimoutStream<RData=bool>, M: Mutex<Inner=DebounceState>{
+    type RData = bool;
+
+    fn try_receive(&self) -> Option<bool> {
+        self.state.lock(|state|{
+            while let Some(val) = self.stream.try_receive(){
+                state.update(val, self.uf.system_time());
+            }
+            match state.pending(){
+                Some(since) if self.uf.system_time() >= since + self.interval => {
+                    state.stable = state.candidate.map(|(val, _)| val);
+                    state.stable
+                },
+                _ => None,
+            }
+        })
+    }
+
+    fn receive(&self) -> bool {
+        self.state.lock(|state| self.debounced(state, None, &self.uf)).unwrap()
+    }
+}
+impl<UF, R, M> ReceiveTimoutStream for DebounceStream<UF, R, M> where UF: UniversalFunctions, R: ReceiveTimoutStream<RData=bool>, M: Mutex<Inner=DebounceState>{
+    fn receive_timeout(&self, timeout: Duration, uf: &impl UniversalFunctions) -> Option<bool> {
+        let end_time = uf.system_time() + timeout;
+        self.state.lock(|state| self.debounced(state, Some(end_time), uf))
+    }
+}
+
+/// The last received state of a DebounceStream and the candidate waiting to become stable
+#[derive(Debug)]
+pub struct DebounceState{
+    stable: Option<bool>,
+    candidate: Option<(bool, Duration)>,
+}
+impl DebounceState{
+    fn update(&mut self, val: bool, now: Duration){
+        if self.candidate.map(|(candidate, _)| candidate) != Some(val){
+            self.candidate = Some((val, now));
+        }
+    }
+
+    /// When the candidate arrived if it differs from the stable state
+    fn pending(&self) -> Option<Duration>{
+        match self.candidate{
+            Some((val, since)) if self.stable != Some(val) => Some(since),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod test{
+    use v5_traits::stream::std_impls::new_mpsc_channel;
+    use v5_traits::stream::{SendStream, ReceiveStream, ReceiveTimoutStream};
+    use parking_lot::Mutex;
+    use crate::debounce_stream::DebounceStream;
+    use crate::test::Uf;
+    use core::time::Duration;
+
+    #[test]
+    fn debounce_stream_test(){
+        let (sender, receiver) = new_mpsc_channel();
+        let switch = DebounceStream::<_, _, Mutex<_>>::new(Uf(), receiver, Duration::from_millis(20));
+        sender.send(false);
+        assert_eq!(switch.try_receive(), None);
+        assert!(!switch.receive());
+
+        sender.send_slice(&[true, false, true, false]);
+        assert_eq!(switch.receive_timeout(Duration::from_millis(40), &Uf()), None);
+
+        sender.send(true);
+        assert!(switch.receive());
+        assert_eq!(switch.stable(), Some(true));
+    }
+}
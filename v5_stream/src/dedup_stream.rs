@@ -0,0 +1,91 @@
+use v5_traits::stream::{ReceiveStream, ReceiveTimoutStream};
+use v5_traits::mutex::Mutex;
+use v5_traits::UniversalFunctions;
+use core::time::Duration;
+
+/// Wraps a receive stream and suppresses consecutive equal values
+/// The first value received is always emitted
+#[derive(Debug)]
+pub struct DedupStream<R, M> where R: ReceiveStream, R::RData: PartialEq + Clone, M: Mutex<Inner=Option<R::RData>>{
+    stream: R,
+    last: M,
+}
+impl<R, M> DedupStream<R, M> where R: ReceiveStream, R::RData: PartialEq + Clone, M: Mutex<Inner=Option<R::RData>>{
+    pub fn new(stream: R) -> Self{
+        Self{ stream, last: M::new(None) }
+    }
+
+    pub fn stream(&self) -> &R{
+        &self.stream
+    }
+
+    /// Returns true and stores val if it differs from the last emitted value
+    fn is_new(&self, val: &R::RData) -> bool{
+        self.last.lock(|last|{
+            if last.as_ref() == Some(val){
+                false
+            }
+            else{
+                *last = Some(val.clone());
+                true
+            }
+        })
+    }
+}
+impl<R, M> ReceiveStream for DedupStream<R, M> where R: ReceiveStream, R::RData: PartialEq + Clone, M: Mutex<Inner=Option<R::RData>>{
+    type RData = R::RData;
+
+    /// Returns None if nothing is available or all available values are duplicates
+    fn try_receive(&self) -> Option<Self::RData> {
+        while let Some(val) = self.stream.try_receive(){
+            if self.is_new(&val){
+                return Some(val);
+            }
+        }
+        None
+    }
+
+    fn receive(&self) -> Self::RData {
+        loop {
+            let val = self.stream.receive();
+            if self.is_new(&val){
+                return val;
+            }
+        }
+    }
+}
+impl<R, M> ReceiveTimoutStream for DedupStream<R, M> where R: ReceiveTimoutStream, R::RData: PartialEq + Clone, M: Mutex<Inner=Option<R::RData>>{
+    fn receive_timeout(&self, timeout: Duration, uf: &impl UniversalFunctions) -> Option<Self::RData> {
+        let end_time = uf.system_time() + timeout;
+        loop {
+            let val = self.stream.receive_timeout(end_time.checked_sub(uf.system_time()).unwrap_or_default(), uf)?;
+            if self.is_new(&val){
+                return Some(val);
+            }
+            if uf.system_time() >= end_time{
+                return None;
+            }
+        }
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod test{
+    use v5_traits::stream::std_impls::new_mpsc_channel;
+    use v5_traits::stream::{SendStream, ReceiveStream};
+    use parking_lot::Mutex;
+    use crate::dedup_stream::DedupStream;
+
+    #[test]
+    fn dedup_stream_test(){
+        let (sender, receiver) = new_mpsc_channel();
+        let dedup = DedupStream::<_, Mutex<_>>::new(receiver);
+        sender.send_slice(&[1, 1, 2, 2, 2, 3, 1, 1]);
+
+        assert_eq!(dedup.receive(), 1);
+        assert_eq!(dedup.receive(), 2);
+        assert_eq!(dedup.receive(), 3);
+        assert_eq!(dedup.receive(), 1);
+        assert_eq!(dedup.try_receive(), None);
+    }
+}
@@ -0,0 +1,78 @@
+use v5_traits::stream::{SendStream, SendTimeoutStream, MessageStreamCreator};
+use v5_traits::param_store::ParamKey;
+use v5_traits::mutex::Mutex;
+use v5_traits::UniversalFunctions;
+use core::marker::PhantomData;
+use alloc::vec::Vec;
+use crate::broadcast_stream::{BroadcastStream, BroadcastPolicy};
+
+/// Publishes events to subscribers by topic, each topic is a separate BroadcastStream
+/// Subscribers only receive events published to their topic after they subscribe, every topic uses the same BroadcastPolicy
+pub struct EventBus<UF, K, T, C, M> where UF: UniversalFunctions, K: ParamKey, T: 'static + Send + Clone, C: MessageStreamCreator<T>, M: Mutex<Inner=Vec<C::Sender>>{
+    topics: Vec<BroadcastStream<UF, T, C, M>>,
+    phantom_k: PhantomData<K>,
+}
+impl<UF, K, T, C, M> EventBus<UF, K, T, C, M> where UF: UniversalFunctions + Clone, K: ParamKey, T: 'static + Send + Clone, C: MessageStreamCreator<T> + Clone, M: Mutex<Inner=Vec<C::Sender>>{
+    pub fn new(uf: UF, creator: C, policy: BroadcastPolicy) -> Self{
+        Self{
+            topics: (0..K::COUNT).map(|_| BroadcastStream::new(uf.clone(), creator.clone(), policy)).collect(),
+            phantom_k: Default::default(),
+        }
+    }
+}
+impl<UF, K, T, C, M> EventBus<UF, K, T, C, M> where UF: UniversalFunctions, K: ParamKey, T: 'static + Send + Clone, C: MessageStreamCreator<T>, M: Mutex<Inner=Vec<C::Sender>>{
+    /// Sends event to every subscriber of topic, full subscribers are handled by the bus' BroadcastPolicy
+    pub fn publish(&self, topic: K, event: T) where C::Sender: SendTimeoutStream{
+        self.topics[topic.index()].send(event)
+    }
+
+    pub fn subscribe(&self, topic: K) -> C::Receiver{
+        self.topics[topic.index()].subscribe()
+    }
+
+    pub fn topic(&self, topic: K) -> &BroadcastStream<UF, T, C, M>{
+        &self.topics[topic.index()]
+    }
+}
+/// This ensures that this is sync if possible because no K is actually stored
+unsafe impl<UF, K, T, C, M> Sync for EventBus<UF, K, T, C, M> where UF: UniversalFunctions + Sync, K: ParamKey, T: 'static + Send + Clone, C: MessageStreamCreator<T> + Sync, M: Mutex<Inner=Vec<C::Sender>> + Sync{}
+
+#[cfg(all(test, feature = "std"))]
+mod test{
+    use v5_traits::stream::std_impls::MPSCMessageCreator;
+    use v5_traits::stream::ReceiveStream;
+    use v5_traits::param_store::ParamKey;
+    use parking_lot::Mutex;
+    use crate::event_bus::EventBus;
+    use crate::broadcast_stream::BroadcastPolicy;
+    use crate::test::Uf;
+
+    #[derive(Copy, Clone, Debug)]
+    enum Topic{
+        Drive,
+        Intake,
+    }
+    impl ParamKey for Topic{
+        const COUNT: usize = 2;
+
+        fn index(self) -> usize {
+            self as usize
+        }
+    }
+
+    #[test]
+    fn event_bus_test(){
+        let bus = EventBus::<_, Topic, _, _, Mutex<_>>::new(Uf(), MPSCMessageCreator(), BroadcastPolicy::BlockAll);
+        let drive = bus.subscribe(Topic::Drive);
+        let drive_log = bus.subscribe(Topic::Drive);
+        let intake = bus.subscribe(Topic::Intake);
+        bus.publish(Topic::Drive, 1);
+        bus.publish(Topic::Intake, 2);
+        bus.publish(Topic::Drive, 3);
+
+        assert_eq!(drive.receive_vec(2), vec![1, 3]);
+        assert_eq!(drive_log.receive_vec(2), vec![1, 3]);
+        assert_eq!(intake.receive_vec(1), vec![2]);
+        assert_eq!(intake.try_receive(), None);
+    }
+}
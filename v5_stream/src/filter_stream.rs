@@ -0,0 +1,79 @@
+use v5_traits::stream::{ReceiveStream, ReceiveTimoutStream};
+use v5_traits::UniversalFunctions;
+use core::time::Duration;
+
+/// Receives only the items predicate returns true for, the rest are dropped
+/// For picking the messages meant for one consumer off a shared sensor bus
+#[derive(Debug)]
+pub struct FilterStream<R, P> where R: ReceiveStream, P: Fn(&R::RData) -> bool{
+    stream: R,
+    predicate: P,
+}
+impl<R, P> FilterStream<R, P> where R: ReceiveStream, P: Fn(&R::RData) -> bool{
+    pub fn new(stream: R, predicate: P) -> Self{
+        Self{ stream, predicate }
+    }
+
+    pub fn stream(&self) -> &R{
+        &self.stream
+    }
+}
+impl<R, P> ReceiveStream for FilterStream<R, P> where R: ReceiveStream, P: Fn(&R::RData) -> bool{
+    type RData = R::RData;
+
+    /// Takes at most one item, returns None if nothing is available or the item fails predicate
+    fn try_receive(&self) -> Option<Self::RData> {
+        self.stream.try_receive().filter(&self.predicate)
+    }
+
+    fn receive(&self) -> Self::RData {
+        loop {
+            let val = self.stream.receive();
+            if (self.predicate)(&val){
+                return val;
+            }
+        }
+    }
+}
+impl<R, P> ReceiveTimoutStream for FilterStream<R, P> where R: ReceiveTimoutStream, P: Fn(&R::RData) -> bool{
+    /// Skipped items count against timeout
+    fn receive_timeout(&self, timeout: Duration, uf: &impl UniversalFunctions) -> Option<Self::RData> {
+        let end_time = uf.system_time() + timeout;
+        loop {
+            let val = self.stream.receive_timeout(end_time.checked_sub(uf.system_time()).unwrap_or_default(), uf)?;
+            if (self.predicate)(&val){
+                return Some(val);
+            }
+            if uf.system_time() >= end_time{
+                return None;
+            }
+        }
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod test{
+    use v5_traits::stream::{SendStream, ReceiveStream, ReceiveTimoutStream};
+    use v5_traits::UniversalFunctions;
+    use crate::filter_stream::FilterStream;
+    use crate::test::{Uf, TestQueue};
+    use alloc::sync::Arc;
+    use core::time::Duration;
+
+    #[test]
+    fn filter_stream_test(){
+        let queue = Arc::new(TestQueue::new(8));
+        let evens = FilterStream::new(queue.clone(), |val: &u32| val & 1 == 0);
+        queue.send_slice(&[1, 2, 3, 5, 4, 7]);
+
+        assert_eq!(evens.receive(), 2);
+        assert_eq!(evens.try_receive(), None);
+        assert_eq!(evens.receive_timeout(Duration::from_millis(50), &Uf()), Some(4));
+
+        let start = Uf().system_time();
+        assert_eq!(evens.receive_timeout(Duration::from_millis(50), &Uf()), None);
+        let waited = Uf().system_time() - start;
+        assert!(waited >= Duration::from_millis(50) && waited < Duration::from_millis(200), "Waited {:?}", waited);
+        assert_eq!(queue.try_receive(), None);
+    }
+}
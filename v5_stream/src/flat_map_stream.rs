@@ -0,0 +1,90 @@
+use v5_traits::stream::{ReceiveStream, ReceiveTimoutStream};
+use v5_traits::mutex::Mutex;
+use v5_traits::UniversalFunctions;
+use alloc::collections::VecDeque;
+use core::time::Duration;
+
+/// Expands each received item into many with function and receives them one at a time, like Iterator::flat_map
+/// The expansion of one item is fully received before the next item is pulled from the inner stream
+#[derive(Debug)]
+pub struct FlatMapStream<R, F, I, M> where R: ReceiveStream, F: Fn(R::RData) -> I, I: IntoIterator, I::Item: 'static + Send, M: Mutex<Inner=VecDeque<I::Item>>{
+    stream: R,
+    function: F,
+    buffer: M,
+}
+impl<R, F, I, M> FlatMapStream<R, F, I, M> where R: ReceiveStream, F: Fn(R::RData) -> I, I: IntoIterator, I::Item: 'static + Send, M: Mutex<Inner=VecDeque<I::Item>>{
+    pub fn new(stream: R, function: F) -> Self{
+        Self{ stream, function, buffer: M::new(VecDeque::new()) }
+    }
+
+    pub fn stream(&self) -> &R{
+        &self.stream
+    }
+
+    /// The amount of expanded items waiting to be received
+    pub fn buffered(&self) -> usize{
+        self.buffer.lock(|buffer| buffer.len())
+    }
+}
+impl<R, F, I, M> ReceiveStream for FlatMapStream<R, F, I, M> where R: ReceiveStream, F: Fn(R::RData) -> I, I: IntoIterator, I::Item: 'static + Send, M: Mutex<Inner=VecDeque<I::Item>>{
+    type RData = I::Item;
+
+    fn try_receive(&self) -> Option<Self::RData> {
+        self.buffer.lock(|buffer|{
+            loop {
+                if let Some(val) = buffer.pop_front(){
+                    return Some(val);
+                }
+                buffer.extend((self.function)(self.stream.try_receive()?));
+            }
+        })
+    }
+
+    fn receive(&self) -> Self::RData {
+        self.buffer.lock(|buffer|{
+            loop {
+                if let Some(val) = buffer.pop_front(){
+                    return val;
+                }
+                buffer.extend((self.function)(self.stream.receive()));
+            }
+        })
+    }
+}
+impl<R, F, I, M> ReceiveTimoutStream for FlatMapStream<R, F, I, M> where R: ReceiveTimoutStream, F: Fn(R::RData) -> I, I: IntoIterator, I::Item: 'static + Send, M: Mutex<Inner=VecDeque<I::Item>>{
+    fn receive_timeout(&self, timeout: Duration, uf: &impl UniversalFunctions) -> Option<Self::RData> {
+        let end_time = uf.system_time() + timeout;
+        self.buffer.lock(|buffer|{
+            loop {
+                if let Some(val) = buffer.pop_front(){
+                    return Some(val);
+                }
+                let remaining = end_time.checked_sub(uf.system_time()).unwrap_or_default();
+                buffer.extend((self.function)(self.stream.receive_timeout(remaining, uf)?));
+            }
+        })
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod test{
+    use v5_traits::stream::std_impls::new_mpsc_channel;
+    use v5_traits::stream::{SendStream, ReceiveStream};
+    use parking_lot::Mutex;
+    use crate::flat_map_stream::FlatMapStream;
+    use alloc::vec::Vec;
+
+    #[test]
+    fn flat_map_stream_test(){
+        let (sender, receiver) = new_mpsc_channel::<Vec<u8>>();
+        let records = FlatMapStream::<_, _, _, Mutex<_>>::new(receiver, |frame| frame);
+        sender.send(vec![1, 2, 3]);
+        sender.send(vec![]);
+        sender.send(vec![4]);
+
+        assert_eq!(records.receive(), 1);
+        assert_eq!(records.buffered(), 2);
+        assert_eq!(records.receive_vec(3), vec![2, 3, 4]);
+        assert_eq!(records.try_receive(), None);
+    }
+}
@@ -0,0 +1,135 @@
+use v5_traits::stream::{SendStream, SendTimeoutStream, ReceiveStream, ReceiveTimoutStream};
+use v5_traits::UniversalFunctions;
+use alloc::sync::Arc;
+use core::sync::atomic::{AtomicBool, Ordering};
+use core::time::Duration;
+
+const POLL_INTERVAL: Duration = Duration::from_millis(1);
+
+/// Wraps both ends of a stream so the receiving end can pause the sending end
+/// The ends share a pause flag, sends block while it is set checking it every millisecond,
+/// items already sent when pause is called are still delivered
+pub fn flow_controlled<UF, S, R>(uf: UF, sender: S, receiver: R) -> (FlowControlledSender<UF, S>, FlowControlledReceiver<R>) where UF: UniversalFunctions, S: SendStream, R: ReceiveStream<RData=S::SData>{
+    let paused = Arc::new(AtomicBool::new(false));
+    (FlowControlledSender{ uf, stream: sender, paused: paused.clone() }, FlowControlledReceiver{ stream: receiver, paused })
+}
+
+/// The sending end of flow_controlled
+pub struct FlowControlledSender<UF, S> where UF: UniversalFunctions, S: SendStream{
+    uf: UF,
+    stream: S,
+    paused: Arc<AtomicBool>,
+}
+impl<UF, S> FlowControlledSender<UF, S> where UF: UniversalFunctions, S: SendStream{
+    pub fn stream(&self) -> &S{
+        &self.stream
+    }
+
+    pub fn is_paused(&self) -> bool{
+        self.paused.load(Ordering::Acquire)
+    }
+}
+impl<UF, S> SendStream for FlowControlledSender<UF, S> where UF: UniversalFunctions, S: SendStream{
+    type SData = S::SData;
+
+    fn send(&self, val: Self::SData) {
+        while self.is_paused(){
+            self.uf.delay(POLL_INTERVAL);
+        }
+        self.stream.send(val)
+    }
+}
+impl<UF, S> SendTimeoutStream for FlowControlledSender<UF, S> where UF: UniversalFunctions, S: SendTimeoutStream{
+    /// Time spent paused counts towards timeout
+    fn send_timeout(&self, val: Self::SData, timeout: Duration, uf: &impl UniversalFunctions) -> Option<Self::SData> {
+        let end_time = uf.system_time() + timeout;
+        while self.is_paused(){
+            match end_time.checked_sub(uf.system_time()){
+                None => return Some(val),
+                Some(remaining) => uf.delay(remaining.min(POLL_INTERVAL)),
+            }
+        }
+        self.stream.send_timeout(val, end_time.checked_sub(uf.system_time()).unwrap_or_default(), uf)
+    }
+}
+
+/// The receiving end of flow_controlled
+pub struct FlowControlledReceiver<R> where R: ReceiveStream{
+    stream: R,
+    paused: Arc<AtomicBool>,
+}
+impl<R> FlowControlledReceiver<R> where R: ReceiveStream{
+    pub fn stream(&self) -> &R{
+        &self.stream
+    }
+
+    /// Makes sends block until resume is called
+    pub fn pause(&self){
+        self.paused.store(true, Ordering::Release);
+    }
+
+    pub fn resume(&self){
+        self.paused.store(false, Ordering::Release);
+    }
+
+    pub fn is_paused(&self) -> bool{
+        self.paused.load(Ordering::Acquire)
+    }
+
+    /// Pauses once fill reaches high_water and resumes once it drops to low_water, between the two the state is kept
+    /// Call with the receiver's buffer fill level after each receive
+    pub fn update_fill(&self, fill: usize, high_water: usize, low_water: usize){
+        if fill >= high_water{
+            self.pause();
+        }
+        else if fill <= low_water{
+            self.resume();
+        }
+    }
+}
+impl<R> ReceiveStream for FlowControlledReceiver<R> where R: ReceiveStream{
+    type RData = R::RData;
+
+    fn try_receive(&self) -> Option<Self::RData> {
+        self.stream.try_receive()
+    }
+
+    fn receive(&self) -> Self::RData {
+        self.stream.receive()
+    }
+}
+impl<R> ReceiveTimoutStream for FlowControlledReceiver<R> where R: ReceiveTimoutStream{
+    fn receive_timeout(&self, timeout: Duration, uf: &impl UniversalFunctions) -> Option<Self::RData> {
+        self.stream.receive_timeout(timeout, uf)
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod test{
+    use v5_traits::stream::std_impls::new_mpsc_channel;
+    use v5_traits::stream::{SendStream, SendTimeoutStream, ReceiveStream};
+    use crate::flow_controlled_stream::flow_controlled;
+    use crate::test::Uf;
+    use core::time::Duration;
+
+    #[test]
+    fn flow_controlled_stream_test(){
+        let (sender, receiver) = new_mpsc_channel();
+        let (sender, receiver) = flow_controlled(Uf(), sender, receiver);
+        sender.send(1);
+        receiver.update_fill(3, 3, 1);
+        assert!(sender.is_paused());
+        assert_eq!(sender.send_timeout(2, Duration::from_millis(10), &Uf()), Some(2));
+
+        std::thread::scope(|scope|{
+            scope.spawn(||{
+                std::thread::sleep(Duration::from_millis(10));
+                receiver.update_fill(1, 3, 1);
+            });
+            sender.send(3);
+        });
+        assert!(!receiver.is_paused());
+        assert_eq!(receiver.receive_vec(2), vec![1, 3]);
+        assert_eq!(receiver.try_receive(), None);
+    }
+}
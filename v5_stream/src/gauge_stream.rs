@@ -0,0 +1,119 @@
+use v5_traits::stream::{ReceiveStream, ReceiveTimoutStream};
+use v5_traits::mutex::Mutex;
+use v5_traits::UniversalFunctions;
+use core::time::Duration;
+
+/// Samples a gauge such as a queue's len every period and receives the samples
+/// e.g. GaugeStream::new(uf, || queue.len(), period, true)
+/// Samples are taken on a fixed schedule from creation, with dedup samples equal to the last received one are skipped
+#[derive(Debug)]
+pub struct GaugeStream<UF, F, M> where UF: UniversalFunctions, F: Fn() -> u32, M: Mutex<Inner=GaugeState>{
+    uf: UF,
+    gauge: F,
+    period: Duration,
+    dedup: bool,
+    state: M,
+}
+impl<UF, F, M> GaugeStream<UF, F, M> where UF: UniversalFunctions, F: Fn() -> u32, M: Mutex<Inner=GaugeState>{
+    /// The first sample is taken immediately
+    /// Panics if period is zero, receive would spin sampling the gauge without delaying
+    pub fn new(uf: UF, gauge: F, period: Duration, dedup: bool) -> Self{
+        assert!(period > Duration::default(), "GaugeStream period must not be zero");
+        let next_sample = uf.system_time();
+        Self{ uf, gauge, period, dedup, state: M::new(GaugeState{ next_sample, last: None }) }
+    }
+
+    pub fn period(&self) -> Duration{
+        self.period
+    }
+
+    /// Takes a sample if one is due, returns None if not due or deduped
+    fn sample(&self, state: &mut GaugeState) -> Option<u32>{
+        let now = self.uf.system_time();
+        if now < state.next_sample{
+            return None;
+        }
+        while state.next_sample <= now{
+            state.next_sample += self.period;
+        }
+        let val = (self.gauge)();
+        if self.dedup && state.last == Some(val){
+            return None;
+        }
+        state.last = Some(val);
+        Some(val)
+    }
+}
+impl<UF, F, M> ReceiveStream for GaugeStream<UF, F, M> where UF: UniversalFunctions, F: Fn() -> u32, M: Mutex<Inner=GaugeState>{
+    type RData = u32;
+
+    fn try_receive(&self) -> Option<u32> {
+        self.state.lock(|state| self.sample(state))
+    }
+
+    fn receive(&self) -> u32 {
+        self.state.lock(|state|{
+            loop {
+                if let Some(val) = self.sample(state){
+                    return val;
+                }
+                if let Some(wait) = state.next_sample.checked_sub(self.uf.system_time()){
+                    self.uf.delay(wait);
+                }
+            }
+        })
+    }
+}
+impl<UF, F, M> ReceiveTimoutStream for GaugeStream<UF, F, M> where UF: UniversalFunctions, F: Fn() -> u32, M: Mutex<Inner=GaugeState>{
+    fn receive_timeout(&self, timeout: Duration, uf: &impl UniversalFunctions) -> Option<u32> {
+        let end_time = uf.system_time() + timeout;
+        self.state.lock(|state|{
+            loop {
+                if let Some(val) = self.sample(state){
+                    return Some(val);
+                }
+                let now = uf.system_time();
+                if now >= end_time{
+                    return None;
+                }
+                uf.delay(state.next_sample.min(end_time).checked_sub(now).unwrap_or_default());
+            }
+        })
+    }
+}
+
+/// The sampling schedule of a GaugeStream
+#[derive(Debug)]
+pub struct GaugeState{
+    next_sample: Duration,
+    last: Option<u32>,
+}
+
+#[cfg(all(test, feature = "std"))]
+mod test{
+    use v5_traits::stream::{ReceiveStream, ReceiveTimoutStream};
+    use parking_lot::Mutex;
+    use crate::gauge_stream::GaugeStream;
+    use crate::test::Uf;
+    use core::sync::atomic::{AtomicU32, Ordering};
+    use core::time::Duration;
+
+    #[test]
+    fn gauge_stream_test(){
+        let level = AtomicU32::new(3);
+        let gauge = GaugeStream::<_, _, Mutex<_>>::new(Uf(), || level.load(Ordering::SeqCst), Duration::from_millis(5), true);
+
+        assert_eq!(gauge.receive(), 3);
+        assert_eq!(gauge.try_receive(), None);
+        assert_eq!(gauge.receive_timeout(Duration::from_millis(20), &Uf()), None);
+
+        level.store(7, Ordering::SeqCst);
+        assert_eq!(gauge.receive(), 7);
+    }
+
+    #[test]
+    #[should_panic(expected = "GaugeStream period must not be zero")]
+    fn gauge_stream_zero_period_test(){
+        GaugeStream::<_, _, Mutex<_>>::new(Uf(), || 0, Duration::default(), true);
+    }
+}
@@ -0,0 +1,105 @@
+use v5_traits::stream::{SendStream, SendTimeoutStream};
+use v5_traits::mutex::Mutex;
+use v5_traits::UniversalFunctions;
+use core::time::Duration;
+
+/// Wraps a send stream and injects heartbeat whenever nothing has been sent for interval
+/// Keeps links that time out without traffic alive. Heartbeats are only sent from heartbeat_if_due or run,
+/// real sends hold the same lock as heartbeats and reset the timer so a heartbeat never follows a real send within interval
+#[derive(Debug)]
+pub struct HeartbeatStream<UF, S, M> where UF: UniversalFunctions, S: SendStream, S::SData: Clone, M: Mutex<Inner=Duration>{
+    uf: UF,
+    stream: S,
+    heartbeat: S::SData,
+    interval: Duration,
+    last_send: M,
+}
+impl<UF, S, M> HeartbeatStream<UF, S, M> where UF: UniversalFunctions, S: SendStream, S::SData: Clone, M: Mutex<Inner=Duration>{
+    pub fn new(uf: UF, stream: S, heartbeat: S::SData, interval: Duration) -> Self{
+        let last_send = M::new(uf.system_time());
+        Self{ uf, stream, heartbeat, interval, last_send }
+    }
+
+    pub fn stream(&self) -> &S{
+        &self.stream
+    }
+
+    pub fn interval(&self) -> Duration{
+        self.interval
+    }
+
+    /// Time left until a heartbeat is due, zero if one is due now
+    pub fn time_until_due(&self) -> Duration{
+        let due = self.last_send.lock(|last_send| *last_send) + self.interval;
+        due.checked_sub(self.uf.system_time()).unwrap_or_default()
+    }
+
+    /// Sends a heartbeat if nothing has been sent for interval, returns true if one was sent
+    pub fn heartbeat_if_due(&self) -> bool{
+        self.last_send.lock(|last_send|{
+            let now = self.uf.system_time();
+            if now < *last_send + self.interval{
+                return false;
+            }
+            self.stream.send(self.heartbeat.clone());
+            *last_send = self.uf.system_time();
+            true
+        })
+    }
+
+    /// Sends heartbeats forever, run this on its own task
+    pub fn run(&self) -> !{
+        loop {
+            self.uf.delay(self.time_until_due());
+            self.heartbeat_if_due();
+        }
+    }
+}
+impl<UF, S, M> SendStream for HeartbeatStream<UF, S, M> where UF: UniversalFunctions, S: SendStream, S::SData: Clone, M: Mutex<Inner=Duration>{
+    type SData = S::SData;
+
+    fn send(&self, val: Self::SData) {
+        self.last_send.lock(|last_send|{
+            self.stream.send(val);
+            *last_send = self.uf.system_time();
+        })
+    }
+}
+impl<UF, S, M> SendTimeoutStream for HeartbeatStream<UF, S, M> where UF: UniversalFunctions, S: SendTimeoutStream, S::SData: Clone, M: Mutex<Inner=Duration>{
+    /// Only resets the timer if val was sent
+    fn send_timeout(&self, val: Self::SData, timeout: Duration, uf: &impl UniversalFunctions) -> Option<Self::SData> {
+        self.last_send.lock(|last_send|{
+            let out = self.stream.send_timeout(val, timeout, uf);
+            if out.is_none(){
+                *last_send = self.uf.system_time();
+            }
+            out
+        })
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod test{
+    use v5_traits::stream::std_impls::new_mpsc_channel;
+    use v5_traits::stream::{SendStream, ReceiveStream};
+    use parking_lot::Mutex;
+    use crate::heartbeat_stream::HeartbeatStream;
+    use crate::test::Uf;
+    use core::time::Duration;
+
+    #[test]
+    fn heartbeat_stream_test(){
+        let (sender, receiver) = new_mpsc_channel();
+        let stream = HeartbeatStream::<_, _, Mutex<_>>::new(Uf(), sender, 0, Duration::from_millis(20));
+        assert!(!stream.heartbeat_if_due());
+
+        std::thread::sleep(Duration::from_millis(25));
+        stream.send(5);
+        assert!(!stream.heartbeat_if_due());
+        std::thread::sleep(Duration::from_millis(25));
+        assert!(stream.heartbeat_if_due());
+
+        assert_eq!(receiver.receive_vec(2), vec![5, 0]);
+        assert_eq!(receiver.try_receive(), None);
+    }
+}
@@ -0,0 +1,99 @@
+use v5_traits::stream::{ReceiveStream, ReceiveTimoutStream};
+use v5_traits::mutex::Mutex;
+use v5_traits::UniversalFunctions;
+use core::ops::Sub;
+use core::time::Duration;
+
+/// Wraps a receive stream and suppresses values within threshold of the last emitted value
+/// Like DedupStream for continuous signals, a value is only emitted once it has moved more than threshold away.
+/// The first value received is always emitted
+#[derive(Debug)]
+pub struct HysteresisStream<R, M> where R: ReceiveStream, R::RData: PartialOrd + Copy + Sub<Output=R::RData>, M: Mutex<Inner=Option<R::RData>>{
+    stream: R,
+    threshold: R::RData,
+    last: M,
+}
+impl<R, M> HysteresisStream<R, M> where R: ReceiveStream, R::RData: PartialOrd + Copy + Sub<Output=R::RData>, M: Mutex<Inner=Option<R::RData>>{
+    pub fn new(stream: R, threshold: R::RData) -> Self{
+        Self{ stream, threshold, last: M::new(None) }
+    }
+
+    pub fn stream(&self) -> &R{
+        &self.stream
+    }
+
+    pub fn threshold(&self) -> R::RData{
+        self.threshold
+    }
+
+    /// Returns true and stores val if it is more than threshold from the last emitted value
+    fn is_new(&self, val: R::RData) -> bool{
+        self.last.lock(|last|{
+            let changed = match *last{
+                None => true,
+                Some(last) if val > last => val - last > self.threshold,
+                Some(last) => last - val > self.threshold,
+            };
+            if changed{
+                *last = Some(val);
+            }
+            changed
+        })
+    }
+}
+impl<R, M> ReceiveStream for HysteresisStream<R, M> where R: ReceiveStream, R::RData: PartialOrd + Copy + Sub<Output=R::RData>, M: Mutex<Inner=Option<R::RData>>{
+    type RData = R::RData;
+
+    /// Returns None if nothing is available or all available values are within threshold
+    fn try_receive(&self) -> Option<Self::RData> {
+        while let Some(val) = self.stream.try_receive(){
+            if self.is_new(val){
+                return Some(val);
+            }
+        }
+        None
+    }
+
+    fn receive(&self) -> Self::RData {
+        loop {
+            let val = self.stream.receive();
+            if self.is_new(val){
+                return val;
+            }
+        }
+    }
+}
+impl<R, M> ReceiveTimoutStream for HysteresisStream<R, M> where R: ReceiveTimoutStream, R::RData: PartialOrd + Copy + Sub<Output=R::RData>, M: Mutex<Inner=Option<R::RData>>{
+    fn receive_timeout(&self, timeout: Duration, uf: &impl UniversalFunctions) -> Option<Self::RData> {
+        let end_time = uf.system_time() + timeout;
+        loop {
+            let val = self.stream.receive_timeout(end_time.checked_sub(uf.system_time()).unwrap_or_default(), uf)?;
+            if self.is_new(val){
+                return Some(val);
+            }
+            if uf.system_time() >= end_time{
+                return None;
+            }
+        }
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod test{
+    use v5_traits::stream::std_impls::new_mpsc_channel;
+    use v5_traits::stream::{SendStream, ReceiveStream};
+    use parking_lot::Mutex;
+    use crate::hysteresis_stream::HysteresisStream;
+
+    #[test]
+    fn hysteresis_stream_test(){
+        let (sender, receiver) = new_mpsc_channel();
+        let selector = HysteresisStream::<_, Mutex<_>>::new(receiver, 10);
+        sender.send_slice(&[500, 505, 495, 510, 521, 515, 499]);
+
+        assert_eq!(selector.receive(), 500);
+        assert_eq!(selector.receive(), 521);
+        assert_eq!(selector.receive(), 499);
+        assert_eq!(selector.try_receive(), None);
+    }
+}
@@ -6,12 +6,53 @@ extern crate std;
 #[macro_use]
 extern crate alloc;
 
+pub mod backoff_poll_stream;
+pub mod broadcast_stream;
+pub mod buffered_stream;
 pub mod checksum_byte_stream;
+pub mod checksummed_stream;
+pub mod clamp_stream;
+pub mod coalesce_stream;
+pub mod cobs_stream;
+pub mod compose_stream;
 pub mod composed_stream;
+pub mod deadband_stream;
+pub mod debounce_stream;
+pub mod dedup_stream;
+pub mod event_bus;
+pub mod filter_stream;
+pub mod flat_map_stream;
+pub mod flow_controlled_stream;
+pub mod gauge_stream;
+pub mod heartbeat_stream;
+pub mod hysteresis_stream;
 pub mod identifiable;
+pub mod line_reader;
+pub mod map_stream;
+pub mod mutex_protected_stream;
+pub mod object_pool;
+pub mod policy_stream;
+pub mod priority_queue;
+pub mod reactive_cell;
+pub mod reconnect_stream;
+pub mod request_response;
+pub mod rendezvous;
+pub mod route_stream;
+pub mod scale_stream;
+pub mod selector;
+pub mod sequenced_stream;
 pub mod serialize_stream;
+pub mod serialized_stream;
 pub mod simple_byte_stream;
 pub mod split_stream;
+pub mod tick_merge_stream;
+pub mod timeout_adapter;
+pub mod ttl_queue;
+pub mod urgent_queue;
+pub mod validate_stream;
+pub mod watchdog_stream;
+pub mod weighted_merge_stream;
+pub mod window_stream;
 
 pub struct TaskTracker<T>{
     pub multiplex_inbound: T,
@@ -29,16 +70,17 @@ mod test{
     use ansi_rgb::{Foreground, white, Background, red, yellow, orange, blue};
     use v5_traits::stream::std_impls::MPSCMessageCreator;
     use crate::composed_stream::ComposedStream;
-    use v5_traits::stream::{MessageStreamCreator, SendStream, ReceiveStream};
+    use v5_traits::stream::{MessageStreamCreator, SendStream, ReceiveStream, SendTimeoutStream, ReceiveTimoutStream};
     use alloc::sync::Arc;
     use alloc::vec;
+    use alloc::collections::VecDeque;
     use crate::checksum_byte_stream::{ChecksumByteStream};
     use v5_traits::task::{TaskRunner, TaskFunction};
     use crate::serialize_stream::SerializeStream;
     use parking_lot::Mutex;
 
     #[derive(Debug, Clone)]
-    struct Uf();
+    pub(crate) struct Uf();
     impl UniversalFunctions for Uf{
         fn delay(&self, duration: Duration) {
             sleep(duration)
@@ -82,6 +124,84 @@ mod test{
         }
     }
 
+    /// A bounded in memory queue standing in for a PROS queue in tests
+    #[derive(Debug)]
+    pub(crate) struct TestQueue<T>{
+        items: Mutex<VecDeque<T>>,
+        max_length: usize,
+    }
+    impl<T> TestQueue<T>{
+        pub(crate) fn new(max_length: usize) -> Self{
+            Self{ items: Mutex::new(VecDeque::with_capacity(max_length)), max_length }
+        }
+
+        fn try_send(&self, val: T) -> Option<T>{
+            let mut items = self.items.lock();
+            if items.len() >= self.max_length{
+                Some(val)
+            }
+            else{
+                items.push_back(val);
+                None
+            }
+        }
+    }
+    impl<T> SendStream for TestQueue<T> where T: 'static + Send{
+        type SData = T;
+
+        fn send(&self, val: T) {
+            let mut val = val;
+            while let Some(returned) = self.try_send(val){
+                val = returned;
+                sleep(Duration::from_millis(1));
+            }
+        }
+    }
+    impl<T> SendTimeoutStream for TestQueue<T> where T: 'static + Send{
+        fn send_timeout(&self, val: T, timeout: Duration, uf: &impl UniversalFunctions) -> Option<T> {
+            let end_time = uf.system_time() + timeout;
+            let mut val = val;
+            while let Some(returned) = self.try_send(val){
+                if uf.system_time() >= end_time{
+                    return Some(returned);
+                }
+                val = returned;
+                sleep(Duration::from_millis(1));
+            }
+            None
+        }
+    }
+    impl<T> ReceiveStream for TestQueue<T> where T: 'static + Send{
+        type RData = T;
+
+        fn try_receive(&self) -> Option<T> {
+            self.items.lock().pop_front()
+        }
+
+        fn receive(&self) -> T {
+            loop {
+                if let Some(val) = self.try_receive(){
+                    return val;
+                }
+                sleep(Duration::from_millis(1));
+            }
+        }
+    }
+    impl<T> ReceiveTimoutStream for TestQueue<T> where T: 'static + Send{
+        fn receive_timeout(&self, timeout: Duration, uf: &impl UniversalFunctions) -> Option<T> {
+            let end_time = uf.system_time() + timeout;
+            loop {
+                if let Some(val) = self.try_receive(){
+                    return Some(val);
+                }
+                if uf.system_time() >= end_time{
+                    return None;
+                }
+                sleep(Duration::from_millis(1));
+            }
+        }
+    }
+
     #[test]
     fn checksum_byte_stream_test(){
         let creator = MPSCMessageCreator();
@@ -0,0 +1,134 @@
+use v5_traits::stream::ReceiveStream;
+use v5_traits::mutex::Mutex;
+use v5_traits::error::Error;
+use alloc::vec::Vec;
+use alloc::string::{String, FromUtf8Error};
+use core::mem::take;
+
+/// Splits a byte stream into newline terminated lines
+/// Lines are returned without the newline and all carriage returns are discarded so both \n and \r\n endings work
+#[derive(Debug)]
+pub struct LineReader<R, M> where R: ReceiveStream<RData=u8>, M: Mutex<Inner=LineState>{
+    stream: R,
+    state: M,
+    max_line_length: usize,
+}
+impl<R, M> LineReader<R, M> where R: ReceiveStream<RData=u8>, M: Mutex<Inner=LineState>{
+    /// Lines longer than max_line_length bytes produce a LineError::TooLong and the rest of that line is discarded
+    pub fn new(stream: R, max_line_length: usize) -> Self{
+        Self{ stream, state: M::new(LineState::default()), max_line_length }
+    }
+
+    pub fn stream(&self) -> &R{
+        &self.stream
+    }
+
+    /// Receives a line and decodes it as utf8
+    pub fn receive_string(&self) -> Result<String, LineError>{
+        Ok(String::from_utf8(self.receive()?)?)
+    }
+
+    /// Receives a line if one is completed by the currently available bytes and decodes it as utf8
+    pub fn try_receive_string(&self) -> Option<Result<String, LineError>>{
+        self.try_receive().map(|line| Ok(String::from_utf8(line?)?))
+    }
+}
+impl<R, M> ReceiveStream for LineReader<R, M> where R: ReceiveStream<RData=u8>, M: Mutex<Inner=LineState>{
+    type RData = Result<Vec<u8>, LineError>;
+
+    /// Returns None if the available bytes do not finish a line, they are kept for the next call
+    fn try_receive(&self) -> Option<Self::RData> {
+        self.state.lock(|state|{
+            while let Some(byte) = self.stream.try_receive(){
+                if let Some(line) = state.push(byte, self.max_line_length){
+                    return Some(line);
+                }
+            }
+            None
+        })
+    }
+
+    fn receive(&self) -> Self::RData {
+        self.state.lock(|state|{
+            loop {
+                if let Some(line) = state.push(self.stream.receive(), self.max_line_length){
+                    return line;
+                }
+            }
+        })
+    }
+}
+
+/// The partially received line of a LineReader
+#[derive(Debug, Default)]
+pub struct LineState{
+    buffer: Vec<u8>,
+    overflowed: bool,
+}
+impl LineState{
+    fn push(&mut self, byte: u8, max_line_length: usize) -> Option<Result<Vec<u8>, LineError>>{
+        match byte {
+            b'\n' => if self.overflowed {
+                self.overflowed = false;
+                None
+            }
+            else {
+                Some(Ok(take(&mut self.buffer)))
+            },
+            b'\r' => None,
+            _ if self.overflowed => None,
+            _ if self.buffer.len() >= max_line_length => {
+                self.overflowed = true;
+                self.buffer.clear();
+                Some(Err(LineError::TooLong{ max_line_length }))
+            },
+            _ => {
+                self.buffer.push(byte);
+                None
+            },
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum LineError{
+    TooLong{ max_line_length: usize },
+    InvalidUtf8(FromUtf8Error),
+}
+impl Error for LineError{
+    fn is_recoverable(&self) -> bool {
+        true
+    }
+}
+impl From<FromUtf8Error> for LineError{
+    fn from(from: FromUtf8Error) -> Self {
+        Self::InvalidUtf8(from)
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod test{
+    use v5_traits::stream::std_impls::new_mpsc_channel;
+    use v5_traits::stream::{SendStream, ReceiveStream};
+    use parking_lot::Mutex;
+    use crate::line_reader::{LineReader, LineError};
+
+    #[test]
+    fn line_reader_test(){
+        let (sender, receiver) = new_mpsc_channel();
+        let reader = LineReader::<_, Mutex<_>>::new(receiver, 8);
+        sender.send_slice(b"hello\r\nworld\nthis line is too long\nok\npart");
+
+        assert_eq!(reader.receive_string().unwrap(), "hello");
+        assert_eq!(reader.receive().unwrap(), b"world");
+        match reader.receive(){
+            Err(LineError::TooLong{ max_line_length: 8 }) => {},
+            other => panic!("Expected TooLong, got {:?}", other),
+        }
+        assert_eq!(reader.receive().unwrap(), b"ok");
+        assert!(reader.try_receive().is_none());
+
+        sender.send_slice(b"ial\n");
+        assert_eq!(reader.try_receive_string().unwrap().unwrap(), "partial");
+    }
+}
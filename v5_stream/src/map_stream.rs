@@ -0,0 +1,100 @@
+use v5_traits::stream::{SendStream, SendTimeoutStream, ReceiveStream, ReceiveTimoutStream};
+use v5_traits::UniversalFunctions;
+use core::marker::PhantomData;
+use core::time::Duration;
+
+/// Maps each received item to U with function, for example decoding raw packets into commands
+#[derive(Debug)]
+pub struct MapStream<R, F, U> where R: ReceiveStream, F: Fn(R::RData) -> U, U: 'static + Send{
+    stream: R,
+    function: F,
+}
+impl<R, F, U> MapStream<R, F, U> where R: ReceiveStream, F: Fn(R::RData) -> U, U: 'static + Send{
+    pub fn new(stream: R, function: F) -> Self{
+        Self{ stream, function }
+    }
+
+    pub fn stream(&self) -> &R{
+        &self.stream
+    }
+}
+impl<R, F, U> ReceiveStream for MapStream<R, F, U> where R: ReceiveStream, F: Fn(R::RData) -> U, U: 'static + Send{
+    type RData = U;
+
+    fn try_receive(&self) -> Option<U> {
+        self.stream.try_receive().map(&self.function)
+    }
+
+    fn receive(&self) -> U {
+        (self.function)(self.stream.receive())
+    }
+}
+impl<R, F, U> ReceiveTimoutStream for MapStream<R, F, U> where R: ReceiveTimoutStream, F: Fn(R::RData) -> U, U: 'static + Send{
+    fn receive_timeout(&self, timeout: Duration, uf: &impl UniversalFunctions) -> Option<U> {
+        self.stream.receive_timeout(timeout, uf).map(&self.function)
+    }
+}
+
+/// Maps each item sent as U with function before sending it to the inner stream, for example encoding commands into packets
+#[derive(Debug)]
+pub struct MapSendStream<S, F, U> where S: SendStream, F: Fn(U) -> S::SData, U: 'static + Send{
+    stream: S,
+    function: F,
+    phantom: PhantomData<U>,
+}
+impl<S, F, U> MapSendStream<S, F, U> where S: SendStream, F: Fn(U) -> S::SData, U: 'static + Send{
+    pub fn new(stream: S, function: F) -> Self{
+        Self{ stream, function, phantom: Default::default() }
+    }
+
+    pub fn stream(&self) -> &S{
+        &self.stream
+    }
+}
+/// This ensures that this is sync if possible because no U is actually stored
+unsafe impl<S, F, U> Sync for MapSendStream<S, F, U> where S: SendStream + Sync, F: Fn(U) -> S::SData + Sync, U: 'static + Send{}
+impl<S, F, U> SendStream for MapSendStream<S, F, U> where S: SendStream, F: Fn(U) -> S::SData, U: 'static + Send{
+    type SData = U;
+
+    fn send(&self, val: U) {
+        self.stream.send((self.function)(val))
+    }
+}
+impl<S, F, U> SendTimeoutStream for MapSendStream<S, F, U> where S: SendTimeoutStream, F: Fn(U) -> S::SData, U: 'static + Send + Clone{
+    /// The mapped item can not be mapped back so a clone of val is kept to return on timeout
+    fn send_timeout(&self, val: U, timeout: Duration, uf: &impl UniversalFunctions) -> Option<U> {
+        self.stream.send_timeout((self.function)(val.clone()), timeout, uf).map(|_| val)
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod test{
+    use v5_traits::stream::{SendStream, SendTimeoutStream, ReceiveStream, ReceiveTimoutStream};
+    use crate::map_stream::{MapStream, MapSendStream};
+    use crate::test::{Uf, TestQueue};
+    use alloc::sync::Arc;
+    use core::time::Duration;
+
+    #[test]
+    fn map_stream_test(){
+        let queue = Arc::new(TestQueue::<u32>::new(4));
+        let doubled = MapStream::new(queue.clone(), |val| val * 2);
+        queue.send_slice(&[1, 2, 3]);
+
+        assert_eq!(doubled.receive(), 2);
+        assert_eq!(doubled.receive_timeout(Duration::from_millis(10), &Uf()), Some(4));
+        assert_eq!(doubled.try_receive(), Some(6));
+        assert_eq!(doubled.receive_timeout(Duration::from_millis(10), &Uf()), None);
+    }
+
+    #[test]
+    fn map_send_stream_test(){
+        let queue = Arc::new(TestQueue::<u32>::new(2));
+        let doubling = MapSendStream::new(queue.clone(), |val: u32| val * 2);
+        doubling.send(1);
+        assert_eq!(doubling.send_timeout(2, Duration::from_millis(10), &Uf()), None);
+        assert_eq!(doubling.send_timeout(3, Duration::from_millis(10), &Uf()), Some(3));
+
+        assert_eq!(queue.receive_vec(2), vec![2, 4]);
+    }
+}
@@ -0,0 +1,82 @@
+use v5_traits::stream::{SendStream, SendTimeoutStream};
+use v5_traits::mutex::{Mutex, MutexTimeout};
+use v5_traits::UniversalFunctions;
+use alloc::vec::Vec;
+use core::time::Duration;
+
+/// Makes a send stream that is not sync usable from many tasks by locking it for every send
+/// All senders are serialized, a slow send blocks every other task sending through this until it finishes
+#[derive(Debug)]
+pub struct MutexProtectedStream<S, M> where S: SendStream + Send, M: Mutex<Inner=S>{
+    stream: M,
+}
+impl<S, M> MutexProtectedStream<S, M> where S: SendStream + Send, M: Mutex<Inner=S>{
+    pub fn new(stream: S) -> Self{
+        Self{ stream: M::new(stream) }
+    }
+
+    pub fn into_inner(self) -> S{
+        self.stream.into_inner()
+    }
+}
+impl<S, M> SendStream for MutexProtectedStream<S, M> where S: SendStream + Send, M: Mutex<Inner=S>{
+    type SData = S::SData;
+
+    fn send(&self, val: Self::SData) {
+        self.stream.lock(|stream| stream.send(val))
+    }
+
+    fn send_slice(&self, slice: &[Self::SData]) where Self::SData: Copy {
+        self.stream.lock(|stream| stream.send_slice(slice))
+    }
+
+    fn send_vec(&self, data: Vec<Self::SData>) {
+        self.stream.lock(|stream| stream.send_vec(data))
+    }
+}
+/// The timeout covers waiting for the lock as well as the send
+impl<S, M> SendTimeoutStream for MutexProtectedStream<S, M> where S: SendTimeoutStream + Send, M: MutexTimeout<Inner=S>{
+    fn send_timeout(&self, val: Self::SData, timeout: Duration, uf: &impl UniversalFunctions) -> Option<Self::SData> {
+        let end_time = uf.system_time() + timeout;
+        let mut val = Some(val);
+        let result = self.stream.lock_timeout(timeout, |stream|{
+            let remaining = end_time.checked_sub(uf.system_time()).unwrap_or_default();
+            stream.send_timeout(val.take().unwrap(), remaining, uf)
+        }).ok();
+        match result {
+            Some(returned) => returned,
+            None => val,
+        }
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod test{
+    use v5_traits::stream::std_impls::new_mpsc_channel;
+    use v5_traits::stream::{SendStream, ReceiveStream};
+    use parking_lot::Mutex;
+    use crate::mutex_protected_stream::MutexProtectedStream;
+    use alloc::sync::Arc;
+    use alloc::vec::Vec;
+    use std::thread::spawn;
+
+    #[test]
+    fn mutex_protected_stream_test(){
+        let (sender, receiver) = new_mpsc_channel();
+        let stream = Arc::new(MutexProtectedStream::<_, Mutex<_>>::new(sender));
+
+        let handles: Vec<_> = (0..4).map(|thread|{
+            let stream = stream.clone();
+            spawn(move || stream.send_slice(&[thread * 10; 10]))
+        }).collect();
+        for handle in handles{
+            handle.join().unwrap();
+        }
+
+        let mut received = receiver.receive_vec(40);
+        received.sort_unstable();
+        let mut expected: Vec<_> = (0..4).flat_map(|thread| vec![thread * 10; 10]).collect();
+        expected.sort_unstable();
+        assert_eq!(received, expected);
+    }
+}
@@ -0,0 +1,93 @@
+use v5_traits::stream::{SendStream, ReceiveTimoutStream};
+use v5_traits::UniversalFunctions;
+use alloc::boxed::Box;
+use core::marker::PhantomData;
+use core::ops::{Deref, DerefMut};
+use core::time::Duration;
+
+/// A fixed set of reusable objects handed out through a queue so they are allocated once
+/// On the brain the queue is a Queue<Box<T>>, it must be able to hold every object in the pool
+#[derive(Debug)]
+pub struct ObjectPool<UF, T, Q> where UF: UniversalFunctions, T: 'static + Send, Q: SendStream<SData=Box<T>> + ReceiveTimoutStream<RData=Box<T>>{
+    uf: UF,
+    queue: Q,
+    size: usize,
+    phantom_t: PhantomData<T>,
+}
+impl<UF, T, Q> ObjectPool<UF, T, Q> where UF: UniversalFunctions, T: 'static + Send, Q: SendStream<SData=Box<T>> + ReceiveTimoutStream<RData=Box<T>>{
+    /// Fills queue with size objects made by factory
+    pub fn new(uf: UF, queue: Q, size: usize, mut factory: impl FnMut() -> T) -> Self{
+        for _ in 0..size{
+            queue.send(Box::new(factory()));
+        }
+        Self{ uf, queue, size, phantom_t: Default::default() }
+    }
+
+    /// The amount of objects in the pool including acquired ones
+    pub fn size(&self) -> usize{
+        self.size
+    }
+
+    /// Takes an object from the pool if one is free
+    pub fn try_acquire(&self) -> Option<PooledObject<'_, UF, T, Q>>{
+        self.queue.try_receive().map(|object| PooledObject{ pool: self, object: Some(object) })
+    }
+
+    /// Takes an object from the pool waiting up to timeout for one to be returned if all are in use
+    pub fn acquire(&self, timeout: Duration) -> Option<PooledObject<'_, UF, T, Q>>{
+        self.queue.receive_timeout(timeout, &self.uf).map(|object| PooledObject{ pool: self, object: Some(object) })
+    }
+}
+/// This ensures that this is sync if possible because no T is actually stored
+unsafe impl<UF, T, Q> Sync for ObjectPool<UF, T, Q> where UF: UniversalFunctions, T: 'static + Send, Q: SendStream<SData=Box<T>> + ReceiveTimoutStream<RData=Box<T>> + Sync{}
+
+/// An object taken from an ObjectPool, returned to the pool when dropped
+/// The object keeps whatever state it had, clear it after acquiring if needed
+#[derive(Debug)]
+pub struct PooledObject<'a, UF, T, Q> where UF: UniversalFunctions, T: 'static + Send, Q: SendStream<SData=Box<T>> + ReceiveTimoutStream<RData=Box<T>>{
+    pool: &'a ObjectPool<UF, T, Q>,
+    object: Option<Box<T>>,
+}
+impl<'a, UF, T, Q> Deref for PooledObject<'a, UF, T, Q> where UF: UniversalFunctions, T: 'static + Send, Q: SendStream<SData=Box<T>> + ReceiveTimoutStream<RData=Box<T>>{
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        self.object.as_ref().unwrap()
+    }
+}
+impl<'a, UF, T, Q> DerefMut for PooledObject<'a, UF, T, Q> where UF: UniversalFunctions, T: 'static + Send, Q: SendStream<SData=Box<T>> + ReceiveTimoutStream<RData=Box<T>>{
+    fn deref_mut(&mut self) -> &mut T {
+        self.object.as_mut().unwrap()
+    }
+}
+impl<'a, UF, T, Q> Drop for PooledObject<'a, UF, T, Q> where UF: UniversalFunctions, T: 'static + Send, Q: SendStream<SData=Box<T>> + ReceiveTimoutStream<RData=Box<T>>{
+    fn drop(&mut self) {
+        if let Some(object) = self.object.take(){
+            self.pool.queue.send(object);
+        }
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod test{
+    use crate::object_pool::ObjectPool;
+    use crate::test::{Uf, TestQueue};
+    use alloc::vec::Vec;
+    use core::time::Duration;
+
+    #[test]
+    fn object_pool_test(){
+        let pool = ObjectPool::new(Uf(), TestQueue::new(2), 2, || Vec::<u8>::with_capacity(64));
+        let mut first = pool.acquire(Duration::from_millis(10)).unwrap();
+        first.extend_from_slice(b"abc");
+        let first_pointer = first.as_ptr();
+        let second = pool.try_acquire().unwrap();
+        assert!(pool.acquire(Duration::from_millis(10)).is_none());
+
+        drop(first);
+        drop(second);
+        let reused = pool.try_acquire().unwrap();
+        assert_eq!(reused.as_ptr(), first_pointer);
+        assert_eq!(reused.as_slice(), b"abc");
+    }
+}
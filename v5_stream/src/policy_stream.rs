@@ -0,0 +1,144 @@
+use v5_traits::stream::{SendStream, SendTimeoutStream, ReceiveStream, ReceiveTimoutStream};
+use v5_traits::UniversalFunctions;
+use alloc::vec::Vec;
+use core::sync::atomic::{AtomicUsize, Ordering};
+use core::time::Duration;
+
+/// Decides what happens when sending into a full stream
+/// The stream must be able to receive from itself so that old items can be removed
+pub trait DropPolicy{
+    /// Sends val into stream, returns the amount of items dropped to do so
+    fn send<S>(&self, stream: &S, val: S::SData, uf: &impl UniversalFunctions) -> usize where S: SendTimeoutStream + ReceiveStream<RData=<S as SendStream>::SData>;
+}
+
+/// Waits for space in the stream, never drops
+#[derive(Copy, Clone, Debug, Default)]
+pub struct Block;
+impl DropPolicy for Block{
+    fn send<S>(&self, stream: &S, val: S::SData, _uf: &impl UniversalFunctions) -> usize where S: SendTimeoutStream + ReceiveStream<RData=<S as SendStream>::SData> {
+        stream.send(val);
+        0
+    }
+}
+
+/// Drops the item being sent if the stream is full
+#[derive(Copy, Clone, Debug, Default)]
+pub struct DropNewest;
+impl DropPolicy for DropNewest{
+    fn send<S>(&self, stream: &S, val: S::SData, uf: &impl UniversalFunctions) -> usize where S: SendTimeoutStream + ReceiveStream<RData=<S as SendStream>::SData> {
+        match stream.send_timeout(val, Duration::default(), uf){
+            None => 0,
+            Some(_) => 1,
+        }
+    }
+}
+
+/// Drops items from the front of the stream until the item being sent fits
+#[derive(Copy, Clone, Debug, Default)]
+pub struct DropOldest;
+impl DropPolicy for DropOldest{
+    fn send<S>(&self, stream: &S, val: S::SData, uf: &impl UniversalFunctions) -> usize where S: SendTimeoutStream + ReceiveStream<RData=<S as SendStream>::SData> {
+        let mut dropped = 0;
+        let mut val = val;
+        while let Some(returned) = stream.send_timeout(val, Duration::default(), uf){
+            val = returned;
+            if stream.try_receive().is_some(){
+                dropped += 1;
+            }
+        }
+        dropped
+    }
+}
+
+/// Sends into a bounded stream using a DropPolicy when it is full
+/// Receives are forwarded unchanged
+#[derive(Debug)]
+pub struct PolicyStream<UF, S, P> where UF: UniversalFunctions, S: SendTimeoutStream + ReceiveStream<RData=<S as SendStream>::SData>, P: DropPolicy{
+    uf: UF,
+    stream: S,
+    policy: P,
+    dropped: AtomicUsize,
+}
+impl<UF, S, P> PolicyStream<UF, S, P> where UF: UniversalFunctions, S: SendTimeoutStream + ReceiveStream<RData=<S as SendStream>::SData>, P: DropPolicy{
+    pub fn new(uf: UF, stream: S, policy: P) -> Self{
+        Self{ uf, stream, policy, dropped: AtomicUsize::new(0) }
+    }
+
+    pub fn stream(&self) -> &S{
+        &self.stream
+    }
+
+    pub fn policy(&self) -> &P{
+        &self.policy
+    }
+
+    /// The amount of items dropped by the policy so far
+    pub fn dropped_count(&self) -> usize{
+        self.dropped.load(Ordering::SeqCst)
+    }
+}
+impl<UF, S, P> SendStream for PolicyStream<UF, S, P> where UF: UniversalFunctions, S: SendTimeoutStream + ReceiveStream<RData=<S as SendStream>::SData>, P: DropPolicy{
+    type SData = S::SData;
+
+    fn send(&self, val: Self::SData) {
+        let dropped = self.policy.send(&self.stream, val, &self.uf);
+        if dropped > 0{
+            self.dropped.fetch_add(dropped, Ordering::SeqCst);
+        }
+    }
+}
+impl<UF, S, P> ReceiveStream for PolicyStream<UF, S, P> where UF: UniversalFunctions, S: SendTimeoutStream + ReceiveStream<RData=<S as SendStream>::SData>, P: DropPolicy{
+    type RData = S::RData;
+
+    fn try_receive(&self) -> Option<Self::RData> {
+        self.stream.try_receive()
+    }
+
+    fn receive(&self) -> Self::RData {
+        self.stream.receive()
+    }
+
+    fn receive_slice(&self, buffer: &mut [Self::RData]) -> usize {
+        self.stream.receive_slice(buffer)
+    }
+
+    fn receive_all(&self, buffer: &mut [Self::RData]) {
+        self.stream.receive_all(buffer)
+    }
+
+    fn receive_vec(&self, limit: usize) -> Vec<Self::RData> {
+        self.stream.receive_vec(limit)
+    }
+}
+impl<UF, S, P> ReceiveTimoutStream for PolicyStream<UF, S, P> where UF: UniversalFunctions, S: SendTimeoutStream + ReceiveTimoutStream<RData=<S as SendStream>::SData>, P: DropPolicy{
+    fn receive_timeout(&self, timeout: Duration, uf: &impl UniversalFunctions) -> Option<Self::RData> {
+        self.stream.receive_timeout(timeout, uf)
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod test{
+    use v5_traits::stream::{SendStream, ReceiveStream};
+    use crate::policy_stream::{PolicyStream, DropNewest, DropOldest};
+    use crate::test::{Uf, TestQueue};
+
+    #[test]
+    fn drop_newest_test(){
+        let stream = PolicyStream::new(Uf(), TestQueue::new(2), DropNewest);
+        stream.send_slice(&[1, 2, 3, 4]);
+
+        assert_eq!(stream.dropped_count(), 2);
+        assert_eq!(stream.receive_vec(2), vec![1, 2]);
+        assert_eq!(stream.try_receive(), None);
+    }
+
+    #[test]
+    fn drop_oldest_test(){
+        let stream = PolicyStream::new(Uf(), TestQueue::new(2), DropOldest);
+        stream.send_slice(&[1, 2, 3, 4]);
+
+        assert_eq!(stream.dropped_count(), 2);
+        assert_eq!(stream.receive_vec(2), vec![3, 4]);
+        assert_eq!(stream.try_receive(), None);
+    }
+}
@@ -0,0 +1,207 @@
+use v5_traits::stream::{SendStream, SendTimeoutStream, ReceiveStream, ReceiveTimoutStream};
+use v5_traits::mutex::Mutex;
+use v5_traits::UniversalFunctions;
+use alloc::vec::Vec;
+use core::time::Duration;
+use core::marker::PhantomData;
+
+const POLL_INTERVAL: Duration = Duration::from_millis(1);
+
+/// An item stored in a PriorityQueue along with its priority and enqueue time
+#[derive(Debug)]
+pub struct PriorityEntry<T>{
+    priority: u32,
+    enqueued: Duration,
+    item: T,
+}
+
+/// A bounded queue that receives the highest priority item first, ties are received in FIFO order
+/// Higher numbers are higher priority
+/// If aging_threshold is Some an item's priority is raised by one for every aging_threshold it has waited,
+/// this keeps low priority items from being starved by a steady stream of high priority items
+/// Blocking operations poll every millisecond
+#[derive(Debug)]
+pub struct PriorityQueue<UF, T, M> where UF: UniversalFunctions, T: 'static + Send, M: Mutex<Inner=Vec<PriorityEntry<T>>>{
+    uf: UF,
+    entries: M,
+    max_length: usize,
+    aging_threshold: Option<Duration>,
+    phantom_t: PhantomData<T>,
+}
+impl<UF, T, M> PriorityQueue<UF, T, M> where UF: UniversalFunctions, T: 'static + Send, M: Mutex<Inner=Vec<PriorityEntry<T>>>{
+    /// Creates a queue that can store up to max_length items
+    /// Aging is disabled if aging_threshold is None
+    pub fn new(uf: UF, max_length: usize, aging_threshold: Option<Duration>) -> Self{
+        Self{ uf, entries: M::new(Vec::with_capacity(max_length)), max_length, aging_threshold, phantom_t: Default::default() }
+    }
+
+    /// Adds an item with a priority
+    /// Will wait up to timeout for a spot in the queue
+    /// Returns Ok if sent or Err if queue full and timeout reached
+    pub fn append(&self, item: T, priority: u32, timeout: Option<Duration>) -> Result<(), T>{
+        let end_time = timeout.map(|timeout| self.uf.system_time() + timeout);
+        let mut item = item;
+        loop {
+            item = match self.try_append(item, priority){
+                Ok(()) => return Ok(()),
+                Err(item) => item,
+            };
+            if let Some(end_time) = end_time{
+                if self.uf.system_time() >= end_time{
+                    return Err(item);
+                }
+            }
+            self.uf.delay(POLL_INTERVAL);
+        }
+    }
+
+    fn try_append(&self, item: T, priority: u32) -> Result<(), T>{
+        let enqueued = self.uf.system_time();
+        self.entries.lock(|entries|{
+            if entries.len() >= self.max_length{
+                Err(item)
+            }
+            else{
+                entries.push(PriorityEntry{ priority, enqueued, item });
+                Ok(())
+            }
+        })
+    }
+
+    /// Pulls the highest priority item out of the queue
+    /// Returns Some if item pulled or None if timeout reached
+    pub fn queue_receive(&self, timeout: Option<Duration>) -> Option<T>{
+        let end_time = timeout.map(|timeout| self.uf.system_time() + timeout);
+        loop {
+            if let Some(item) = self.try_pop(){
+                return Some(item);
+            }
+            if let Some(end_time) = end_time{
+                if self.uf.system_time() >= end_time{
+                    return None;
+                }
+            }
+            self.uf.delay(POLL_INTERVAL);
+        }
+    }
+
+    fn try_pop(&self) -> Option<T>{
+        let now = self.uf.system_time();
+        self.entries.lock(|entries|{
+            let mut best: Option<(usize, u64)> = None;
+            for (index, entry) in entries.iter().enumerate(){
+                let priority = self.effective_priority(entry, now);
+                match best {
+                    Some((_, best_priority)) if priority <= best_priority => {},
+                    _ => best = Some((index, priority)),
+                }
+            }
+            best.map(|(index, _)| entries.remove(index).item)
+        })
+    }
+
+    fn effective_priority(&self, entry: &PriorityEntry<T>, now: Duration) -> u64{
+        match self.aging_threshold {
+            None => entry.priority as u64,
+            Some(threshold) => {
+                let waited = now.checked_sub(entry.enqueued).unwrap_or_default();
+                entry.priority as u64 + (waited.as_nanos() / threshold.as_nanos().max(1)) as u64
+            },
+        }
+    }
+
+    /// The amount of items in the queue
+    pub fn len(&self) -> usize{
+        self.entries.lock(|entries| entries.len())
+    }
+    pub fn is_empty(&self) -> bool{
+        self.len() == 0
+    }
+    /// The maximum items this queue can store
+    pub fn max_len(&self) -> usize{
+        self.max_length
+    }
+    pub fn aging_threshold(&self) -> Option<Duration>{
+        self.aging_threshold
+    }
+
+    /// Clears all items from the queue dropping each
+    pub fn clear(&self){
+        self.entries.lock(|entries| entries.clear())
+    }
+}
+/// This ensures that this is sync if possible because T is only stored inside M
+unsafe impl<UF, T, M> Sync for PriorityQueue<UF, T, M> where UF: UniversalFunctions, T: 'static + Send, M: Mutex<Inner=Vec<PriorityEntry<T>>>{}
+/// Sends (priority, item)
+impl<UF, T, M> SendStream for PriorityQueue<UF, T, M> where UF: UniversalFunctions, T: 'static + Send, M: Mutex<Inner=Vec<PriorityEntry<T>>>{
+    type SData = (u32, T);
+
+    fn send(&self, val: (u32, T)) {
+        match self.append(val.1, val.0, None){
+            Ok(_) => {},
+            Err(_) => unreachable!(),
+        }
+    }
+}
+impl<UF, T, M> SendTimeoutStream for PriorityQueue<UF, T, M> where UF: UniversalFunctions, T: 'static + Send, M: Mutex<Inner=Vec<PriorityEntry<T>>>{
+    fn send_timeout(&self, val: (u32, T), timeout: Duration, _uf: &impl UniversalFunctions) -> Option<(u32, T)> {
+        match self.append(val.1, val.0, Some(timeout)){
+            Ok(_) => None,
+            Err(error) => Some((val.0, error)),
+        }
+    }
+}
+impl<UF, T, M> ReceiveStream for PriorityQueue<UF, T, M> where UF: UniversalFunctions, T: 'static + Send, M: Mutex<Inner=Vec<PriorityEntry<T>>>{
+    type RData = T;
+
+    fn try_receive(&self) -> Option<T> {
+        self.try_pop()
+    }
+
+    fn receive(&self) -> T {
+        match self.queue_receive(None){
+            None => unreachable!("PriorityQueue returned none with no timeout"),
+            Some(val) => val,
+        }
+    }
+}
+impl<UF, T, M> ReceiveTimoutStream for PriorityQueue<UF, T, M> where UF: UniversalFunctions, T: 'static + Send, M: Mutex<Inner=Vec<PriorityEntry<T>>>{
+    fn receive_timeout(&self, timeout: Duration, _uf: &impl UniversalFunctions) -> Option<T> {
+        self.queue_receive(Some(timeout))
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod test{
+    use v5_traits::stream::{SendStream, SendTimeoutStream, ReceiveStream};
+    use core::time::Duration;
+    use std::thread::sleep;
+    use parking_lot::Mutex;
+    use crate::priority_queue::PriorityQueue;
+    use crate::test::Uf;
+
+    #[test]
+    fn priority_queue_test(){
+        let queue = PriorityQueue::<_, _, Mutex<_>>::new(Uf(), 3, None);
+        queue.send((0, 'a'));
+        queue.send((10, 'b'));
+        queue.send((10, 'c'));
+        assert_eq!(queue.send_timeout((20, 'd'), Duration::from_millis(5), &Uf()), Some((20, 'd')));
+
+        assert_eq!(queue.receive(), 'b');
+        assert_eq!(queue.receive(), 'c');
+        assert_eq!(queue.receive(), 'a');
+        assert_eq!(queue.try_receive(), None);
+    }
+
+    #[test]
+    fn priority_queue_aging_test(){
+        let queue = PriorityQueue::<_, _, Mutex<_>>::new(Uf(), 10, Some(Duration::from_millis(10)));
+        queue.send((0, "routine"));
+        sleep(Duration::from_millis(35));
+        queue.send((2, "estop"));
+
+        assert_eq!(queue.receive(), "routine");
+        assert_eq!(queue.receive(), "estop");
+    }
+}
@@ -0,0 +1,121 @@
+use v5_traits::stream::{ReceiveStream, ReceiveTimoutStream};
+use v5_traits::sync_cell::SyncCell;
+use v5_traits::mutex::Mutex;
+use v5_traits::UniversalFunctions;
+use alloc::boxed::Box;
+use alloc::sync::{Arc, Weak};
+use alloc::vec::Vec;
+use core::time::Duration;
+
+const POLL_INTERVAL: Duration = Duration::from_millis(1);
+
+/// A value that can be read and set from any task and notifies subscribers of each change
+/// Each subscriber has room for one pending change, a change that arrives before the last was received replaces it
+/// so set never blocks and subscribers only see the latest value
+pub struct ReactiveCell<UF, T, M> where UF: UniversalFunctions, T: 'static + Clone + Send, M: Mutex<Inner=Vec<Weak<SyncCell<T>>>>{
+    uf: UF,
+    value: SyncCell<T>,
+    subscribers: M,
+}
+impl<UF, T, M> ReactiveCell<UF, T, M> where UF: UniversalFunctions, T: 'static + Clone + Send, M: Mutex<Inner=Vec<Weak<SyncCell<T>>>>{
+    pub fn new(uf: UF, value: T) -> Self{
+        Self{ uf, value: SyncCell::from(value), subscribers: M::new(Vec::new()) }
+    }
+
+    pub fn get(&self) -> T{
+        self.value.clone_inner().expect("ReactiveCell value is never emptied")
+    }
+
+    /// Stores val and sends it to every subscriber, replacing any change they have not received
+    pub fn set(&self, val: T){
+        self.value.swap(Some(Box::new(val.clone())));
+        self.subscribers.lock(|subscribers|{
+            subscribers.retain(|subscriber| match subscriber.upgrade(){
+                None => false,
+                Some(subscriber) => {
+                    subscriber.swap(Some(Box::new(val.clone())));
+                    true
+                },
+            });
+        });
+    }
+
+    /// Returns a stream that receives the value after each set from now on
+    pub fn subscribe(&self) -> Subscription<UF, T>{
+        let pending = Arc::new(SyncCell::default());
+        self.subscribers.lock(|subscribers| subscribers.push(Arc::downgrade(&pending)));
+        Subscription{ uf: self.uf.clone(), pending }
+    }
+
+    /// The amount of subscriptions that have not been dropped, dropped ones are cleaned up on the next set
+    pub fn subscriber_count(&self) -> usize{
+        self.subscribers.lock(|subscribers| subscribers.iter().filter(|subscriber| subscriber.strong_count() > 0).count())
+    }
+}
+
+/// A subscription to a ReactiveCell, blocking receives check for a change every millisecond
+/// Dropping it unsubscribes
+pub struct Subscription<UF, T> where UF: UniversalFunctions, T: 'static + Clone + Send{
+    uf: UF,
+    pending: Arc<SyncCell<T>>,
+}
+impl<UF, T> ReceiveStream for Subscription<UF, T> where UF: UniversalFunctions, T: 'static + Clone + Send{
+    type RData = T;
+
+    fn try_receive(&self) -> Option<Self::RData> {
+        self.pending.swap(None).map(|val| *val)
+    }
+
+    fn receive(&self) -> Self::RData {
+        loop {
+            if let Some(val) = self.try_receive(){
+                return val;
+            }
+            self.uf.delay(POLL_INTERVAL);
+        }
+    }
+}
+impl<UF, T> ReceiveTimoutStream for Subscription<UF, T> where UF: UniversalFunctions, T: 'static + Clone + Send{
+    fn receive_timeout(&self, timeout: Duration, uf: &impl UniversalFunctions) -> Option<Self::RData> {
+        let end_time = uf.system_time() + timeout;
+        loop {
+            if let Some(val) = self.try_receive(){
+                return Some(val);
+            }
+            let remaining = end_time.checked_sub(uf.system_time())?;
+            uf.delay(remaining.min(POLL_INTERVAL));
+        }
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod test{
+    use v5_traits::stream::{ReceiveStream, ReceiveTimoutStream};
+    use parking_lot::Mutex;
+    use crate::reactive_cell::ReactiveCell;
+    use crate::test::Uf;
+    use core::time::Duration;
+
+    #[test]
+    fn reactive_cell_test(){
+        let gain = ReactiveCell::<_, _, Mutex<_>>::new(Uf(), 1.0);
+        let drive = gain.subscribe();
+        let arm = gain.subscribe();
+        gain.set(2.0);
+        gain.set(3.0);
+
+        assert_eq!(gain.get(), 3.0);
+        assert_eq!(drive.receive(), 3.0);
+        assert_eq!(drive.try_receive(), None);
+        drop(arm);
+        assert_eq!(gain.subscriber_count(), 1);
+
+        std::thread::scope(|scope|{
+            scope.spawn(||{
+                std::thread::sleep(Duration::from_millis(10));
+                gain.set(4.0);
+            });
+            assert_eq!(drive.receive_timeout(Duration::from_millis(500), &Uf()), Some(4.0));
+        });
+    }
+}
@@ -0,0 +1,183 @@
+use v5_traits::stream::{SendStream, SendTimeoutStream, ReceiveStream, ReceiveTimoutStream};
+use v5_traits::mutex::Mutex;
+use v5_traits::UniversalFunctions;
+use alloc::sync::Arc;
+use core::sync::atomic::{AtomicUsize, Ordering};
+use core::time::Duration;
+
+/// Rebuilds the inner stream with factory whenever it looks broken
+/// Streams have no error reporting so failure is detected through timeouts:
+/// a send that can not complete within timeout or a receive that gets nothing for timeout drops the inner stream.
+/// The next operation calls factory to make a new one, retrying with exponential backoff from initial_backoff up to max_backoff while factory returns None.
+/// Receive failure detection only suits links that are expected to carry data at least every timeout, such as a coprocessor sending status
+#[derive(Debug)]
+pub struct ReconnectStream<UF, F, S, M> where UF: UniversalFunctions, F: Fn() -> Option<S>, S: 'static + Send + Sync, M: Mutex<Inner=Option<Arc<S>>>{
+    uf: UF,
+    factory: F,
+    timeout: Duration,
+    initial_backoff: Duration,
+    max_backoff: Duration,
+    stream: M,
+    connections: AtomicUsize,
+}
+impl<UF, F, S, M> ReconnectStream<UF, F, S, M> where UF: UniversalFunctions, F: Fn() -> Option<S>, S: 'static + Send + Sync, M: Mutex<Inner=Option<Arc<S>>>{
+    /// The inner stream is first created on the first operation
+    pub fn new(uf: UF, factory: F, timeout: Duration, initial_backoff: Duration, max_backoff: Duration) -> Self{
+        Self{ uf, factory, timeout, initial_backoff, max_backoff, stream: M::new(None), connections: AtomicUsize::new(0) }
+    }
+
+    /// The amount of times the inner stream was rebuilt after the first was created
+    pub fn reconnect_count(&self) -> usize{
+        self.connections.load(Ordering::SeqCst).saturating_sub(1)
+    }
+
+    /// True if an inner stream is currently held
+    pub fn is_connected(&self) -> bool{
+        self.stream.lock(|stream| stream.is_some())
+    }
+
+    fn try_connect(&self) -> Option<Arc<S>>{
+        self.stream.lock(|stream|{
+            if stream.is_none(){
+                if let Some(new) = (self.factory)(){
+                    *stream = Some(Arc::new(new));
+                    self.connections.fetch_add(1, Ordering::SeqCst);
+                }
+            }
+            stream.clone()
+        })
+    }
+
+    /// Gets the inner stream connecting if needed, gives up at deadline if given
+    fn connect(&self, deadline: Option<Duration>) -> Option<Arc<S>>{
+        let mut backoff = self.initial_backoff;
+        loop {
+            if let Some(stream) = self.try_connect(){
+                return Some(stream);
+            }
+            let wait = match deadline {
+                None => backoff,
+                Some(deadline) => backoff.min(deadline.checked_sub(self.uf.system_time())?),
+            };
+            self.uf.delay(wait);
+            backoff = (backoff * 2).min(self.max_backoff);
+        }
+    }
+
+    /// Drops failed if it is still the inner stream so only one failure is handled per stream
+    fn disconnect(&self, failed: &Arc<S>){
+        self.stream.lock(|stream|{
+            if matches!(stream, Some(current) if Arc::ptr_eq(current, failed)){
+                *stream = None;
+            }
+        })
+    }
+}
+impl<UF, F, S, M> SendStream for ReconnectStream<UF, F, S, M> where UF: UniversalFunctions, F: Fn() -> Option<S>, S: 'static + Send + Sync + SendTimeoutStream, M: Mutex<Inner=Option<Arc<S>>>{
+    type SData = S::SData;
+
+    fn send(&self, val: Self::SData) {
+        let mut val = val;
+        loop {
+            let stream = self.connect(None).unwrap();
+            match stream.send_timeout(val, self.timeout, &self.uf){
+                None => return,
+                Some(returned) => {
+                    val = returned;
+                    self.disconnect(&stream);
+                },
+            }
+        }
+    }
+}
+impl<UF, F, S, M> SendTimeoutStream for ReconnectStream<UF, F, S, M> where UF: UniversalFunctions, F: Fn() -> Option<S>, S: 'static + Send + Sync + SendTimeoutStream, M: Mutex<Inner=Option<Arc<S>>>{
+    /// Only a send that waited the full inner timeout counts as a failure, running out of the given timeout does not
+    fn send_timeout(&self, val: Self::SData, timeout: Duration, uf: &impl UniversalFunctions) -> Option<Self::SData> {
+        let deadline = uf.system_time() + timeout;
+        let mut val = val;
+        loop {
+            let stream = match self.connect(Some(deadline)){
+                None => return Some(val),
+                Some(stream) => stream,
+            };
+            let remaining = deadline.checked_sub(uf.system_time()).unwrap_or_default();
+            match stream.send_timeout(val, remaining.min(self.timeout), uf){
+                None => return None,
+                Some(returned) => {
+                    val = returned;
+                    if remaining < self.timeout{
+                        return Some(val);
+                    }
+                    self.disconnect(&stream);
+                },
+            }
+        }
+    }
+}
+impl<UF, F, S, M> ReceiveStream for ReconnectStream<UF, F, S, M> where UF: UniversalFunctions, F: Fn() -> Option<S>, S: 'static + Send + Sync + ReceiveTimoutStream, M: Mutex<Inner=Option<Arc<S>>>{
+    type RData = S::RData;
+
+    /// Calls factory at most once if not connected, an empty inner stream is not a failure here
+    fn try_receive(&self) -> Option<Self::RData> {
+        self.try_connect()?.try_receive()
+    }
+
+    fn receive(&self) -> Self::RData {
+        loop {
+            let stream = self.connect(None).unwrap();
+            match stream.receive_timeout(self.timeout, &self.uf){
+                Some(val) => return val,
+                None => self.disconnect(&stream),
+            }
+        }
+    }
+}
+impl<UF, F, S, M> ReceiveTimoutStream for ReconnectStream<UF, F, S, M> where UF: UniversalFunctions, F: Fn() -> Option<S>, S: 'static + Send + Sync + ReceiveTimoutStream, M: Mutex<Inner=Option<Arc<S>>>{
+    /// Only a receive that waited the full inner timeout counts as a failure, running out of the given timeout does not
+    fn receive_timeout(&self, timeout: Duration, uf: &impl UniversalFunctions) -> Option<Self::RData> {
+        let deadline = uf.system_time() + timeout;
+        loop {
+            let stream = self.connect(Some(deadline))?;
+            let remaining = deadline.checked_sub(uf.system_time()).unwrap_or_default();
+            match stream.receive_timeout(remaining.min(self.timeout), uf){
+                Some(val) => return Some(val),
+                None => {
+                    if remaining < self.timeout{
+                        return None;
+                    }
+                    self.disconnect(&stream);
+                },
+            }
+        }
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod test{
+    use v5_traits::stream::{SendStream, ReceiveStream, ReceiveTimoutStream};
+    use parking_lot::Mutex;
+    use crate::reconnect_stream::ReconnectStream;
+    use crate::test::{Uf, TestQueue};
+    use core::sync::atomic::{AtomicUsize, Ordering};
+    use core::time::Duration;
+
+    #[test]
+    fn reconnect_stream_test(){
+        let attempts = AtomicUsize::new(0);
+        let factory = ||{
+            // Every other attempt fails to exercise the backoff
+            if attempts.fetch_add(1, Ordering::SeqCst) & 1 == 0 { None } else { Some(TestQueue::new(1)) }
+        };
+        let stream = ReconnectStream::<_, _, _, Mutex<_>>::new(Uf(), factory, Duration::from_millis(5), Duration::from_millis(1), Duration::from_millis(4));
+
+        stream.send(1);
+        assert_eq!(stream.reconnect_count(), 0);
+        stream.send(2);
+        assert_eq!(stream.reconnect_count(), 1);
+        assert_eq!(stream.receive(), 2);
+
+        assert_eq!(stream.receive_timeout(Duration::from_millis(2), &Uf()), None);
+        assert!(stream.is_connected());
+        assert_eq!(attempts.load(Ordering::SeqCst), 4);
+    }
+}
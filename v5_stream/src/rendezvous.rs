@@ -0,0 +1,104 @@
+use v5_traits::stream::{SendStream, SendTimeoutStream, ReceiveStream, ReceiveTimoutStream};
+use v5_traits::mutex::Mutex;
+use v5_traits::UniversalFunctions;
+use core::time::Duration;
+
+/// A zero capacity channel, sending blocks until a receiver has taken the item
+/// On the brain items and acks are both Queue::new(1).
+/// A sender puts its item in items and then waits for an ack, a receiver takes an item and immediately sends an ack.
+/// Senders hold a lock for the whole handoff so an ack always belongs to the only item in flight.
+/// Sending and receiving on the same task deadlocks, a timed out send takes its item back out of items so it is never received late
+#[derive(Debug)]
+pub struct Rendezvous<Q, A, M> where Q: SendTimeoutStream + ReceiveTimoutStream<RData=<Q as SendStream>::SData>, A: SendStream<SData=()> + ReceiveTimoutStream<RData=()>, M: Mutex<Inner=()>{
+    items: Q,
+    acks: A,
+    send_lock: M,
+}
+impl<Q, A, M> Rendezvous<Q, A, M> where Q: SendTimeoutStream + ReceiveTimoutStream<RData=<Q as SendStream>::SData>, A: SendStream<SData=()> + ReceiveTimoutStream<RData=()>, M: Mutex<Inner=()>{
+    /// items and acks must be empty
+    pub fn new(items: Q, acks: A) -> Self{
+        Self{ items, acks, send_lock: M::new(()) }
+    }
+}
+impl<Q, A, M> SendStream for Rendezvous<Q, A, M> where Q: SendTimeoutStream + ReceiveTimoutStream<RData=<Q as SendStream>::SData>, A: SendStream<SData=()> + ReceiveTimoutStream<RData=()>, M: Mutex<Inner=()>{
+    type SData = Q::SData;
+
+    fn send(&self, val: Self::SData) {
+        self.send_lock.lock(|_|{
+            self.items.send(val);
+            self.acks.receive();
+        })
+    }
+}
+impl<Q, A, M> SendTimeoutStream for Rendezvous<Q, A, M> where Q: SendTimeoutStream + ReceiveTimoutStream<RData=<Q as SendStream>::SData>, A: SendStream<SData=()> + ReceiveTimoutStream<RData=()>, M: Mutex<Inner=()>{
+    /// Waiting for the send lock is not bounded by timeout
+    /// If the item is taken just as timeout runs out this waits for the receiver's ack and returns None
+    fn send_timeout(&self, val: Self::SData, timeout: Duration, uf: &impl UniversalFunctions) -> Option<Self::SData> {
+        let end_time = uf.system_time() + timeout;
+        self.send_lock.lock(|_|{
+            if let Some(val) = self.items.send_timeout(val, timeout, uf){
+                return Some(val);
+            }
+            let remaining = end_time.checked_sub(uf.system_time()).unwrap_or_default();
+            if self.acks.receive_timeout(remaining, uf).is_some(){
+                return None;
+            }
+            match self.items.try_receive(){
+                Some(val) => Some(val),
+                None => {
+                    self.acks.receive();
+                    None
+                },
+            }
+        })
+    }
+}
+impl<Q, A, M> ReceiveStream for Rendezvous<Q, A, M> where Q: SendTimeoutStream + ReceiveTimoutStream<RData=<Q as SendStream>::SData>, A: SendStream<SData=()> + ReceiveTimoutStream<RData=()>, M: Mutex<Inner=()>{
+    type RData = Q::SData;
+
+    fn try_receive(&self) -> Option<Self::RData> {
+        let val = self.items.try_receive()?;
+        self.acks.send(());
+        Some(val)
+    }
+
+    fn receive(&self) -> Self::RData {
+        let val = self.items.receive();
+        self.acks.send(());
+        val
+    }
+}
+impl<Q, A, M> ReceiveTimoutStream for Rendezvous<Q, A, M> where Q: SendTimeoutStream + ReceiveTimoutStream<RData=<Q as SendStream>::SData>, A: SendStream<SData=()> + ReceiveTimoutStream<RData=()>, M: Mutex<Inner=()>{
+    fn receive_timeout(&self, timeout: Duration, uf: &impl UniversalFunctions) -> Option<Self::RData> {
+        let val = self.items.receive_timeout(timeout, uf)?;
+        self.acks.send(());
+        Some(val)
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod test{
+    use v5_traits::stream::{SendStream, SendTimeoutStream, ReceiveStream};
+    use parking_lot::Mutex;
+    use crate::rendezvous::Rendezvous;
+    use crate::test::{Uf, TestQueue};
+    use core::time::Duration;
+    use std::time::Instant;
+
+    #[test]
+    fn rendezvous_test(){
+        let rendezvous = Rendezvous::<_, _, Mutex<_>>::new(TestQueue::new(1), TestQueue::new(1));
+        assert_eq!(rendezvous.send_timeout(1, Duration::from_millis(5), &Uf()), Some(1));
+        assert_eq!(rendezvous.try_receive(), None);
+
+        std::thread::scope(|scope|{
+            scope.spawn(||{
+                std::thread::sleep(Duration::from_millis(20));
+                assert_eq!(rendezvous.receive(), 2);
+            });
+            let start = Instant::now();
+            rendezvous.send(2);
+            assert!(start.elapsed() >= Duration::from_millis(15));
+        });
+    }
+}
@@ -0,0 +1,185 @@
+use v5_traits::stream::DuplexTimeoutStream;
+use v5_traits::mutex::Mutex;
+use v5_traits::error::Error;
+use v5_traits::UniversalFunctions;
+use alloc::collections::BTreeMap;
+use core::marker::PhantomData;
+use core::sync::atomic::{AtomicU64, Ordering};
+use core::time::Duration;
+
+const POLL_INTERVAL: Duration = Duration::from_millis(1);
+
+/// The calls of a RequestResponse waiting for their response
+#[derive(Debug)]
+pub struct PendingCalls<T>{
+    /// Responses received for a pending sequence number are held until taken
+    pending: BTreeMap<u64, Option<T>>,
+    discarded: usize,
+}
+impl<T> Default for PendingCalls<T>{
+    fn default() -> Self {
+        Self{ pending: BTreeMap::new(), discarded: 0 }
+    }
+}
+
+/// Request and response calls over a duplex of (sequence, message) pairs
+/// Each request is sent with a new sequence number and the remote end replies with the same sequence number,
+/// responses can arrive in any order. Responses for sequence numbers that are not pending, because they timed out,
+/// were cancelled, already got a response or were never sent, are dropped and counted as discarded.
+/// Concurrent callers share the duplex, whichever caller receives a response hands it to its owner
+/// so receives wait at most a millisecond at a time to check for handed over responses
+#[derive(Debug)]
+pub struct RequestResponse<UF, D, T, M> where UF: UniversalFunctions, D: DuplexTimeoutStream<SData=(u64, T)>, T: 'static + Send, M: Mutex<Inner=PendingCalls<T>>{
+    uf: UF,
+    duplex: D,
+    max_pending: usize,
+    next_sequence: AtomicU64,
+    calls: M,
+    phantom_t: PhantomData<T>,
+}
+impl<UF, D, T, M> RequestResponse<UF, D, T, M> where UF: UniversalFunctions, D: DuplexTimeoutStream<SData=(u64, T)>, T: 'static + Send, M: Mutex<Inner=PendingCalls<T>>{
+    /// At most max_pending requests can wait for a response at once
+    pub fn new(uf: UF, duplex: D, max_pending: usize) -> Self{
+        Self{ uf, duplex, max_pending, next_sequence: AtomicU64::new(0), calls: M::new(PendingCalls::default()), phantom_t: Default::default() }
+    }
+
+    pub fn duplex(&self) -> &D{
+        &self.duplex
+    }
+
+    /// The sequence number the next request will be sent with
+    pub fn next_sequence(&self) -> u64{
+        self.next_sequence.load(Ordering::SeqCst)
+    }
+
+    /// The amount of requests waiting for a response
+    pub fn pending_count(&self) -> usize{
+        self.calls.lock(|calls| calls.pending.len())
+    }
+
+    /// The amount of received responses that did not match a pending request
+    pub fn discarded_count(&self) -> usize{
+        self.calls.lock(|calls| calls.discarded)
+    }
+
+    /// Sends request and waits up to timeout in total for its response
+    pub fn call(&self, request: T, timeout: Duration) -> Result<T, RequestError>{
+        let end_time = self.uf.system_time() + timeout;
+        let sequence = self.request(request, timeout)?;
+        self.response(sequence, end_time.checked_sub(self.uf.system_time()).unwrap_or_default())
+    }
+
+    /// Sends request and returns its sequence number to wait on with response
+    /// A request that is never waited on or cancelled stays pending and counts towards max_pending
+    pub fn request(&self, request: T, timeout: Duration) -> Result<u64, RequestError>{
+        let sequence = self.calls.lock(|calls|{
+            if calls.pending.len() >= self.max_pending{
+                return Err(RequestError::TooManyPending{ max_pending: self.max_pending });
+            }
+            let sequence = self.next_sequence.fetch_add(1, Ordering::SeqCst);
+            calls.pending.insert(sequence, None);
+            Ok(sequence)
+        })?;
+        if self.duplex.send_timeout((sequence, request), timeout, &self.uf).is_some(){
+            self.cancel(sequence);
+            return Err(RequestError::SendTimeout);
+        }
+        Ok(sequence)
+    }
+
+    /// Waits up to timeout for the response to sequence, a timed out request is no longer pending
+    pub fn response(&self, sequence: u64, timeout: Duration) -> Result<T, RequestError>{
+        let end_time = self.uf.system_time() + timeout;
+        loop {
+            let taken = self.calls.lock(|calls| match calls.pending.get(&sequence){
+                None => Err(RequestError::NotPending{ sequence }),
+                Some(None) => Ok(None),
+                Some(Some(_)) => Ok(calls.pending.remove(&sequence).flatten()),
+            })?;
+            if let Some(response) = taken{
+                return Ok(response);
+            }
+            let remaining = end_time.checked_sub(self.uf.system_time()).unwrap_or_default();
+            if remaining == Duration::default(){
+                self.cancel(sequence);
+                return Err(RequestError::Timeout{ sequence });
+            }
+            if let Some(received) = self.duplex.receive_timeout(remaining.min(POLL_INTERVAL), &self.uf){
+                self.dispatch(received);
+            }
+        }
+    }
+
+    /// Stops waiting for the response to sequence, it will be discarded if it arrives
+    pub fn cancel(&self, sequence: u64){
+        self.calls.lock(|calls| calls.pending.remove(&sequence));
+    }
+
+    fn dispatch(&self, (sequence, response): (u64, T)){
+        self.calls.lock(|calls| match calls.pending.get_mut(&sequence){
+            Some(slot @ None) => *slot = Some(response),
+            _ => calls.discarded += 1,
+        })
+    }
+}
+/// This ensures that this is sync if possible because T is only stored in the duplex and calls
+unsafe impl<UF, D, T, M> Sync for RequestResponse<UF, D, T, M> where UF: UniversalFunctions, D: DuplexTimeoutStream<SData=(u64, T)> + Sync, T: 'static + Send, M: Mutex<Inner=PendingCalls<T>> + Sync{}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum RequestError{
+    /// max_pending requests were already waiting for a response
+    TooManyPending{ max_pending: usize },
+    /// The request could not be sent within the timeout, it is not pending
+    SendTimeout,
+    /// No response arrived within the timeout, the request is no longer pending
+    Timeout{ sequence: u64 },
+    /// sequence was never requested, timed out, was cancelled or its response was already taken
+    NotPending{ sequence: u64 },
+}
+impl Error for RequestError{
+    fn is_recoverable(&self) -> bool {
+        true
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod test{
+    use v5_traits::stream::{SendStream, ReceiveStream};
+    use parking_lot::Mutex;
+    use crate::request_response::{RequestResponse, RequestError};
+    use crate::composed_stream::ComposedStream;
+    use crate::test::{Uf, TestQueue};
+    use alloc::sync::Arc;
+    use core::time::Duration;
+
+    #[test]
+    fn request_response_test(){
+        let requests = Arc::new(TestQueue::new(4));
+        let responses = Arc::new(TestQueue::new(4));
+        let rpc = RequestResponse::<_, _, _, Mutex<_>>::new(Uf(), ComposedStream::new(requests.clone(), responses.clone()), 2);
+
+        let first = rpc.request(10, Duration::from_millis(10)).unwrap();
+        let second = rpc.request(20, Duration::from_millis(10)).unwrap();
+        assert_eq!(rpc.request(30, Duration::from_millis(10)), Err(RequestError::TooManyPending{ max_pending: 2 }));
+        assert_eq!(requests.receive_vec(2), vec![(first, 10), (second, 20)]);
+
+        responses.send_slice(&[(second, 21), (first, 11), (first, 12), (7, 0)]);
+        assert_eq!(rpc.response(first, Duration::from_millis(50)), Ok(11));
+        assert_eq!(rpc.response(second, Duration::from_millis(50)), Ok(21));
+        assert_eq!(rpc.response(second, Duration::from_millis(50)), Err(RequestError::NotPending{ sequence: second }));
+
+        let third = rpc.next_sequence();
+        assert_eq!(rpc.call(40, Duration::from_millis(20)), Err(RequestError::Timeout{ sequence: third }));
+        assert_eq!(requests.try_receive(), Some((third, 40)));
+        assert_eq!(rpc.pending_count(), 0);
+        assert_eq!(rpc.discarded_count(), 2);
+
+        std::thread::scope(|scope|{
+            scope.spawn(||{
+                let (sequence, request) = requests.receive();
+                responses.send((sequence, request + 1));
+            });
+            assert_eq!(rpc.call(50, Duration::from_millis(500)), Ok(51));
+        });
+    }
+}
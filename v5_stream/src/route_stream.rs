@@ -0,0 +1,66 @@
+use v5_traits::stream::{SendStream, SendTimeoutStream};
+use v5_traits::UniversalFunctions;
+use core::time::Duration;
+
+/// Sends each item to matched if predicate returns true for it and to unmatched otherwise
+/// Items are moved into the chosen stream, never cloned
+#[derive(Debug)]
+pub struct RouteStream<S1, S2, F> where S1: SendStream, S2: SendStream<SData=S1::SData>, F: Fn(&S1::SData) -> bool{
+    matched: S1,
+    unmatched: S2,
+    predicate: F,
+}
+impl<S1, S2, F> RouteStream<S1, S2, F> where S1: SendStream, S2: SendStream<SData=S1::SData>, F: Fn(&S1::SData) -> bool{
+    pub fn new(matched: S1, unmatched: S2, predicate: F) -> Self{
+        Self{ matched, unmatched, predicate }
+    }
+
+    pub fn matched(&self) -> &S1{
+        &self.matched
+    }
+
+    pub fn unmatched(&self) -> &S2{
+        &self.unmatched
+    }
+}
+impl<S1, S2, F> SendStream for RouteStream<S1, S2, F> where S1: SendStream, S2: SendStream<SData=S1::SData>, F: Fn(&S1::SData) -> bool{
+    type SData = S1::SData;
+
+    fn send(&self, val: Self::SData) {
+        if (self.predicate)(&val){
+            self.matched.send(val)
+        }
+        else{
+            self.unmatched.send(val)
+        }
+    }
+}
+impl<S1, S2, F> SendTimeoutStream for RouteStream<S1, S2, F> where S1: SendTimeoutStream, S2: SendTimeoutStream<SData=S1::SData>, F: Fn(&S1::SData) -> bool{
+    fn send_timeout(&self, val: Self::SData, timeout: Duration, uf: &impl UniversalFunctions) -> Option<Self::SData> {
+        if (self.predicate)(&val){
+            self.matched.send_timeout(val, timeout, uf)
+        }
+        else{
+            self.unmatched.send_timeout(val, timeout, uf)
+        }
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod test{
+    use v5_traits::stream::std_impls::new_mpsc_channel;
+    use v5_traits::stream::{SendStream, ReceiveStream};
+    use crate::route_stream::RouteStream;
+
+    #[test]
+    fn route_stream_test(){
+        let (urgent_sender, urgent_receiver) = new_mpsc_channel();
+        let (normal_sender, normal_receiver) = new_mpsc_channel();
+        let commands = RouteStream::new(urgent_sender, normal_sender, |command: &(bool, u32)| command.0);
+        commands.send_slice(&[(false, 1), (true, 2), (false, 3)]);
+
+        assert_eq!(urgent_receiver.receive_vec(1), vec![(true, 2)]);
+        assert_eq!(normal_receiver.receive_vec(2), vec![(false, 1), (false, 3)]);
+        assert_eq!(urgent_receiver.try_receive(), None);
+    }
+}
@@ -0,0 +1,66 @@
+use v5_traits::stream::{ReceiveStream, ReceiveTimoutStream};
+use v5_traits::UniversalFunctions;
+use core::ops::{Add, Mul};
+use core::time::Duration;
+
+/// Converts received items to N and maps them to item * scale + offset
+/// For converting raw sensor readings such as encoder ticks into engineering units
+#[derive(Debug)]
+pub struct ScaleStream<R, N> where R: ReceiveStream, R::RData: Into<N>, N: 'static + Copy + Send + Mul<Output=N> + Add<Output=N>{
+    stream: R,
+    scale: N,
+    offset: N,
+}
+impl<R, N> ScaleStream<R, N> where R: ReceiveStream, R::RData: Into<N>, N: 'static + Copy + Send + Mul<Output=N> + Add<Output=N>{
+    pub fn new(stream: R, scale: N, offset: N) -> Self{
+        Self{ stream, scale, offset }
+    }
+
+    pub fn stream(&self) -> &R{
+        &self.stream
+    }
+
+    pub fn scale(&self) -> N{
+        self.scale
+    }
+
+    pub fn offset(&self) -> N{
+        self.offset
+    }
+
+    fn convert(&self, val: R::RData) -> N{
+        val.into() * self.scale + self.offset
+    }
+}
+impl<R, N> ReceiveStream for ScaleStream<R, N> where R: ReceiveStream, R::RData: Into<N>, N: 'static + Copy + Send + Mul<Output=N> + Add<Output=N>{
+    type RData = N;
+
+    fn try_receive(&self) -> Option<N> {
+        self.stream.try_receive().map(|val| self.convert(val))
+    }
+
+    fn receive(&self) -> N {
+        self.convert(self.stream.receive())
+    }
+}
+impl<R, N> ReceiveTimoutStream for ScaleStream<R, N> where R: ReceiveTimoutStream, R::RData: Into<N>, N: 'static + Copy + Send + Mul<Output=N> + Add<Output=N>{
+    fn receive_timeout(&self, timeout: Duration, uf: &impl UniversalFunctions) -> Option<N> {
+        self.stream.receive_timeout(timeout, uf).map(|val| self.convert(val))
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod test{
+    use v5_traits::stream::std_impls::new_mpsc_channel;
+    use v5_traits::stream::{SendStream, ReceiveStream};
+    use crate::scale_stream::ScaleStream;
+
+    #[test]
+    fn scale_stream_test(){
+        let (sender, receiver) = new_mpsc_channel::<i32>();
+        let meters = ScaleStream::<_, f64>::new(receiver, 0.5, -1.0);
+        sender.send_slice(&[0, 2, 10]);
+
+        assert_eq!(meters.receive_vec(3), vec![-1.0, 0.0, 4.0]);
+    }
+}
@@ -0,0 +1,110 @@
+use v5_traits::stream::ReceiveStream;
+use v5_traits::UniversalFunctions;
+use alloc::boxed::Box;
+use alloc::vec::Vec;
+use core::time::Duration;
+
+trait SelectArm<O>{
+    fn try_fire(&mut self) -> Option<O>;
+}
+struct StreamArm<'a, R, F>{
+    stream: &'a R,
+    handler: F,
+}
+impl<'a, R, F, O> SelectArm<O> for StreamArm<'a, R, F> where R: ReceiveStream, F: FnMut(R::RData) -> O{
+    fn try_fire(&mut self) -> Option<O> {
+        self.stream.try_receive().map(&mut self.handler)
+    }
+}
+
+/// Waits on several receive streams of possibly different item types
+/// Each arm has a handler that turns its item into O, usually an enum saying which stream fired
+/// Streams are polled with try_receive every poll_interval so an item can wait up to poll_interval before being handled
+/// Polling starts one arm after the last one that fired so a busy stream can not starve the others
+pub struct Selector<'a, UF, O> where UF: UniversalFunctions{
+    uf: UF,
+    poll_interval: Duration,
+    arms: Vec<Box<dyn SelectArm<O> + 'a>>,
+    next_arm: usize,
+}
+impl<'a, UF, O> Selector<'a, UF, O> where UF: UniversalFunctions{
+    pub fn new(uf: UF, poll_interval: Duration) -> Self{
+        Self{ uf, poll_interval, arms: Vec::new(), next_arm: 0 }
+    }
+
+    /// Adds stream to the selection, handler is called with each item selected from it
+    pub fn arm<R, F>(mut self, stream: &'a R, handler: F) -> Self where R: ReceiveStream, F: 'a + FnMut(R::RData) -> O{
+        self.arms.push(Box::new(StreamArm{ stream, handler }));
+        self
+    }
+
+    /// Polls every arm once, returns the output of the first one with an item
+    pub fn try_select(&mut self) -> Option<O>{
+        let arm_count = self.arms.len();
+        for offset in 0..arm_count{
+            let index = (self.next_arm + offset) % arm_count;
+            if let Some(out) = self.arms[index].try_fire(){
+                self.next_arm = (index + 1) % arm_count;
+                return Some(out);
+            }
+        }
+        None
+    }
+
+    /// Waits until an arm has an item and returns its output
+    pub fn select(&mut self) -> O{
+        loop {
+            if let Some(out) = self.try_select(){
+                return out;
+            }
+            self.uf.delay(self.poll_interval);
+        }
+    }
+
+    /// Waits up to timeout for an arm to have an item and returns its output
+    pub fn select_timeout(&mut self, timeout: Duration) -> Option<O>{
+        let end_time = self.uf.system_time() + timeout;
+        loop {
+            if let Some(out) = self.try_select(){
+                return Some(out);
+            }
+            if self.uf.system_time() >= end_time{
+                return None;
+            }
+            self.uf.delay(self.poll_interval);
+        }
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod test{
+    use v5_traits::stream::std_impls::new_mpsc_channel;
+    use v5_traits::stream::SendStream;
+    use crate::selector::Selector;
+    use crate::test::Uf;
+    use core::time::Duration;
+
+    #[derive(Debug, PartialEq)]
+    enum Event{
+        Input(i8),
+        Message(&'static str),
+    }
+
+    #[test]
+    fn selector_test(){
+        let (input_sender, input_receiver) = new_mpsc_channel();
+        let (message_sender, message_receiver) = new_mpsc_channel();
+        let mut selector = Selector::new(Uf(), Duration::from_millis(1))
+            .arm(&input_receiver, Event::Input)
+            .arm(&message_receiver, Event::Message);
+
+        assert_eq!(selector.select_timeout(Duration::from_millis(10)), None);
+
+        input_sender.send_slice(&[1, 2]);
+        message_sender.send("hello");
+        assert_eq!(selector.select(), Event::Input(1));
+        assert_eq!(selector.select(), Event::Message("hello"));
+        assert_eq!(selector.select(), Event::Input(2));
+        assert_eq!(selector.try_select(), None);
+    }
+}
@@ -0,0 +1,250 @@
+use v5_traits::stream::{SendStream, SendTimeoutStream, ReceiveStream, ReceiveTimoutStream};
+use v5_traits::mutex::Mutex;
+use v5_traits::UniversalFunctions;
+use alloc::collections::BTreeMap;
+use core::sync::atomic::{AtomicU64, Ordering};
+use core::time::Duration;
+use core::marker::PhantomData;
+
+/// Stamps each sent item with a monotonically increasing sequence number before forwarding it as (sequence, item)
+/// Share one SequencedStream (through an Arc) between producers so they draw from the same counter
+#[derive(Debug)]
+pub struct SequencedStream<S, T> where S: SendStream<SData=(u64, T)>, T: 'static + Send{
+    stream: S,
+    next_sequence: AtomicU64,
+    phantom_t: PhantomData<T>,
+}
+impl<S, T> SequencedStream<S, T> where S: SendStream<SData=(u64, T)>, T: 'static + Send{
+    pub fn new(stream: S) -> Self{
+        Self{ stream, next_sequence: AtomicU64::new(0), phantom_t: Default::default() }
+    }
+
+    pub fn stream(&self) -> &S{
+        &self.stream
+    }
+
+    /// The sequence number the next sent item will receive
+    pub fn next_sequence(&self) -> u64{
+        self.next_sequence.load(Ordering::SeqCst)
+    }
+
+    fn take_sequence(&self) -> u64{
+        self.next_sequence.fetch_add(1, Ordering::SeqCst)
+    }
+}
+/// This ensures that this is sync if possible because no T is actually stored
+unsafe impl<S, T> Sync for SequencedStream<S, T> where S: SendStream<SData=(u64, T)> + Sync, T: 'static + Send{}
+impl<S, T> SendStream for SequencedStream<S, T> where S: SendStream<SData=(u64, T)>, T: 'static + Send{
+    type SData = T;
+
+    fn send(&self, val: T) {
+        self.stream.send((self.take_sequence(), val))
+    }
+}
+impl<S, T> SendTimeoutStream for SequencedStream<S, T> where S: SendTimeoutStream<SData=(u64, T)>, T: 'static + Send{
+    /// A timed out send still uses up its sequence number, so the receiver will see it as a gap
+    fn send_timeout(&self, val: T, timeout: Duration, uf: &impl UniversalFunctions) -> Option<T> {
+        self.stream.send_timeout((self.take_sequence(), val), timeout, uf).map(|(_, val)| val)
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum SequenceEvent<T>{
+    /// An item received in sequence order
+    Item(u64, T),
+    /// Sequence numbers expected..received were given up on because the reorder buffer filled
+    Gap{ expected: u64, received: u64 },
+}
+
+/// The reorder buffer state of a ReorderStream
+#[derive(Debug)]
+pub struct ReorderState<T>{
+    next_sequence: u64,
+    buffer: BTreeMap<u64, T>,
+}
+impl<T> ReorderState<T>{
+    fn insert(&mut self, sequence: u64, val: T){
+        // Earlier sequences were already emitted or given up on
+        if sequence >= self.next_sequence{
+            self.buffer.insert(sequence, val);
+        }
+    }
+
+    fn next_event(&mut self, max_buffer: usize) -> Option<SequenceEvent<T>>{
+        if let Some(val) = self.buffer.remove(&self.next_sequence){
+            let sequence = self.next_sequence;
+            self.next_sequence += 1;
+            Some(SequenceEvent::Item(sequence, val))
+        }
+        else if self.buffer.len() > max_buffer{
+            let received = *self.buffer.keys().next().unwrap();
+            let expected = self.next_sequence;
+            self.next_sequence = received;
+            Some(SequenceEvent::Gap{ expected, received })
+        }
+        else{
+            None
+        }
+    }
+}
+impl<T> Default for ReorderState<T>{
+    fn default() -> Self {
+        Self{ next_sequence: 0, buffer: BTreeMap::new() }
+    }
+}
+
+/// Receives (sequence, item) pairs from a SequencedStream and emits them in sequence order
+/// Up to max_buffer out of order items are held while waiting for a missing sequence number,
+/// once that is exceeded the missing numbers are reported as a Gap and reordering continues from the earliest buffered item
+/// Items with sequence numbers that were already emitted or skipped are dropped
+#[derive(Debug)]
+pub struct ReorderStream<R, T, M> where R: ReceiveStream<RData=(u64, T)>, T: 'static + Send, M: Mutex<Inner=ReorderState<T>>{
+    stream: R,
+    state: M,
+    max_buffer: usize,
+}
+impl<R, T, M> ReorderStream<R, T, M> where R: ReceiveStream<RData=(u64, T)>, T: 'static + Send, M: Mutex<Inner=ReorderState<T>>{
+    /// The first expected sequence number is 0
+    pub fn new(stream: R, max_buffer: usize) -> Self{
+        Self{ stream, state: M::new(ReorderState::default()), max_buffer }
+    }
+
+    pub fn stream(&self) -> &R{
+        &self.stream
+    }
+
+    /// The amount of out of order items currently held
+    pub fn buffered(&self) -> usize{
+        self.state.lock(|state| state.buffer.len())
+    }
+}
+impl<R, T, M> ReceiveStream for ReorderStream<R, T, M> where R: ReceiveStream<RData=(u64, T)>, T: 'static + Send, M: Mutex<Inner=ReorderState<T>>{
+    type RData = SequenceEvent<T>;
+
+    fn try_receive(&self) -> Option<Self::RData> {
+        loop {
+            if let Some(event) = self.state.lock(|state| state.next_event(self.max_buffer)){
+                return Some(event);
+            }
+            let (sequence, val) = self.stream.try_receive()?;
+            self.state.lock(|state| state.insert(sequence, val));
+        }
+    }
+
+    fn receive(&self) -> Self::RData {
+        loop {
+            if let Some(event) = self.state.lock(|state| state.next_event(self.max_buffer)){
+                return event;
+            }
+            let (sequence, val) = self.stream.receive();
+            self.state.lock(|state| state.insert(sequence, val));
+        }
+    }
+}
+
+/// Receives (sequence, item) pairs from a SequencedStream and strips the sequence number
+/// Counts the sequence numbers skipped over as dropped, the first expected sequence number is 0
+/// Sequence numbers at or before the last received one are passed through without counting, but an item that arrives after a later one
+/// stays counted as dropped, so with reordering the dropped count is an upper bound on loss. Use ReorderStream to tell the two apart
+#[derive(Debug)]
+pub struct GapDetectStream<R, T> where R: ReceiveStream<RData=(u64, T)>, T: 'static + Send{
+    stream: R,
+    next_sequence: AtomicU64,
+    dropped: AtomicU64,
+    phantom_t: PhantomData<T>,
+}
+impl<R, T> GapDetectStream<R, T> where R: ReceiveStream<RData=(u64, T)>, T: 'static + Send{
+    pub fn new(stream: R) -> Self{
+        Self{ stream, next_sequence: AtomicU64::new(0), dropped: AtomicU64::new(0), phantom_t: Default::default() }
+    }
+
+    pub fn stream(&self) -> &R{
+        &self.stream
+    }
+
+    /// The amount of sequence numbers skipped so far, including ones that later arrived out of order
+    pub fn dropped_count(&self) -> u64{
+        self.dropped.load(Ordering::SeqCst)
+    }
+
+    fn check(&self, (sequence, val): (u64, T)) -> T{
+        let expected = self.next_sequence.fetch_max(sequence + 1, Ordering::SeqCst);
+        if sequence > expected{
+            self.dropped.fetch_add(sequence - expected, Ordering::SeqCst);
+        }
+        val
+    }
+}
+/// This ensures that this is sync if possible because no T is actually stored
+unsafe impl<R, T> Sync for GapDetectStream<R, T> where R: ReceiveStream<RData=(u64, T)> + Sync, T: 'static + Send{}
+impl<R, T> ReceiveStream for GapDetectStream<R, T> where R: ReceiveStream<RData=(u64, T)>, T: 'static + Send{
+    type RData = T;
+
+    fn try_receive(&self) -> Option<T> {
+        self.stream.try_receive().map(|val| self.check(val))
+    }
+
+    fn receive(&self) -> T {
+        self.check(self.stream.receive())
+    }
+}
+impl<R, T> ReceiveTimoutStream for GapDetectStream<R, T> where R: ReceiveTimoutStream<RData=(u64, T)>, T: 'static + Send{
+    fn receive_timeout(&self, timeout: Duration, uf: &impl UniversalFunctions) -> Option<T> {
+        self.stream.receive_timeout(timeout, uf).map(|val| self.check(val))
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod test{
+    use v5_traits::stream::std_impls::new_mpsc_channel;
+    use v5_traits::stream::{SendStream, ReceiveStream};
+    use parking_lot::Mutex;
+    use crate::sequenced_stream::{SequencedStream, ReorderStream, SequenceEvent, GapDetectStream};
+
+    #[test]
+    fn sequenced_stream_test(){
+        let (sender, receiver) = new_mpsc_channel();
+        let sequenced = SequencedStream::new(sender);
+        sequenced.send('a');
+        sequenced.send('b');
+
+        assert_eq!(receiver.receive(), (0, 'a'));
+        assert_eq!(receiver.receive(), (1, 'b'));
+        assert_eq!(sequenced.next_sequence(), 2);
+    }
+
+    #[test]
+    fn reorder_stream_test(){
+        let (sender, receiver) = new_mpsc_channel();
+        let reorder = ReorderStream::<_, _, Mutex<_>>::new(receiver, 2);
+        sender.send_vec(vec![(1, 'b'), (0, 'a'), (0, 'a'), (2, 'c')]);
+
+        assert_eq!(reorder.receive(), SequenceEvent::Item(0, 'a'));
+        assert_eq!(reorder.receive(), SequenceEvent::Item(1, 'b'));
+        assert_eq!(reorder.receive(), SequenceEvent::Item(2, 'c'));
+        assert_eq!(reorder.try_receive(), None);
+
+        sender.send_vec(vec![(4, 'e'), (5, 'f'), (6, 'g')]);
+        assert_eq!(reorder.receive(), SequenceEvent::Gap{ expected: 3, received: 4 });
+        assert_eq!(reorder.receive(), SequenceEvent::Item(4, 'e'));
+        assert_eq!(reorder.receive(), SequenceEvent::Item(5, 'f'));
+        assert_eq!(reorder.receive(), SequenceEvent::Item(6, 'g'));
+        assert_eq!(reorder.buffered(), 0);
+    }
+
+    #[test]
+    fn gap_detect_stream_test(){
+        let (sender, receiver) = new_mpsc_channel();
+        let gap_detect = GapDetectStream::new(receiver);
+        sender.send_vec(vec![(0, 'a'), (1, 'b'), (4, 'e'), (3, 'd'), (5, 'f')]);
+
+        assert_eq!(gap_detect.receive_vec(5), vec!['a', 'b', 'e', 'd', 'f']);
+        // 2 and 3 were skipped when 4 arrived, 3 arriving late does not take it back
+        assert_eq!(gap_detect.dropped_count(), 2);
+        assert_eq!(gap_detect.try_receive(), None);
+
+        sender.send_vec(vec![(6, 'g'), (8, 'i'), (7, 'h'), (7, 'h')]);
+        assert_eq!(gap_detect.receive_vec(4), vec!['g', 'i', 'h', 'h']);
+        assert_eq!(gap_detect.dropped_count(), 3);
+    }
+}
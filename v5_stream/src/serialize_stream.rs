@@ -1,17 +1,66 @@
 use v5_traits::stream::{DuplexStream, SendStream, ReceiveStream};
 use alloc::vec::Vec;
-use v5_traits::{UniversalFunctions, LogLevel, EnsureSync, EnsureSend};
+use v5_traits::{UniversalFunctions, EnsureSync, EnsureSend};
+use v5_traits::codec::{Codec, DecodeError, EncodeError};
+use alloc::boxed::Box;
 use core::marker::PhantomData;
 use serde::Serialize;
 use v5_traits::error::Error;
 use serde::de::DeserializeOwned;
 
-pub struct SerializeStream<UF, T, S>
+/// Serde cbor encoding for any serde type
+#[derive(Copy, Clone, Debug, Default)]
+pub struct CborCodec;
+impl<T> Codec<T> for CborCodec where T: Serialize + DeserializeOwned{
+    fn encode(&self, val: &T, buffer: &mut Vec<u8>) -> Result<(), EncodeError> {
+        let start = buffer.len();
+        val.serialize(&mut serde_cbor::Serializer::new(&mut *buffer)).map_err(|error|{
+            buffer.truncate(start);
+            EncodeError(Box::new(error))
+        })
+    }
+
+    fn decode(&self, data: &[u8]) -> Result<T, DecodeError> {
+        serde_cbor::from_slice(data).map_err(|error| DecodeError::Invalid(Box::new(error)))
+    }
+}
+
+/// Serde postcard encoding for any serde type, more compact than cbor because field names and types are not sent
+/// so both ends must use the same definition of T
+#[derive(Copy, Clone, Debug, Default)]
+pub struct PostcardCodec;
+impl<T> Codec<T> for PostcardCodec where T: Serialize + DeserializeOwned{
+    fn encode(&self, val: &T, buffer: &mut Vec<u8>) -> Result<(), EncodeError> {
+        let start = buffer.len();
+        match postcard::to_extend(val, Append(buffer)){
+            Ok(_) => Ok(()),
+            Err(error) => {
+                buffer.truncate(start);
+                Err(EncodeError(Box::new(error)))
+            },
+        }
+    }
+
+    fn decode(&self, data: &[u8]) -> Result<T, DecodeError> {
+        postcard::from_bytes(data).map_err(|error| DecodeError::Invalid(Box::new(error)))
+    }
+}
+/// Lets postcard extend a borrowed buffer so it is still available when serializing fails
+struct Append<'a>(&'a mut Vec<u8>);
+impl Extend<u8> for Append<'_>{
+    fn extend<I: IntoIterator<Item=u8>>(&mut self, iter: I) {
+        self.0.extend(iter)
+    }
+}
+
+pub struct SerializeStream<UF, T, S, C = CborCodec>
     where UF: UniversalFunctions,
-          T: 'static + Send + Serialize + DeserializeOwned,
-          S: DuplexStream<SData=Vec<u8>, RData=Vec<u8>>{
+          T: 'static + Send,
+          S: DuplexStream<SData=Vec<u8>, RData=Vec<u8>>,
+          C: Codec<T>{
     uf: UF,
     stream: S,
+    codec: C,
     phantom_t: PhantomData<T>,
 }
 impl<UF, T, S> SerializeStream<UF, T, S>
@@ -19,46 +68,59 @@ impl<UF, T, S> SerializeStream<UF, T, S>
           T: 'static + Send + Serialize + DeserializeOwned,
           S: DuplexStream<SData=Vec<u8>, RData=Vec<u8>>{
     pub fn new(uf: UF, stream: S) -> Self{
-        Self{ uf, stream, phantom_t: Default::default() }
+        Self::with_codec(uf, stream, CborCodec)
+    }
+}
+impl<UF, T, S, C> SerializeStream<UF, T, S, C>
+    where UF: UniversalFunctions,
+          T: 'static + Send,
+          S: DuplexStream<SData=Vec<u8>, RData=Vec<u8>>,
+          C: Codec<T>{
+    pub fn with_codec(uf: UF, stream: S, codec: C) -> Self{
+        Self{ uf, stream, codec, phantom_t: Default::default() }
     }
 
     pub fn stream(&self) -> &S where S: Sync{
         &self.stream
     }
+
+    pub fn codec(&self) -> &C{
+        &self.codec
+    }
 }
 /// This ensures that this is sync if possible because no T is actually stored
-unsafe impl<UF, T, S> Sync for SerializeStream<UF, T, S>
+unsafe impl<UF, T, S, C> Sync for SerializeStream<UF, T, S, C>
     where UF: UniversalFunctions + Sync,
-          T: 'static + Send + Serialize + DeserializeOwned,
-          S: DuplexStream<SData=Vec<u8>, RData=Vec<u8>> + Sync{}
-impl<UF, T, S> SendStream for SerializeStream<UF, T, S>
+          T: 'static + Send,
+          S: DuplexStream<SData=Vec<u8>, RData=Vec<u8>> + Sync,
+          C: Codec<T> + Sync{}
+impl<UF, T, S, C> SendStream for SerializeStream<UF, T, S, C>
     where UF: UniversalFunctions,
-          T: 'static + Send + Serialize + DeserializeOwned,
-          S: DuplexStream<SData=Vec<u8>, RData=Vec<u8>>{
+          T: 'static + Send,
+          S: DuplexStream<SData=Vec<u8>, RData=Vec<u8>>,
+          C: Codec<T>{
     type SData = T;
 
     fn send(&self, val: T) {
-        self.stream.send(match serde_cbor::to_vec(&val){
-            Ok(data) => data,
-            Err(error) => {
-                self.uf.log(||format!("Serde cbor error while serializing: {}", error), LogLevel::ERROR);
-                return;
-            },
-        })
+        match self.codec.encode_to_vec(&val){
+            Ok(data) => self.stream.send(data),
+            Err(error) => self.uf.log_error(||format!("Encode error: {:?}", error)),
+        }
     }
 }
-impl<UF, T, S> ReceiveStream for SerializeStream<UF, T, S>
+impl<UF, T, S, C> ReceiveStream for SerializeStream<UF, T, S, C>
     where UF: UniversalFunctions,
-          T: 'static + Send + Serialize + DeserializeOwned,
-          S: DuplexStream<SData=Vec<u8>, RData=Vec<u8>>{
+          T: 'static + Send,
+          S: DuplexStream<SData=Vec<u8>, RData=Vec<u8>>,
+          C: Codec<T>{
     type RData = T;
 
     fn try_receive(&self) -> Option<Self::RData> {
         self.stream.try_receive().and_then(|val|{
-            match serde_cbor::from_slice(&val){
+            match self.codec.decode(&val){
                 Ok(val) => Some(val),
                 Err(error) => {
-                    self.uf.log_error(||format!("Serde cbor error: {}", error));
+                    self.uf.log_error(||format!("Decode error: {:?}", error));
                     None
                 },
             }
@@ -67,27 +129,30 @@ impl<UF, T, S> ReceiveStream for SerializeStream<UF, T, S>
 
     fn receive(&self) -> T {
         loop {
-            match serde_cbor::from_slice(&self.stream.receive()) {
+            match self.codec.decode(&self.stream.receive()) {
                 Ok(val) => return val,
                 Err(error) => {
-                    self.uf.log_error(|| format!("Serde cbor error: {}", error));
+                    self.uf.log_error(|| format!("Decode error: {:?}", error));
                 },
             }
         }
     }
 }
-impl<UF, T, S> DuplexStream for SerializeStream<UF, T, S>
+impl<UF, T, S, C> DuplexStream for SerializeStream<UF, T, S, C>
     where UF: UniversalFunctions,
-          T: 'static + Send + Serialize + DeserializeOwned,
-          S: DuplexStream<SData=Vec<u8>, RData=Vec<u8>>{}
-impl<UF, T, S> EnsureSync for SerializeStream<UF, T, S>
+          T: 'static + Send,
+          S: DuplexStream<SData=Vec<u8>, RData=Vec<u8>>,
+          C: Codec<T>{}
+impl<UF, T, S, C> EnsureSync for SerializeStream<UF, T, S, C>
     where UF: UniversalFunctions + Sync,
-          T: Send + Serialize + DeserializeOwned,
-          S: DuplexStream<SData=Vec<u8>, RData=Vec<u8>> + Sync{}
-impl<UF, T, S> EnsureSend for SerializeStream<UF, T, S>
+          T: Send,
+          S: DuplexStream<SData=Vec<u8>, RData=Vec<u8>> + Sync,
+          C: Codec<T> + Sync{}
+impl<UF, T, S, C> EnsureSend for SerializeStream<UF, T, S, C>
     where UF: UniversalFunctions + Send,
-          T: Send + Serialize + DeserializeOwned,
-          S: DuplexStream<SData=Vec<u8>, RData=Vec<u8>> + Send{}
+          T: Send,
+          S: DuplexStream<SData=Vec<u8>, RData=Vec<u8>> + Send,
+          C: Codec<T> + Send{}
 
 #[derive(Debug)]
 pub enum SerializeStreamError<E> where E: Error{
@@ -107,3 +172,49 @@ impl<E> From<E> for SerializeStreamError<E> where E: Error{
         Self::StreamError(from)
     }
 }
+
+#[cfg(all(test, feature = "std"))]
+mod test{
+    use v5_traits::codec::Codec;
+    use serde::{Serialize, Serializer, Deserialize};
+    use serde::ser::{Error, SerializeTuple};
+    use crate::serialize_stream::{CborCodec, PostcardCodec};
+    use alloc::string::{String, ToString};
+
+    #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+    struct Command{
+        name: String,
+        speed: i32,
+    }
+
+    /// Writes part of its encoding before failing
+    #[derive(Debug, Deserialize)]
+    struct Unencodable;
+    impl Serialize for Unencodable{
+        fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error> where S: Serializer {
+            let mut tuple = serializer.serialize_tuple(2)?;
+            tuple.serialize_element(&1u8)?;
+            Err(S::Error::custom("unencodable"))
+        }
+    }
+
+    #[test]
+    fn encode_error_test(){
+        let mut buffer = vec![9];
+        assert!(PostcardCodec.encode(&Unencodable, &mut buffer).is_err());
+        assert_eq!(buffer, [9]);
+        assert!(CborCodec.encode(&Unencodable, &mut buffer).is_err());
+        assert_eq!(buffer, [9]);
+    }
+
+    #[test]
+    fn postcard_codec_test(){
+        let command = Command{ name: "lift".to_string(), speed: 300 };
+        let mut buffer = vec![9];
+        PostcardCodec.encode(&command, &mut buffer).unwrap();
+        assert_eq!(buffer, [9, 4, b'l', b'i', b'f', b't', 0xD8, 0x04]);
+        assert_eq!(PostcardCodec.decode(&buffer[1..]).ok(), Some(command.clone()));
+        assert!(Codec::<Command>::decode(&PostcardCodec, &buffer[1..4]).is_err());
+        assert!(PostcardCodec.encode_to_vec(&command).unwrap().len() < CborCodec.encode_to_vec(&command).unwrap().len());
+    }
+}
@@ -0,0 +1,194 @@
+use v5_traits::stream::{SendStream, ReceiveStream, ReceiveTimoutStream};
+use v5_traits::codec::Codec;
+use v5_traits::mutex::Mutex;
+use v5_traits::UniversalFunctions;
+use alloc::vec::Vec;
+use core::convert::TryInto;
+use core::marker::PhantomData;
+use core::sync::atomic::{AtomicUsize, Ordering};
+use core::time::Duration;
+use crate::serialize_stream::PostcardCodec;
+
+const LENGTH_BYTES: usize = 4;
+
+/// Sends values of T over a byte stream such as a serial or radio link
+/// Each value is encoded with codec, postcard by default, and framed by its length as 4 little endian bytes.
+/// Bytes of a partly received frame are buffered until the rest arrives, frames that fail to decode are logged and skipped.
+/// A length over max_frame_len means the link lost or corrupted a byte, the buffered bytes are dropped and counted by oversized_count
+/// so a bad length can not make the stream buffer forever
+#[derive(Debug)]
+pub struct SerializedStream<UF, S, T, M, C = PostcardCodec> where UF: UniversalFunctions, T: 'static + Send, M: Mutex<Inner=Vec<u8>>, C: Codec<T>{
+    uf: UF,
+    stream: S,
+    codec: C,
+    max_frame_len: usize,
+    buffer: M,
+    oversized: AtomicUsize,
+    phantom_t: PhantomData<T>,
+}
+impl<UF, S, T, M> SerializedStream<UF, S, T, M> where UF: UniversalFunctions, T: 'static + Send, M: Mutex<Inner=Vec<u8>>, PostcardCodec: Codec<T>{
+    pub fn new(uf: UF, stream: S, max_frame_len: usize) -> Self{
+        Self::with_codec(uf, stream, PostcardCodec, max_frame_len)
+    }
+}
+impl<UF, S, T, M, C> SerializedStream<UF, S, T, M, C> where UF: UniversalFunctions, T: 'static + Send, M: Mutex<Inner=Vec<u8>>, C: Codec<T>{
+    pub fn with_codec(uf: UF, stream: S, codec: C, max_frame_len: usize) -> Self{
+        Self{ uf, stream, codec, max_frame_len, buffer: M::new(Vec::new()), oversized: AtomicUsize::new(0), phantom_t: Default::default() }
+    }
+
+    pub fn stream(&self) -> &S{
+        &self.stream
+    }
+
+    pub fn codec(&self) -> &C{
+        &self.codec
+    }
+
+    pub fn max_frame_len(&self) -> usize{
+        self.max_frame_len
+    }
+
+    /// The amount of times a received length was over max_frame_len and the buffer was dropped
+    pub fn oversized_count(&self) -> usize{
+        self.oversized.load(Ordering::SeqCst)
+    }
+
+    /// The amount of bytes received towards the next frame
+    pub fn buffered(&self) -> usize{
+        self.buffer.lock(|buffer| buffer.len())
+    }
+
+    /// Removes and decodes the first frame of buffer if it is complete
+    /// Returns None if the frame is incomplete, Some(None) if it failed to decode or was too long
+    fn take_frame(&self, buffer: &mut Vec<u8>) -> Option<Option<T>>{
+        if buffer.len() < LENGTH_BYTES{
+            return None;
+        }
+        let length = u32::from_le_bytes(buffer[..LENGTH_BYTES].try_into().unwrap()) as usize;
+        if length > self.max_frame_len{
+            self.uf.log_error(|| format!("Frame length {} over max {}, dropping {} buffered bytes", length, self.max_frame_len, buffer.len()));
+            self.oversized.fetch_add(1, Ordering::SeqCst);
+            buffer.clear();
+            return Some(None);
+        }
+        let end = LENGTH_BYTES + length;
+        if buffer.len() < end{
+            return None;
+        }
+        let decoded = self.codec.decode(&buffer[LENGTH_BYTES..end]);
+        buffer.drain(..end);
+        match decoded{
+            Ok(val) => Some(Some(val)),
+            Err(error) => {
+                self.uf.log_error(|| format!("Decode error: {:?}", error));
+                Some(None)
+            },
+        }
+    }
+}
+/// This ensures that this is sync if possible because no T is actually stored
+unsafe impl<UF, S, T, M, C> Sync for SerializedStream<UF, S, T, M, C> where UF: UniversalFunctions + Sync, S: Sync, T: 'static + Send, M: Mutex<Inner=Vec<u8>> + Sync, C: Codec<T> + Sync{}
+impl<UF, S, T, M, C> SendStream for SerializedStream<UF, S, T, M, C> where UF: UniversalFunctions, S: SendStream<SData=u8>, T: 'static + Send, M: Mutex<Inner=Vec<u8>>, C: Codec<T>{
+    type SData = T;
+
+    fn send(&self, val: T) {
+        let mut frame = Vec::from([0; LENGTH_BYTES]);
+        if let Err(error) = self.codec.encode(&val, &mut frame){
+            self.uf.log_error(|| format!("Encode error: {:?}", error));
+            return;
+        }
+        let length = (frame.len() - LENGTH_BYTES) as u32;
+        frame[..LENGTH_BYTES].copy_from_slice(&length.to_le_bytes());
+        self.stream.send_vec(frame)
+    }
+}
+impl<UF, S, T, M, C> ReceiveStream for SerializedStream<UF, S, T, M, C> where UF: UniversalFunctions, S: ReceiveStream<RData=u8>, T: 'static + Send, M: Mutex<Inner=Vec<u8>>, C: Codec<T>{
+    type RData = T;
+
+    fn try_receive(&self) -> Option<T> {
+        self.buffer.lock(|buffer|{
+            loop {
+                if let Some(Some(val)) = self.take_frame(buffer){
+                    return Some(val);
+                }
+                buffer.push(self.stream.try_receive()?);
+            }
+        })
+    }
+
+    fn receive(&self) -> T {
+        self.buffer.lock(|buffer|{
+            loop {
+                if let Some(Some(val)) = self.take_frame(buffer){
+                    return val;
+                }
+                buffer.push(self.stream.receive());
+            }
+        })
+    }
+}
+impl<UF, S, T, M, C> ReceiveTimoutStream for SerializedStream<UF, S, T, M, C> where UF: UniversalFunctions, S: ReceiveTimoutStream<RData=u8>, T: 'static + Send, M: Mutex<Inner=Vec<u8>>, C: Codec<T>{
+    fn receive_timeout(&self, timeout: Duration, uf: &impl UniversalFunctions) -> Option<T> {
+        let end_time = uf.system_time() + timeout;
+        self.buffer.lock(|buffer|{
+            loop {
+                if let Some(Some(val)) = self.take_frame(buffer){
+                    return Some(val);
+                }
+                let remaining = end_time.checked_sub(uf.system_time()).unwrap_or_default();
+                buffer.push(self.stream.receive_timeout(remaining, uf)?);
+            }
+        })
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod test{
+    use v5_traits::stream::{SendStream, ReceiveStream};
+    use parking_lot::Mutex;
+    use serde::{Serialize, Deserialize};
+    use crate::serialized_stream::SerializedStream;
+    use crate::test::{Uf, TestQueue};
+    use alloc::string::{String, ToString};
+    use alloc::sync::Arc;
+    use alloc::vec::Vec;
+
+    #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+    struct Command{
+        name: String,
+        speed: i32,
+    }
+
+    #[test]
+    fn serialized_stream_test(){
+        let bytes = Arc::new(TestQueue::new(256));
+        let link = SerializedStream::<_, _, Command, Mutex<_>>::new(Uf(), bytes.clone(), 64);
+        let drive = Command{ name: "drive".to_string(), speed: -40 };
+        link.send(drive.clone());
+        assert_eq!(link.receive(), drive);
+
+        let mut frame = Vec::new();
+        link.send(Command{ name: "lift".to_string(), speed: 100 });
+        while let Some(byte) = bytes.try_receive(){
+            frame.push(byte);
+        }
+        bytes.send_slice(&frame[..3]);
+        assert_eq!(link.try_receive(), None);
+        assert_eq!(link.buffered(), 3);
+        bytes.send_slice(&frame[3..]);
+        assert_eq!(link.receive(), Command{ name: "lift".to_string(), speed: 100 });
+        assert_eq!(link.buffered(), 0);
+    }
+
+    #[test]
+    fn serialized_stream_oversized_test(){
+        let bytes = Arc::new(TestQueue::new(256));
+        let link = SerializedStream::<_, _, Command, Mutex<_>>::new(Uf(), bytes.clone(), 64);
+        bytes.send_slice(&u32::MAX.to_le_bytes());
+        let drive = Command{ name: "drive".to_string(), speed: 20 };
+        link.send(drive.clone());
+        assert_eq!(link.receive(), drive);
+        assert_eq!(link.oversized_count(), 1);
+        assert_eq!(link.buffered(), 0);
+    }
+}
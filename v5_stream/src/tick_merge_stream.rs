@@ -0,0 +1,110 @@
+use v5_traits::stream::{ReceiveStream, ReceiveTimoutStream};
+use v5_traits::mutex::Mutex;
+use v5_traits::UniversalFunctions;
+use core::time::Duration;
+
+/// An item from the inner stream or a tick because none arrived in time
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum Event<T>{
+    Item(T),
+    Tick,
+}
+
+/// Receives items as soon as they arrive and a Tick whenever period passes without one
+/// The period is measured from when the last event was received so time spent handling it counts,
+/// a loop receiving from this runs at least every period and immediately on new items
+#[derive(Debug)]
+pub struct TickMergeStream<UF, R, M> where UF: UniversalFunctions, R: ReceiveTimoutStream, M: Mutex<Inner=Duration>{
+    uf: UF,
+    stream: R,
+    period: Duration,
+    next_tick: M,
+}
+impl<UF, R, M> TickMergeStream<UF, R, M> where UF: UniversalFunctions, R: ReceiveTimoutStream, M: Mutex<Inner=Duration>{
+    /// The first tick is due period after creation
+    pub fn new(uf: UF, stream: R, period: Duration) -> Self{
+        let next_tick = M::new(uf.system_time() + period);
+        Self{ uf, stream, period, next_tick }
+    }
+
+    pub fn stream(&self) -> &R{
+        &self.stream
+    }
+
+    pub fn period(&self) -> Duration{
+        self.period
+    }
+
+    /// Waits up to end_time for an item, gives a Tick if next_tick comes first
+    fn next_event(&self, next_tick: &mut Duration, end_time: Option<Duration>, uf: &impl UniversalFunctions) -> Option<Event<R::RData>>{
+        let wait_until = end_time.map_or(*next_tick, |end_time| end_time.min(*next_tick));
+        let event = match self.stream.receive_timeout(wait_until.checked_sub(uf.system_time()).unwrap_or_default(), uf){
+            Some(val) => Event::Item(val),
+            None if uf.system_time() >= *next_tick => Event::Tick,
+            None => return None,
+        };
+        *next_tick = uf.system_time() + self.period;
+        Some(event)
+    }
+}
+impl<UF, R, M> ReceiveStream for TickMergeStream<UF, R, M> where UF: UniversalFunctions, R: ReceiveTimoutStream, M: Mutex<Inner=Duration>{
+    type RData = Event<R::RData>;
+
+    fn try_receive(&self) -> Option<Self::RData> {
+        self.next_tick.lock(|next_tick|{
+            let now = self.uf.system_time();
+            let event = match self.stream.try_receive(){
+                Some(val) => Event::Item(val),
+                None if now >= *next_tick => Event::Tick,
+                None => return None,
+            };
+            *next_tick = now + self.period;
+            Some(event)
+        })
+    }
+
+    fn receive(&self) -> Self::RData {
+        self.next_tick.lock(|next_tick|{
+            loop {
+                if let Some(event) = self.next_event(next_tick, None, &self.uf){
+                    return event;
+                }
+            }
+        })
+    }
+}
+impl<UF, R, M> ReceiveTimoutStream for TickMergeStream<UF, R, M> where UF: UniversalFunctions, R: ReceiveTimoutStream, M: Mutex<Inner=Duration>{
+    /// Returns None only if timeout ends before both an item and the next tick
+    fn receive_timeout(&self, timeout: Duration, uf: &impl UniversalFunctions) -> Option<Self::RData> {
+        let end_time = uf.system_time() + timeout;
+        self.next_tick.lock(|next_tick| self.next_event(next_tick, Some(end_time), uf))
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod test{
+    use v5_traits::stream::std_impls::new_mpsc_channel;
+    use v5_traits::stream::{SendStream, ReceiveStream, ReceiveTimoutStream};
+    use parking_lot::Mutex;
+    use crate::tick_merge_stream::{TickMergeStream, Event};
+    use crate::test::Uf;
+    use core::time::Duration;
+    use std::time::Instant;
+
+    #[test]
+    fn tick_merge_stream_test(){
+        let (sender, receiver) = new_mpsc_channel();
+        let events = TickMergeStream::<_, _, Mutex<_>>::new(Uf(), receiver, Duration::from_millis(20));
+        sender.send(1);
+        assert_eq!(events.try_receive(), Some(Event::Item(1)));
+        assert_eq!(events.try_receive(), None);
+        assert_eq!(events.receive_timeout(Duration::from_millis(5), &Uf()), None);
+
+        let start = Instant::now();
+        assert_eq!(events.receive(), Event::Tick);
+        assert!(start.elapsed() >= Duration::from_millis(10));
+
+        sender.send(2);
+        assert_eq!(events.receive(), Event::Item(2));
+    }
+}
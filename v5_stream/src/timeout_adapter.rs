@@ -0,0 +1,106 @@
+use v5_traits::stream::{ReceiveStream, ReceiveTimoutStream};
+use v5_traits::UniversalFunctions;
+use core::time::Duration;
+
+/// The poll interval used by TimeoutAdapter::new
+pub const DEFAULT_POLL_INTERVAL: Duration = Duration::from_millis(1);
+
+/// Adds receive_timeout to a stream that can only try_receive by polling it
+/// Each wait polls every poll_interval at first, doubling the interval after every empty poll up to max_poll_interval.
+/// Short waits stay responsive while long waits use little cpu.
+/// An item can wait up to one poll interval, at most max_poll_interval, before it is received
+#[derive(Debug)]
+pub struct TimeoutAdapter<UF, R> where UF: UniversalFunctions, R: ReceiveStream{
+    uf: UF,
+    stream: R,
+    poll_interval: Duration,
+    max_poll_interval: Duration,
+}
+impl<UF, R> TimeoutAdapter<UF, R> where UF: UniversalFunctions, R: ReceiveStream{
+    /// Polls every DEFAULT_POLL_INTERVAL
+    pub fn new(uf: UF, stream: R) -> Self{
+        Self::with_poll_interval(uf, stream, DEFAULT_POLL_INTERVAL)
+    }
+
+    /// Polls every poll_interval without relaxing
+    pub fn with_poll_interval(uf: UF, stream: R, poll_interval: Duration) -> Self{
+        Self::adaptive(uf, stream, poll_interval, poll_interval)
+    }
+
+    /// Polls every poll_interval at the start of each wait, relaxing up to max_poll_interval
+    pub fn adaptive(uf: UF, stream: R, poll_interval: Duration, max_poll_interval: Duration) -> Self{
+        assert!(poll_interval <= max_poll_interval, "poll_interval must not be greater than max_poll_interval");
+        Self{ uf, stream, poll_interval, max_poll_interval }
+    }
+
+    pub fn stream(&self) -> &R{
+        &self.stream
+    }
+
+    pub fn poll_interval(&self) -> Duration{
+        self.poll_interval
+    }
+
+    pub fn max_poll_interval(&self) -> Duration{
+        self.max_poll_interval
+    }
+
+    /// Polls until an item arrives or end_time passes
+    fn poll(&self, end_time: Option<Duration>, uf: &impl UniversalFunctions) -> Option<R::RData>{
+        let mut interval = self.poll_interval;
+        loop {
+            if let Some(val) = self.stream.try_receive(){
+                return Some(val);
+            }
+            let wait = match end_time{
+                None => interval,
+                Some(end_time) => interval.min(end_time.checked_sub(uf.system_time())?),
+            };
+            uf.delay(wait);
+            interval = (interval * 2).min(self.max_poll_interval);
+        }
+    }
+}
+impl<UF, R> ReceiveStream for TimeoutAdapter<UF, R> where UF: UniversalFunctions, R: ReceiveStream{
+    type RData = R::RData;
+
+    fn try_receive(&self) -> Option<Self::RData> {
+        self.stream.try_receive()
+    }
+
+    /// Polls like receive_timeout rather than calling the inner receive
+    fn receive(&self) -> Self::RData {
+        self.poll(None, &self.uf).unwrap()
+    }
+}
+impl<UF, R> ReceiveTimoutStream for TimeoutAdapter<UF, R> where UF: UniversalFunctions, R: ReceiveStream{
+    fn receive_timeout(&self, timeout: Duration, uf: &impl UniversalFunctions) -> Option<Self::RData> {
+        let end_time = uf.system_time() + timeout;
+        self.poll(Some(end_time), uf)
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod test{
+    use v5_traits::stream::std_impls::new_mpsc_channel;
+    use v5_traits::stream::{SendStream, ReceiveTimoutStream};
+    use crate::timeout_adapter::TimeoutAdapter;
+    use crate::test::Uf;
+    use core::time::Duration;
+
+    #[test]
+    fn timeout_adapter_test(){
+        let (sender, receiver) = new_mpsc_channel();
+        let stream = TimeoutAdapter::adaptive(Uf(), receiver, Duration::from_millis(1), Duration::from_millis(8));
+        assert_eq!(stream.poll_interval(), Duration::from_millis(1));
+        assert_eq!(stream.receive_timeout(Duration::from_millis(20), &Uf()), None);
+
+        std::thread::scope(|scope|{
+            scope.spawn(||{
+                std::thread::sleep(Duration::from_millis(10));
+                sender.send(1);
+            });
+            assert_eq!(stream.receive_timeout(Duration::from_millis(500), &Uf()), Some(1));
+        });
+    }
+}
@@ -0,0 +1,124 @@
+use v5_traits::stream::{SendStream, SendTimeoutStream, ReceiveStream, ReceiveTimoutStream};
+use v5_traits::UniversalFunctions;
+use core::marker::PhantomData;
+use core::sync::atomic::{AtomicUsize, Ordering};
+use core::time::Duration;
+
+/// A queue whose items expire ttl after being sent, expired items are dropped when reached instead of received
+/// Items are stamped with their expiry time from uf, on the brain the queue is a Queue<(Duration, T)>
+#[derive(Debug)]
+pub struct TtlQueue<UF, Q, T> where UF: UniversalFunctions, T: 'static + Send, Q: SendStream<SData=(Duration, T)> + ReceiveStream<RData=(Duration, T)>{
+    uf: UF,
+    queue: Q,
+    ttl: Duration,
+    expired: AtomicUsize,
+    phantom_t: PhantomData<T>,
+}
+impl<UF, Q, T> TtlQueue<UF, Q, T> where UF: UniversalFunctions, T: 'static + Send, Q: SendStream<SData=(Duration, T)> + ReceiveStream<RData=(Duration, T)>{
+    pub fn new(uf: UF, queue: Q, ttl: Duration) -> Self{
+        Self{ uf, queue, ttl, expired: AtomicUsize::new(0), phantom_t: Default::default() }
+    }
+
+    pub fn queue(&self) -> &Q{
+        &self.queue
+    }
+
+    pub fn ttl(&self) -> Duration{
+        self.ttl
+    }
+
+    /// The amount of items dropped for expiring before being received
+    pub fn expired_count(&self) -> usize{
+        self.expired.load(Ordering::SeqCst)
+    }
+
+    /// Sends val to expire after ttl instead of the queue's ttl
+    pub fn send_with_ttl(&self, val: T, ttl: Duration){
+        self.queue.send((self.uf.system_time() + ttl, val))
+    }
+
+    /// Returns val if it has not expired, otherwise drops it and counts it
+    fn fresh(&self, (expiry, val): (Duration, T)) -> Option<T>{
+        if self.uf.system_time() < expiry{
+            Some(val)
+        }
+        else{
+            self.expired.fetch_add(1, Ordering::SeqCst);
+            None
+        }
+    }
+}
+impl<UF, Q, T> SendStream for TtlQueue<UF, Q, T> where UF: UniversalFunctions, T: 'static + Send, Q: SendStream<SData=(Duration, T)> + ReceiveStream<RData=(Duration, T)>{
+    type SData = T;
+
+    fn send(&self, val: Self::SData) {
+        self.send_with_ttl(val, self.ttl)
+    }
+}
+impl<UF, Q, T> SendTimeoutStream for TtlQueue<UF, Q, T> where UF: UniversalFunctions, T: 'static + Send, Q: SendTimeoutStream<SData=(Duration, T)> + ReceiveStream<RData=(Duration, T)>{
+    /// The ttl starts when send_timeout is called, not when val makes it into the queue
+    fn send_timeout(&self, val: Self::SData, timeout: Duration, uf: &impl UniversalFunctions) -> Option<Self::SData> {
+        self.queue.send_timeout((self.uf.system_time() + self.ttl, val), timeout, uf).map(|(_, val)| val)
+    }
+}
+impl<UF, Q, T> ReceiveStream for TtlQueue<UF, Q, T> where UF: UniversalFunctions, T: 'static + Send, Q: SendStream<SData=(Duration, T)> + ReceiveStream<RData=(Duration, T)>{
+    type RData = T;
+
+    /// Drops expired items until a fresh one is found or the queue is empty
+    fn try_receive(&self) -> Option<Self::RData> {
+        while let Some(item) = self.queue.try_receive(){
+            if let Some(val) = self.fresh(item){
+                return Some(val);
+            }
+        }
+        None
+    }
+
+    fn receive(&self) -> Self::RData {
+        loop {
+            if let Some(val) = self.fresh(self.queue.receive()){
+                return val;
+            }
+        }
+    }
+}
+impl<UF, Q, T> ReceiveTimoutStream for TtlQueue<UF, Q, T> where UF: UniversalFunctions, T: 'static + Send, Q: SendStream<SData=(Duration, T)> + ReceiveTimoutStream<RData=(Duration, T)>{
+    fn receive_timeout(&self, timeout: Duration, uf: &impl UniversalFunctions) -> Option<Self::RData> {
+        let end_time = uf.system_time() + timeout;
+        loop {
+            let item = self.queue.receive_timeout(end_time.checked_sub(uf.system_time()).unwrap_or_default(), uf)?;
+            if let Some(val) = self.fresh(item){
+                return Some(val);
+            }
+            if uf.system_time() >= end_time{
+                return None;
+            }
+        }
+    }
+}
+/// This ensures that this is sync if possible because T is only stored in the queue
+unsafe impl<UF, Q, T> Sync for TtlQueue<UF, Q, T> where UF: UniversalFunctions, T: 'static + Send, Q: SendStream<SData=(Duration, T)> + ReceiveStream<RData=(Duration, T)> + Sync{}
+
+#[cfg(all(test, feature = "std"))]
+mod test{
+    use v5_traits::stream::{SendStream, ReceiveStream};
+    use crate::ttl_queue::TtlQueue;
+    use v5_traits::mock::MockClock;
+    use crate::test::TestQueue;
+    use core::time::Duration;
+
+    #[test]
+    fn ttl_queue_test(){
+        let clock = MockClock::default();
+        let queue = TtlQueue::new(clock.clone(), TestQueue::new(4), Duration::from_millis(20));
+        queue.send(1);
+        queue.send_with_ttl(2, Duration::from_secs(10));
+        clock.advance(Duration::from_millis(30));
+        queue.send(3);
+
+        assert_eq!(queue.receive(), 2);
+        assert_eq!(queue.expired_count(), 1);
+        assert_eq!(queue.try_receive(), Some(3));
+        assert_eq!(queue.try_receive(), None);
+    }
+}
@@ -0,0 +1,115 @@
+use v5_traits::stream::{SendStream, SendTimeoutStream, ReceiveStream, ReceiveTimoutStream};
+use v5_traits::sync_cell::SyncCell;
+use v5_traits::UniversalFunctions;
+use alloc::boxed::Box;
+use core::time::Duration;
+
+const POLL_INTERVAL: Duration = Duration::from_millis(1);
+
+/// A queue with a single out of band slot for urgent values such as an estop, receiving always checks the slot first
+/// Only the latest urgent value survives, send_urgent replaces and drops any urgent value that has not been received yet
+/// On the brain the queue is a Queue<T>, blocking receives wait on it at most a millisecond at a time to check the slot
+pub struct UrgentQueue<UF, Q> where UF: UniversalFunctions, Q: SendStream + ReceiveTimoutStream<RData=<Q as SendStream>::SData>{
+    uf: UF,
+    queue: Q,
+    urgent: SyncCell<Q::RData>,
+}
+impl<UF, Q> UrgentQueue<UF, Q> where UF: UniversalFunctions, Q: SendStream + ReceiveTimoutStream<RData=<Q as SendStream>::SData>{
+    pub fn new(uf: UF, queue: Q) -> Self{
+        Self{ uf, queue, urgent: SyncCell::default() }
+    }
+
+    pub fn queue(&self) -> &Q{
+        &self.queue
+    }
+
+    /// Stores val to be received before anything in the queue, dropping any previous urgent value
+    pub fn send_urgent(&self, val: Q::RData){
+        self.urgent.swap(Some(Box::new(val)));
+    }
+
+    /// True if an urgent value is waiting to be received
+    pub fn has_urgent(&self) -> bool{
+        self.urgent.is_some()
+    }
+
+    fn take_urgent(&self) -> Option<Q::RData>{
+        self.urgent.swap(None).map(|val| *val)
+    }
+}
+impl<UF, Q> SendStream for UrgentQueue<UF, Q> where UF: UniversalFunctions, Q: SendStream + ReceiveTimoutStream<RData=<Q as SendStream>::SData>{
+    type SData = Q::SData;
+
+    fn send(&self, val: Self::SData) {
+        self.queue.send(val)
+    }
+}
+impl<UF, Q> SendTimeoutStream for UrgentQueue<UF, Q> where UF: UniversalFunctions, Q: SendTimeoutStream + ReceiveTimoutStream<RData=<Q as SendStream>::SData>{
+    fn send_timeout(&self, val: Self::SData, timeout: Duration, uf: &impl UniversalFunctions) -> Option<Self::SData> {
+        self.queue.send_timeout(val, timeout, uf)
+    }
+}
+impl<UF, Q> ReceiveStream for UrgentQueue<UF, Q> where UF: UniversalFunctions, Q: SendStream + ReceiveTimoutStream<RData=<Q as SendStream>::SData>{
+    type RData = Q::RData;
+
+    fn try_receive(&self) -> Option<Self::RData> {
+        self.take_urgent().or_else(|| self.queue.try_receive())
+    }
+
+    fn receive(&self) -> Self::RData {
+        loop {
+            if let Some(val) = self.take_urgent(){
+                return val;
+            }
+            if let Some(val) = self.queue.receive_timeout(POLL_INTERVAL, &self.uf){
+                return val;
+            }
+        }
+    }
+}
+impl<UF, Q> ReceiveTimoutStream for UrgentQueue<UF, Q> where UF: UniversalFunctions, Q: SendStream + ReceiveTimoutStream<RData=<Q as SendStream>::SData>{
+    fn receive_timeout(&self, timeout: Duration, uf: &impl UniversalFunctions) -> Option<Self::RData> {
+        let end_time = uf.system_time() + timeout;
+        loop {
+            if let Some(val) = self.take_urgent(){
+                return Some(val);
+            }
+            let remaining = end_time.checked_sub(uf.system_time()).unwrap_or_default();
+            if let Some(val) = self.queue.receive_timeout(remaining.min(POLL_INTERVAL), uf){
+                return Some(val);
+            }
+            if remaining.is_zero(){
+                return None;
+            }
+        }
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod test{
+    use v5_traits::stream::{SendStream, ReceiveStream, ReceiveTimoutStream};
+    use crate::urgent_queue::UrgentQueue;
+    use crate::test::{Uf, TestQueue};
+    use core::time::Duration;
+
+    #[test]
+    fn urgent_queue_test(){
+        let queue = UrgentQueue::new(Uf(), TestQueue::new(4));
+        queue.send_slice(&[1, 2]);
+        queue.send_urgent(10);
+        queue.send_urgent(20);
+        assert!(queue.has_urgent());
+
+        assert_eq!(queue.receive(), 20);
+        assert_eq!(queue.receive_vec(2), vec![1, 2]);
+        assert_eq!(queue.receive_timeout(Duration::from_millis(5), &Uf()), None);
+
+        std::thread::scope(|scope|{
+            scope.spawn(||{
+                std::thread::sleep(Duration::from_millis(10));
+                queue.send_urgent(30);
+            });
+            assert_eq!(queue.receive(), 30);
+        });
+    }
+}
@@ -0,0 +1,58 @@
+use v5_traits::stream::{SendStream, SendTimeoutStream};
+use v5_traits::UniversalFunctions;
+use core::marker::PhantomData;
+use core::time::Duration;
+
+/// Checks every message with validator before it is sent into the inner stream
+/// Invalid messages are never sent and their error is returned to the sender instead
+pub struct ValidateStream<S, F, E> where S: SendStream, F: Fn(&S::SData) -> Result<(), E>{
+    stream: S,
+    validator: F,
+    phantom_e: PhantomData<E>,
+}
+impl<S, F, E> ValidateStream<S, F, E> where S: SendStream, F: Fn(&S::SData) -> Result<(), E>{
+    pub fn new(stream: S, validator: F) -> Self{
+        Self{ stream, validator, phantom_e: Default::default() }
+    }
+
+    pub fn stream(&self) -> &S{
+        &self.stream
+    }
+
+    /// Validates val and sends it if valid
+    pub fn try_send(&self, val: S::SData) -> Result<(), E>{
+        (self.validator)(&val)?;
+        self.stream.send(val);
+        Ok(())
+    }
+}
+impl<S, F, E> ValidateStream<S, F, E> where S: SendTimeoutStream, F: Fn(&S::SData) -> Result<(), E>{
+    /// Validates val and sends it if valid
+    /// Returns Ok(Some) with val if it was valid but the timeout was reached
+    pub fn try_send_timeout(&self, val: S::SData, timeout: Duration, uf: &impl UniversalFunctions) -> Result<Option<S::SData>, E>{
+        (self.validator)(&val)?;
+        Ok(self.stream.send_timeout(val, timeout, uf))
+    }
+}
+/// This ensures that this is sync if possible because no E is actually stored
+unsafe impl<S, F, E> Sync for ValidateStream<S, F, E> where S: SendStream + Sync, F: Fn(&S::SData) -> Result<(), E> + Sync{}
+
+#[cfg(all(test, feature = "std"))]
+mod test{
+    use v5_traits::stream::std_impls::new_mpsc_channel;
+    use v5_traits::stream::ReceiveStream;
+    use crate::validate_stream::ValidateStream;
+
+    #[test]
+    fn validate_stream_test(){
+        let (sender, receiver) = new_mpsc_channel();
+        let stream = ValidateStream::new(sender, |val: &i32| if *val >= 0 { Ok(()) } else { Err(*val) });
+
+        assert_eq!(stream.try_send(1), Ok(()));
+        assert_eq!(stream.try_send(-2), Err(-2));
+        assert_eq!(stream.try_send(3), Ok(()));
+
+        assert_eq!(receiver.receive_vec(2), vec![1, 3]);
+        assert_eq!(receiver.try_receive(), None);
+    }
+}
@@ -0,0 +1,159 @@
+use v5_traits::stream::{ReceiveStream, ReceiveTimoutStream};
+use v5_traits::UniversalFunctions;
+use core::time::Duration;
+use core::fmt::{Debug, Formatter};
+use core::sync::atomic::{AtomicU64, Ordering};
+
+/// Wraps a receive stream and calls on_starved if no item arrives within interval
+/// Starvation is measured from the last item received by any receive method, so polling with timeouts or try_receive
+/// shorter than interval still fires it. The callback runs on the receiving task and fires repeatedly,
+/// once per interval, for as long as the stream is starved
+pub struct WatchdogStream<UF, R, F> where UF: UniversalFunctions, R: ReceiveTimoutStream, F: Fn(){
+    uf: UF,
+    stream: R,
+    interval: Duration,
+    on_starved: F,
+    /// System time in nanoseconds of the last item or the last time the watchdog fired
+    last_fed: AtomicU64,
+}
+impl<UF, R, F> WatchdogStream<UF, R, F> where UF: UniversalFunctions, R: ReceiveTimoutStream, F: Fn(){
+    pub fn new(uf: UF, stream: R, interval: Duration, on_starved: F) -> Self{
+        let last_fed = AtomicU64::new(uf.system_time().as_nanos() as u64);
+        Self{ uf, stream, interval, on_starved, last_fed }
+    }
+
+    pub fn stream(&self) -> &R{
+        &self.stream
+    }
+
+    pub fn interval(&self) -> Duration{
+        self.interval
+    }
+
+    fn now(&self) -> u64{
+        self.uf.system_time().as_nanos() as u64
+    }
+
+    fn feed(&self){
+        self.last_fed.store(self.now(), Ordering::SeqCst);
+    }
+
+    /// Fires the watchdog if interval has passed since it was last fed or fired
+    fn check(&self){
+        let now = self.now();
+        let last_fed = self.last_fed.load(Ordering::SeqCst);
+        if now.saturating_sub(last_fed) >= self.interval.as_nanos() as u64
+            && self.last_fed.compare_exchange(last_fed, now, Ordering::SeqCst, Ordering::SeqCst).is_ok(){
+            (self.on_starved)();
+        }
+    }
+
+    /// Time left before the watchdog fires
+    fn until_starved(&self) -> Duration{
+        let elapsed = Duration::from_nanos(self.now().saturating_sub(self.last_fed.load(Ordering::SeqCst)));
+        self.interval.checked_sub(elapsed).unwrap_or_default()
+    }
+}
+impl<UF, R, F> Debug for WatchdogStream<UF, R, F> where UF: UniversalFunctions, R: ReceiveTimoutStream + Debug, F: Fn(){
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("WatchdogStream")
+            .field("uf", &self.uf)
+            .field("stream", &self.stream)
+            .field("interval", &self.interval)
+            .finish()
+    }
+}
+impl<UF, R, F> ReceiveStream for WatchdogStream<UF, R, F> where UF: UniversalFunctions, R: ReceiveTimoutStream, F: Fn(){
+    type RData = R::RData;
+
+    fn try_receive(&self) -> Option<Self::RData> {
+        match self.stream.try_receive(){
+            Some(val) => {
+                self.feed();
+                Some(val)
+            },
+            None => {
+                self.check();
+                None
+            },
+        }
+    }
+
+    fn receive(&self) -> Self::RData {
+        loop {
+            self.check();
+            if let Some(val) = self.stream.receive_timeout(self.until_starved(), &self.uf){
+                self.feed();
+                return val;
+            }
+        }
+    }
+}
+impl<UF, R, F> ReceiveTimoutStream for WatchdogStream<UF, R, F> where UF: UniversalFunctions, R: ReceiveTimoutStream, F: Fn(){
+    fn receive_timeout(&self, timeout: Duration, uf: &impl UniversalFunctions) -> Option<Self::RData> {
+        let end_time = uf.system_time() + timeout;
+        loop {
+            self.check();
+            let remaining = end_time.checked_sub(uf.system_time()).unwrap_or_default();
+            if remaining == Duration::default(){
+                return None;
+            }
+            if let Some(val) = self.stream.receive_timeout(remaining.min(self.until_starved()), uf){
+                self.feed();
+                return Some(val);
+            }
+        }
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod test{
+    use v5_traits::stream::std_impls::new_mpsc_channel;
+    use v5_traits::stream::{SendStream, ReceiveStream, ReceiveTimoutStream};
+    use core::sync::atomic::{AtomicUsize, Ordering};
+    use core::time::Duration;
+    use crate::watchdog_stream::WatchdogStream;
+    use crate::test::Uf;
+    use std::time::Instant;
+
+    #[test]
+    fn watchdog_stream_test(){
+        let (sender, receiver) = new_mpsc_channel::<u32>();
+        let fired = AtomicUsize::new(0);
+        let watchdog = WatchdogStream::new(Uf(), receiver, Duration::from_millis(10), ||{
+            fired.fetch_add(1, Ordering::SeqCst);
+        });
+
+        assert_eq!(watchdog.receive_timeout(Duration::from_millis(55), &Uf()), None);
+        let fired_count = fired.load(Ordering::SeqCst);
+        assert!((3..=5).contains(&fired_count), "Watchdog fired {} times", fired_count);
+
+        sender.send(10);
+        assert_eq!(watchdog.receive_timeout(Duration::from_millis(55), &Uf()), Some(10));
+        assert_eq!(fired.load(Ordering::SeqCst), fired_count);
+    }
+
+    #[test]
+    fn watchdog_stream_polling_test(){
+        let (sender, receiver) = new_mpsc_channel::<u32>();
+        let fired = AtomicUsize::new(0);
+        let watchdog = WatchdogStream::new(Uf(), receiver, Duration::from_millis(20), ||{
+            fired.fetch_add(1, Ordering::SeqCst);
+        });
+
+        let end_time = Instant::now() + Duration::from_millis(70);
+        while Instant::now() < end_time{
+            assert_eq!(watchdog.receive_timeout(Duration::from_millis(5), &Uf()), None);
+        }
+        let fired_count = fired.load(Ordering::SeqCst);
+        assert!((2..=4).contains(&fired_count), "Watchdog fired {} times", fired_count);
+
+        sender.send(1);
+        assert_eq!(watchdog.try_receive(), Some(1));
+        let end_time = Instant::now() + Duration::from_millis(30);
+        while Instant::now() < end_time{
+            assert_eq!(watchdog.try_receive(), None);
+        }
+        assert_eq!(fired.load(Ordering::SeqCst), fired_count + 1);
+    }
+}
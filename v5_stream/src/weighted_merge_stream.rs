@@ -0,0 +1,105 @@
+use v5_traits::stream::{ReceiveStream, ReceiveTimoutStream};
+use v5_traits::mutex::Mutex;
+use v5_traits::UniversalFunctions;
+use alloc::boxed::Box;
+use alloc::vec::Vec;
+use core::time::Duration;
+
+pub type WeightedSource<T> = (Box<dyn ReceiveStream<RData=T> + Send + Sync>, u32);
+
+/// Where a WeightedMergeStream is in its polling schedule
+#[derive(Debug, Default)]
+pub struct WeightedMergeState{
+    source: usize,
+    taken: u32,
+}
+
+/// Merges several receive streams, polling them in weighted round robin order
+/// Each source in turn is received from until it has given weight items in a row or is empty, then the next source is polled.
+/// While every source has items each receives exactly its weight per round, an empty source gives up the rest of its turn
+/// so no item waits on more than the sum of the other weights. Sources with weight 0 are never polled.
+/// Blocking receives poll every poll_interval so an item can wait up to poll_interval before being received
+pub struct WeightedMergeStream<UF, T, M> where UF: UniversalFunctions, T: 'static + Send, M: Mutex<Inner=WeightedMergeState>{
+    uf: UF,
+    sources: Vec<WeightedSource<T>>,
+    poll_interval: Duration,
+    state: M,
+}
+impl<UF, T, M> WeightedMergeStream<UF, T, M> where UF: UniversalFunctions, T: 'static + Send, M: Mutex<Inner=WeightedMergeState>{
+    pub fn new(uf: UF, sources: Vec<WeightedSource<T>>, poll_interval: Duration) -> Self{
+        Self{ uf, sources, poll_interval, state: M::new(WeightedMergeState::default()) }
+    }
+
+    pub fn weights(&self) -> impl Iterator<Item=u32> + '_{
+        self.sources.iter().map(|(_, weight)| *weight)
+    }
+}
+impl<UF, T, M> ReceiveStream for WeightedMergeStream<UF, T, M> where UF: UniversalFunctions, T: 'static + Send, M: Mutex<Inner=WeightedMergeState>{
+    type RData = T;
+
+    /// Polls each source at most once in schedule order
+    fn try_receive(&self) -> Option<Self::RData> {
+        self.state.lock(|state|{
+            for _ in 0..self.sources.len(){
+                let (source, weight) = &self.sources[state.source];
+                if state.taken < *weight{
+                    if let Some(val) = source.try_receive(){
+                        state.taken += 1;
+                        if state.taken >= *weight{
+                            state.source = (state.source + 1) % self.sources.len();
+                            state.taken = 0;
+                        }
+                        return Some(val);
+                    }
+                }
+                state.source = (state.source + 1) % self.sources.len();
+                state.taken = 0;
+            }
+            None
+        })
+    }
+
+    fn receive(&self) -> Self::RData {
+        loop {
+            if let Some(val) = self.try_receive(){
+                return val;
+            }
+            self.uf.delay(self.poll_interval);
+        }
+    }
+}
+impl<UF, T, M> ReceiveTimoutStream for WeightedMergeStream<UF, T, M> where UF: UniversalFunctions, T: 'static + Send, M: Mutex<Inner=WeightedMergeState>{
+    fn receive_timeout(&self, timeout: Duration, uf: &impl UniversalFunctions) -> Option<Self::RData> {
+        let end_time = uf.system_time() + timeout;
+        loop {
+            if let Some(val) = self.try_receive(){
+                return Some(val);
+            }
+            let remaining = end_time.checked_sub(uf.system_time())?;
+            uf.delay(remaining.min(self.poll_interval));
+        }
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod test{
+    use v5_traits::stream::std_impls::new_mpsc_channel;
+    use v5_traits::stream::{SendStream, ReceiveStream};
+    use parking_lot::Mutex;
+    use crate::weighted_merge_stream::WeightedMergeStream;
+    use crate::test::Uf;
+    use alloc::boxed::Box;
+    use core::time::Duration;
+
+    #[test]
+    fn weighted_merge_stream_test(){
+        let (sensor_sender, sensor_receiver) = new_mpsc_channel();
+        let (log_sender, log_receiver) = new_mpsc_channel();
+        let merged = WeightedMergeStream::<_, _, Mutex<_>>::new(Uf(), vec![(Box::new(sensor_receiver), 3), (Box::new(log_receiver), 1)], Duration::from_millis(1));
+        sensor_sender.send_slice(&[1, 2, 3, 4, 5]);
+        log_sender.send_slice(&[10, 11, 12]);
+
+        assert_eq!(merged.receive_vec(8), vec![1, 2, 3, 10, 4, 5, 11, 12]);
+        assert_eq!(merged.try_receive(), None);
+    }
+}
@@ -0,0 +1,118 @@
+use v5_traits::stream::{ReceiveStream, ReceiveTimoutStream};
+use v5_traits::mutex::Mutex;
+use v5_traits::UniversalFunctions;
+use core::array::from_fn;
+use core::time::Duration;
+
+/// Receives the last N items, oldest first, every time a new item arrives
+/// Until N items have arrived receiving keeps pulling from the inner stream
+#[derive(Debug)]
+pub struct WindowStream<R, M, const N: usize> where R: ReceiveStream, R::RData: Copy, M: Mutex<Inner=WindowState<R::RData, N>>{
+    stream: R,
+    state: M,
+}
+impl<R, M, const N: usize> WindowStream<R, M, N> where R: ReceiveStream, R::RData: Copy, M: Mutex<Inner=WindowState<R::RData, N>>{
+    pub fn new(stream: R) -> Self{
+        Self{ stream, state: M::new(WindowState::default()) }
+    }
+
+    pub fn stream(&self) -> &R{
+        &self.stream
+    }
+
+    /// True once N items have been received
+    pub fn is_primed(&self) -> bool{
+        self.state.lock(|state| state.filled == N)
+    }
+}
+impl<R, M, const N: usize> ReceiveStream for WindowStream<R, M, N> where R: ReceiveStream, R::RData: Copy, M: Mutex<Inner=WindowState<R::RData, N>>{
+    type RData = [R::RData; N];
+
+    fn try_receive(&self) -> Option<Self::RData> {
+        self.state.lock(|state|{
+            loop {
+                state.push(self.stream.try_receive()?);
+                if let Some(window) = state.window(){
+                    return Some(window);
+                }
+            }
+        })
+    }
+
+    fn receive(&self) -> Self::RData {
+        self.state.lock(|state|{
+            loop {
+                state.push(self.stream.receive());
+                if let Some(window) = state.window(){
+                    return window;
+                }
+            }
+        })
+    }
+}
+impl<R, M, const N: usize> ReceiveTimoutStream for WindowStream<R, M, N> where R: ReceiveTimoutStream, R::RData: Copy, M: Mutex<Inner=WindowState<R::RData, N>>{
+    fn receive_timeout(&self, timeout: Duration, uf: &impl UniversalFunctions) -> Option<Self::RData> {
+        let end_time = uf.system_time() + timeout;
+        self.state.lock(|state|{
+            loop {
+                let remaining = end_time.checked_sub(uf.system_time()).unwrap_or_default();
+                state.push(self.stream.receive_timeout(remaining, uf)?);
+                if let Some(window) = state.window(){
+                    return Some(window);
+                }
+            }
+        })
+    }
+}
+
+/// The last N items received by a WindowStream
+#[derive(Debug)]
+pub struct WindowState<T, const N: usize> where T: Copy{
+    items: [Option<T>; N],
+    next: usize,
+    filled: usize,
+}
+impl<T, const N: usize> WindowState<T, N> where T: Copy{
+    fn push(&mut self, val: T){
+        if N == 0{
+            return;
+        }
+        self.items[self.next] = Some(val);
+        self.next = (self.next + 1) % N;
+        self.filled = N.min(self.filled + 1);
+    }
+
+    fn window(&self) -> Option<[T; N]>{
+        if self.filled < N{
+            return None;
+        }
+        Some(from_fn(|index| self.items[(self.next + index) % N].unwrap()))
+    }
+}
+impl<T, const N: usize> Default for WindowState<T, N> where T: Copy{
+    fn default() -> Self {
+        Self{ items: [None; N], next: 0, filled: 0 }
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod test{
+    use v5_traits::stream::std_impls::new_mpsc_channel;
+    use v5_traits::stream::{SendStream, ReceiveStream};
+    use parking_lot::Mutex;
+    use crate::window_stream::WindowStream;
+
+    #[test]
+    fn window_stream_test(){
+        let (sender, receiver) = new_mpsc_channel();
+        let window = WindowStream::<_, Mutex<_>, 3>::new(receiver);
+        sender.send_slice(&[1, 2]);
+        assert_eq!(window.try_receive(), None);
+        assert!(!window.is_primed());
+
+        sender.send_slice(&[3, 4]);
+        assert_eq!(window.receive(), [1, 2, 3]);
+        assert_eq!(window.receive(), [2, 3, 4]);
+        assert_eq!(window.try_receive(), None);
+    }
+}
@@ -0,0 +1,97 @@
+use crate::error::Error;
+use alloc::boxed::Box;
+use alloc::vec::Vec;
+use core::fmt::Debug;
+use core::mem::size_of;
+use core::ptr::read_unaligned;
+
+/// Turns values of T into bytes and back so streams are not tied to one serializer
+pub trait Codec<T>{
+    /// Appends the encoded val to buffer, on error buffer is left as it was
+    fn encode(&self, val: &T, buffer: &mut Vec<u8>) -> Result<(), EncodeError>;
+    fn decode(&self, data: &[u8]) -> Result<T, DecodeError>;
+
+    fn encode_to_vec(&self, val: &T) -> Result<Vec<u8>, EncodeError>{
+        let mut out = Vec::new();
+        self.encode(val, &mut out)?;
+        Ok(out)
+    }
+}
+
+/// The codec could not encode the value, holds the serializer's error
+#[derive(Debug)]
+pub struct EncodeError(pub Box<dyn Debug + Send + Sync>);
+impl Error for EncodeError{
+    fn is_recoverable(&self) -> bool {
+        true
+    }
+}
+
+#[derive(Debug)]
+pub enum DecodeError{
+    /// The data was not the length the codec needs
+    WrongLength{ expected: usize, received: usize },
+    /// The codec could not parse the data
+    Invalid(Box<dyn Debug + Send + Sync>),
+}
+impl Error for DecodeError{
+    fn is_recoverable(&self) -> bool {
+        true
+    }
+}
+
+/// Types that can be copied as raw bytes, any bit pattern of the right size must be a valid value
+/// # Safety
+/// Only implement for #[repr(C)] or primitive types made entirely of other RawBytes types with no padding
+pub unsafe trait RawBytes: 'static + Copy{}
+macro_rules! impl_raw_bytes {
+    ($($ty:ty),*) => {
+        $(unsafe impl RawBytes for $ty{})*
+    };
+}
+impl_raw_bytes!(u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize, f32, f64);
+
+/// Encodes values as their in memory bytes, zero overhead but only usable between identical targets
+#[derive(Copy, Clone, Debug, Default)]
+pub struct RawBytesCodec;
+impl<T> Codec<T> for RawBytesCodec where T: RawBytes{
+    fn encode(&self, val: &T, buffer: &mut Vec<u8>) -> Result<(), EncodeError> {
+        let bytes = unsafe { core::slice::from_raw_parts(val as *const T as *const u8, size_of::<T>()) };
+        buffer.extend_from_slice(bytes);
+        Ok(())
+    }
+
+    fn decode(&self, data: &[u8]) -> Result<T, DecodeError> {
+        if data.len() != size_of::<T>(){
+            return Err(DecodeError::WrongLength{ expected: size_of::<T>(), received: data.len() });
+        }
+        Ok(unsafe { read_unaligned(data.as_ptr() as *const T) })
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod test{
+    use crate::codec::{Codec, RawBytes, RawBytesCodec, DecodeError};
+
+    #[derive(Copy, Clone, Debug, PartialEq)]
+    #[repr(C)]
+    struct Command{
+        left: f32,
+        right: f32,
+        id: u32,
+    }
+    unsafe impl RawBytes for Command{}
+
+    #[test]
+    fn raw_bytes_codec_test(){
+        let command = Command{ left: 0.5, right: -1.0, id: 7 };
+        let encoded = RawBytesCodec.encode_to_vec(&command).unwrap();
+        assert_eq!(encoded.len(), 12);
+        assert_eq!(Codec::<Command>::decode(&RawBytesCodec, &encoded).unwrap(), command);
+
+        match Codec::<Command>::decode(&RawBytesCodec, &encoded[1..]){
+            Err(DecodeError::WrongLength{ expected: 12, received: 11 }) => {},
+            other => panic!("Expected WrongLength, got {:?}", other),
+        }
+    }
+}
@@ -0,0 +1,138 @@
+use crate::sync_cell::SyncCell;
+use alloc::boxed::Box;
+use core::sync::atomic::{AtomicU64, Ordering};
+
+/// A SyncCell that counts the changes made to it so readers can tell if the value changed since they last looked
+/// For caches that recompute derived state only when the source changes. Every operation is SeqCst
+pub struct GenerationalSyncCell<T>{
+    cell: SyncCell<T>,
+    generation: AtomicU64,
+}
+impl<T> GenerationalSyncCell<T>{
+    pub fn new(value: Option<Box<T>>) -> Self{
+        Self{ cell: SyncCell::new(value), generation: AtomicU64::new(0) }
+    }
+
+    /// An empty cell, usable in statics
+    pub const fn empty() -> Self{
+        Self{ cell: SyncCell::empty(), generation: AtomicU64::new(0) }
+    }
+
+    pub fn swap(&self, new: Option<Box<T>>) -> Option<Box<T>>{
+        let out = self.cell.swap(new);
+        self.changed();
+        out
+    }
+
+    /// Empties the cell and returns what it held
+    pub fn take(&self) -> Option<Box<T>>{
+        self.swap(None)
+    }
+
+    /// Stores value and returns what the cell held before
+    pub fn replace(&self, value: Box<T>) -> Option<Box<T>>{
+        self.swap(Some(value))
+    }
+
+    /// See SyncCell::compare_exchange, only a successful exchange changes the generation
+    pub fn compare_exchange(&self, current: *mut T, new: Option<Box<T>>) -> Result<Option<Box<T>>, Option<Box<T>>>{
+        let out = self.cell.compare_exchange(current, new);
+        if out.is_ok(){
+            self.changed();
+        }
+        out
+    }
+
+    /// See SyncCell::update
+    pub fn update<F>(&self, f: F) where F: FnMut(Option<Box<T>>) -> Option<Box<T>>{
+        self.cell.update(f);
+        self.changed();
+    }
+
+    /// See SyncCell::as_ptr
+    pub fn as_ptr(&self) -> *mut T{
+        self.cell.as_ptr()
+    }
+
+    /// See SyncCell::clone_inner
+    pub fn clone_inner(&self) -> Option<T> where T: Clone{
+        self.cell.clone_inner()
+    }
+
+    pub fn is_some(&self) -> bool{
+        self.cell.is_some()
+    }
+
+    /// The amount of changes made to this cell
+    pub fn generation(&self) -> u64{
+        self.generation.load(Ordering::SeqCst)
+    }
+
+    /// Clones the held value along with the generation it belongs to
+    /// The generation is incremented after each change so a reader can see a new value with the old generation but never the reverse,
+    /// a cache keyed on it recomputes spuriously at worst
+    pub fn load_with_generation(&self) -> (Option<Box<T>>, u64) where T: Clone{
+        loop {
+            let generation = self.generation();
+            let out = self.cell.clone_inner();
+            if self.generation() == generation{
+                return (out.map(Box::new), generation);
+            }
+        }
+    }
+
+    fn changed(&self){
+        self.generation.fetch_add(1, Ordering::SeqCst);
+    }
+}
+impl<T> Default for GenerationalSyncCell<T>{
+    fn default() -> Self {
+        Self::new(None)
+    }
+}
+impl<T> From<T> for GenerationalSyncCell<T>{
+    fn from(from: T) -> Self {
+        Self::new(Some(Box::new(from)))
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod test{
+    use crate::generational_sync_cell::GenerationalSyncCell;
+    use alloc::boxed::Box;
+    use alloc::sync::Arc;
+    use alloc::vec::Vec;
+    use core::ptr::null_mut;
+    use std::thread::spawn;
+
+    #[test]
+    fn load_with_generation_test(){
+        let cell = GenerationalSyncCell::from(1);
+        assert_eq!(cell.load_with_generation(), (Some(Box::new(1)), 0));
+        cell.swap(Some(Box::new(2)));
+        cell.swap(None);
+        assert_eq!(cell.load_with_generation(), (None, 2));
+        assert_eq!(cell.generation(), 2);
+
+        assert!(cell.compare_exchange(cell.as_ptr(), Some(Box::new(3))).is_ok());
+        assert!(cell.compare_exchange(null_mut(), Some(Box::new(4))).is_err());
+        assert_eq!(cell.load_with_generation(), (Some(Box::new(3)), 3));
+    }
+
+    #[test]
+    fn update_generation_test(){
+        let cell = Arc::new(GenerationalSyncCell::from(0));
+        let updaters: Vec<_> = (0..2).map(|_|{
+            let cell = cell.clone();
+            spawn(move ||{
+                for _ in 0..1000{
+                    cell.update(|count| count.map(|count| Box::new(*count + 1)));
+                }
+            })
+        }).collect();
+        for updater in updaters{
+            updater.join().unwrap();
+        }
+        assert_eq!(cell.load_with_generation(), (Some(Box::new(2000)), 2000));
+    }
+}
@@ -6,10 +6,16 @@ extern crate std;
 #[macro_use]
 extern crate alloc;
 
+pub mod codec;
 pub mod error;
+pub mod generational_sync_cell;
+#[cfg(feature = "mock")]
+pub mod mock;
 pub mod stream;
 pub mod mutex;
+pub mod param_store;
 pub mod sync_cell;
+pub mod sync_cell_table;
 pub mod task;
 
 use core::time::Duration;
@@ -0,0 +1,72 @@
+use crate::{UniversalFunctions, LogLevel};
+use alloc::sync::Arc;
+use core::fmt::Display;
+use core::sync::atomic::{AtomicU64, Ordering};
+use core::time::Duration;
+
+/// UniversalFunctions with a clock that only moves when told to, for testing time dependent code without real delays
+/// Clones share the same clock. delay advances the clock by its duration and returns immediately,
+/// so a test of a single task sees exactly the time it waited. With several threads each thread's delays add to the shared clock
+#[derive(Clone, Debug, Default)]
+pub struct MockClock{
+    nanos: Arc<AtomicU64>,
+}
+impl MockClock{
+    /// Creates a clock starting at time
+    pub fn new(time: Duration) -> Self{
+        Self{ nanos: Arc::new(AtomicU64::new(time.as_nanos() as u64)) }
+    }
+
+    pub fn advance(&self, duration: Duration){
+        self.nanos.fetch_add(duration.as_nanos() as u64, Ordering::SeqCst);
+    }
+
+    /// Moves the clock to time, which may be earlier than the current time
+    pub fn set(&self, time: Duration){
+        self.nanos.store(time.as_nanos() as u64, Ordering::SeqCst);
+    }
+}
+impl UniversalFunctions for MockClock{
+    fn delay(&self, duration: Duration) {
+        self.advance(duration);
+        std::thread::yield_now();
+    }
+
+    fn system_time(&self) -> Duration {
+        Duration::from_nanos(self.nanos.load(Ordering::SeqCst))
+    }
+
+    fn print(&self, out: impl Display) {
+        print!("{}", out)
+    }
+
+    fn eprint(&self, out: impl Display) {
+        eprint!("{}", out)
+    }
+
+    fn min_log_level(&self) -> LogLevel {
+        LogLevel::TRACE
+    }
+
+    fn log_intern(&self, message: impl Display, _level: LogLevel) {
+        eprintln!("{}", message)
+    }
+}
+
+#[cfg(test)]
+mod test{
+    use crate::mock::MockClock;
+    use crate::UniversalFunctions;
+    use core::time::Duration;
+
+    #[test]
+    fn mock_clock_test(){
+        let clock = MockClock::new(Duration::from_secs(1));
+        let shared = clock.clone();
+        clock.advance(Duration::from_millis(20));
+        shared.delay(Duration::from_millis(5));
+        assert_eq!(clock.system_time(), Duration::from_millis(1025));
+        clock.set(Duration::default());
+        assert_eq!(shared.system_time(), Duration::default());
+    }
+}
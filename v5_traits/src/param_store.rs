@@ -0,0 +1,67 @@
+use crate::sync_cell::SyncCell;
+use alloc::boxed::Box;
+use alloc::vec::Vec;
+use core::marker::PhantomData;
+
+/// A key enum for a ParamStore
+/// index must be unique for each key and less than COUNT, usually the enum discriminant
+pub trait ParamKey: Copy{
+    const COUNT: usize;
+
+    fn index(self) -> usize;
+}
+
+/// A fixed set of parameters keyed by K that can be read and set from any task
+pub struct ParamStore<K, T = f64> where K: ParamKey, T: Clone{
+    params: Box<[SyncCell<T>]>,
+    phantom_k: PhantomData<K>,
+}
+impl<K, T> ParamStore<K, T> where K: ParamKey, T: Clone{
+    /// Creates a store with every parameter set to default
+    pub fn new(default: T) -> Self{
+        Self{
+            params: (0..K::COUNT).map(|_| SyncCell::from(default.clone())).collect::<Vec<_>>().into_boxed_slice(),
+            phantom_k: Default::default(),
+        }
+    }
+
+    pub fn get(&self, param: K) -> T{
+        self.params[param.index()].clone_inner().expect("ParamStore cells are never emptied")
+    }
+
+    pub fn set(&self, param: K, val: T){
+        self.params[param.index()].swap(Some(Box::new(val)));
+    }
+}
+/// This ensures that this is sync if possible because no K is actually stored
+unsafe impl<K, T> Sync for ParamStore<K, T> where K: ParamKey, T: Clone + Send{}
+
+#[cfg(all(test, feature = "std"))]
+mod test{
+    use crate::param_store::{ParamKey, ParamStore};
+
+    #[derive(Copy, Clone, Debug)]
+    enum Gain{
+        P,
+        I,
+        D,
+    }
+    impl ParamKey for Gain{
+        const COUNT: usize = 3;
+
+        fn index(self) -> usize {
+            self as usize
+        }
+    }
+
+    #[test]
+    fn param_store_test(){
+        let store = ParamStore::<Gain>::new(0.0);
+        store.set(Gain::P, 1.5);
+        store.set(Gain::D, 0.25);
+
+        assert_eq!(store.get(Gain::P), 1.5);
+        assert_eq!(store.get(Gain::I), 0.0);
+        assert_eq!(store.get(Gain::D), 0.25);
+    }
+}
@@ -0,0 +1,190 @@
+use core::future::Future;
+use core::pin::Pin;
+use core::task::{Context, Poll};
+use core::time::Duration;
+use crate::stream::{SendTimeoutStream, ReceiveStream};
+use crate::UniversalFunctions;
+
+/// A stream that is received from by polling, for awaiting items instead of blocking a task
+pub trait AsyncReceiveStream{
+    type RData: 'static + Send;
+
+    /// Returns the next item if one is available, otherwise returns Pending and wakes cx's waker once it should be polled again
+    fn poll_receive(&self, cx: &mut Context<'_>) -> Poll<Self::RData>;
+
+    fn receive_async(&self) -> ReceiveFuture<'_, Self> where Self: Sized{
+        ReceiveFuture{ stream: self }
+    }
+}
+/// A stream that is sent to by polling, for awaiting space instead of blocking a task
+pub trait AsyncSendStream{
+    type SData: 'static + Send;
+
+    /// Takes and sends the item in val if there is space, otherwise leaves it, returns Pending
+    /// and wakes cx's waker once it should be polled again. Returns Ready immediately if val is None
+    fn poll_send(&self, val: &mut Option<Self::SData>, cx: &mut Context<'_>) -> Poll<()>;
+
+    fn send_async(&self, val: Self::SData) -> SendFuture<'_, Self> where Self: Sized{
+        SendFuture{ stream: self, val: Some(val) }
+    }
+}
+
+#[derive(Debug)]
+pub struct ReceiveFuture<'a, S> where S: AsyncReceiveStream{
+    stream: &'a S,
+}
+impl<'a, S> Future for ReceiveFuture<'a, S> where S: AsyncReceiveStream{
+    type Output = S::RData;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<S::RData> {
+        self.stream.poll_receive(cx)
+    }
+}
+
+#[derive(Debug)]
+pub struct SendFuture<'a, S> where S: AsyncSendStream{
+    stream: &'a S,
+    val: Option<S::SData>,
+}
+/// The item is never pinned, it is moved into the stream when sent
+impl<'a, S> Unpin for SendFuture<'a, S> where S: AsyncSendStream{}
+impl<'a, S> Future for SendFuture<'a, S> where S: AsyncSendStream{
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        let this = self.get_mut();
+        this.stream.poll_send(&mut this.val, cx)
+    }
+}
+
+/// Makes a blocking stream such as a Queue awaitable by polling it without blocking
+/// Each poll that finds nothing wakes the waker right away to yield to the executor and be polled again,
+/// so the executor should delay between polls of a pending task or this busy waits
+#[derive(Debug)]
+pub struct PollingStream<UF, S> where UF: UniversalFunctions{
+    uf: UF,
+    stream: S,
+}
+impl<UF, S> PollingStream<UF, S> where UF: UniversalFunctions{
+    pub fn new(uf: UF, stream: S) -> Self{
+        Self{ uf, stream }
+    }
+
+    pub fn stream(&self) -> &S{
+        &self.stream
+    }
+
+    /// Receives like receive_async but resolves to None once timeout has passed with nothing received
+    pub fn receive_timeout_async(&self, timeout: Duration) -> ReceiveTimeoutFuture<'_, UF, S> where S: ReceiveStream{
+        ReceiveTimeoutFuture{ stream: self, end_time: self.uf.system_time() + timeout }
+    }
+}
+impl<UF, S> AsyncReceiveStream for PollingStream<UF, S> where UF: UniversalFunctions, S: ReceiveStream{
+    type RData = S::RData;
+
+    fn poll_receive(&self, cx: &mut Context<'_>) -> Poll<Self::RData> {
+        match self.stream.try_receive(){
+            Some(val) => Poll::Ready(val),
+            None => {
+                cx.waker().wake_by_ref();
+                Poll::Pending
+            },
+        }
+    }
+}
+impl<UF, S> AsyncSendStream for PollingStream<UF, S> where UF: UniversalFunctions, S: SendTimeoutStream{
+    type SData = S::SData;
+
+    fn poll_send(&self, val: &mut Option<Self::SData>, cx: &mut Context<'_>) -> Poll<()> {
+        if let Some(to_send) = val.take(){
+            if let Some(returned) = self.stream.send_timeout(to_send, Duration::new(0, 0), &self.uf){
+                *val = Some(returned);
+                cx.waker().wake_by_ref();
+                return Poll::Pending;
+            }
+        }
+        Poll::Ready(())
+    }
+}
+
+#[derive(Debug)]
+pub struct ReceiveTimeoutFuture<'a, UF, S> where UF: UniversalFunctions, S: ReceiveStream{
+    stream: &'a PollingStream<UF, S>,
+    end_time: Duration,
+}
+impl<'a, UF, S> Future for ReceiveTimeoutFuture<'a, UF, S> where UF: UniversalFunctions, S: ReceiveStream{
+    type Output = Option<S::RData>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<S::RData>> {
+        match self.stream.poll_receive(cx){
+            Poll::Ready(val) => Poll::Ready(Some(val)),
+            Poll::Pending if self.stream.uf.system_time() >= self.end_time => Poll::Ready(None),
+            Poll::Pending => Poll::Pending,
+        }
+    }
+}
+
+#[cfg(all(test, feature = "mock"))]
+mod test{
+    use crate::stream::async_stream::{AsyncSendStream, AsyncReceiveStream, PollingStream};
+    use crate::mock::MockClock;
+    use crate::stream::std_impls::new_mpsc_channel;
+    use crate::stream::{SendStream, ReceiveStream};
+    use alloc::boxed::Box;
+    use core::future::Future;
+    use core::pin::Pin;
+    use core::ptr::null;
+    use core::task::{Context, Poll, RawWaker, RawWakerVTable, Waker};
+    use core::time::Duration;
+    use std::thread::{sleep, spawn};
+
+    const NOOP_VTABLE: RawWakerVTable = RawWakerVTable::new(|_| RawWaker::new(null(), &NOOP_VTABLE), |_| {}, |_| {}, |_| {});
+
+    /// Polls future every millisecond until it completes, returns it and the amount of polls
+    fn block_on<F>(future: F) -> (F::Output, usize) where F: Future{
+        let waker = unsafe { Waker::from_raw(RawWaker::new(null(), &NOOP_VTABLE)) };
+        let mut context = Context::from_waker(&waker);
+        let mut future = Box::pin(future);
+        let mut polls = 0;
+        loop {
+            polls += 1;
+            if let Poll::Ready(out) = Pin::as_mut(&mut future).poll(&mut context){
+                return (out, polls);
+            }
+            sleep(Duration::from_millis(1));
+        }
+    }
+
+    #[test]
+    fn polling_stream_test(){
+        let (sender, receiver) = new_mpsc_channel();
+        let receiver = PollingStream::new(MockClock::default(), receiver);
+        let producer = spawn(move ||{
+            sleep(Duration::from_millis(20));
+            sender.send(7u32);
+        });
+        let (received, polls) = block_on(async { receiver.receive_async().await + 1 });
+        producer.join().unwrap();
+        assert_eq!(received, 8);
+        assert!(polls > 1, "Received without waiting");
+
+        let (sender, receiver) = new_mpsc_channel();
+        let sender = PollingStream::new(MockClock::default(), sender);
+        block_on(sender.send_async(3u32));
+        assert_eq!(receiver.try_receive(), Some(3));
+    }
+
+    #[test]
+    fn polling_stream_timeout_test(){
+        let clock = MockClock::default();
+        let (_sender, receiver) = new_mpsc_channel::<u32>();
+        let receiver = PollingStream::new(clock.clone(), receiver);
+        let waker = unsafe { Waker::from_raw(RawWaker::new(null(), &NOOP_VTABLE)) };
+        let mut context = Context::from_waker(&waker);
+        let mut future = receiver.receive_timeout_async(Duration::from_millis(10));
+
+        assert_eq!(Pin::new(&mut future).poll(&mut context), Poll::Pending);
+        clock.advance(Duration::from_millis(10));
+        assert_eq!(Pin::new(&mut future).poll(&mut context), Poll::Ready(None));
+    }
+}
@@ -1,3 +1,4 @@
+pub mod async_stream;
 #[cfg(feature = "std")]
 pub mod std_impls;
 
@@ -24,6 +25,11 @@ pub trait SendStream{
             self.send(val)
         }
     }
+    /// Sends a fixed size batch without allocating
+    /// Requires Sized so SendStream stays usable as a trait object
+    fn send_array<const N: usize>(&self, array: [Self::SData; N]) where Self: Sized, Self::SData: Copy{
+        self.send_slice(&array)
+    }
 }
 impl<S> SendStream for Arc<S> where S: SendStream{
     type SData = S::SData;
@@ -39,6 +45,10 @@ impl<S> SendStream for Arc<S> where S: SendStream{
     fn send_vec(&self, data: Vec<Self::SData>) {
         self.deref().send_vec(data)
     }
+
+    fn send_array<const N: usize>(&self, array: [Self::SData; N]) where Self: Sized, Self::SData: Copy {
+        self.deref().send_array(array)
+    }
 }
 pub trait SendTimeoutStream: SendStream{
     fn send_timeout(&self, val: Self::SData, timeout: Duration, uf: &impl UniversalFunctions) -> Option<Self::SData>;
@@ -170,6 +180,12 @@ pub trait ReceiveTimoutStream: ReceiveStream{
         }
         out
     }
+    /// Same as receive_timeout but also returns how long the call waited measured with uf's clock
+    fn receive_timeout_timed(&self, timeout: Duration, uf: &impl UniversalFunctions) -> (Option<Self::RData>, Duration){
+        let start = uf.system_time();
+        let out = self.receive_timeout(timeout, uf);
+        (out, uf.system_time().checked_sub(start).unwrap_or_default())
+    }
 }
 impl<S> ReceiveTimoutStream for Arc<S> where S: ReceiveTimoutStream{
     fn receive_timeout(&self, timeout: Duration, uf: &impl UniversalFunctions) -> Option<Self::RData> {
@@ -183,6 +199,54 @@ impl<S> ReceiveTimoutStream for Arc<S> where S: ReceiveTimoutStream{
     fn receive_vec_timeout(&self, limit: usize, timeout: Duration, uf: &impl UniversalFunctions) -> Vec<Self::RData> {
         self.deref().receive_vec_timeout(limit, timeout, uf)
     }
+
+    fn receive_timeout_timed(&self, timeout: Duration, uf: &impl UniversalFunctions) -> (Option<Self::RData>, Duration) {
+        self.deref().receive_timeout_timed(timeout, uf)
+    }
+}
+
+/// A stream that may hold a limited number of items, used by generic code to size batches
+/// Unbounded streams keep the default of None
+pub trait BoundedStream{
+    fn capacity(&self) -> Option<usize>{
+        None
+    }
+}
+impl<S> BoundedStream for Arc<S> where S: BoundedStream{
+    fn capacity(&self) -> Option<usize> {
+        self.deref().capacity()
+    }
+}
+
+/// A stream whose producers can signal that no more items will be sent
+/// receive keeps blocking forever on a closed empty stream, receive_or_closed reports it instead
+pub trait ClosableStream: ReceiveStream{
+    /// Closes the stream, items already in it can still be received
+    fn close(&self);
+    fn is_closed(&self) -> bool;
+    /// Blocks until an item is available or the stream is closed and empty
+    fn receive_or_closed(&self) -> Result<Self::RData, Closed>;
+}
+impl<S> ClosableStream for Arc<S> where S: ClosableStream{
+    fn close(&self) {
+        self.deref().close()
+    }
+
+    fn is_closed(&self) -> bool {
+        self.deref().is_closed()
+    }
+
+    fn receive_or_closed(&self) -> Result<Self::RData, Closed> {
+        self.deref().receive_or_closed()
+    }
+}
+/// The stream was closed and every item in it has been received
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct Closed;
+impl Error for Closed{
+    fn is_recoverable(&self) -> bool {
+        true
+    }
 }
 
 pub trait DuplexStream: SendStream + ReceiveStream<RData=<Self as SendStream>::SData>{}
@@ -214,3 +278,30 @@ pub trait MessageStreamCreator<T>{
         (stream1, stream2)
     }
 }
+
+#[cfg(all(test, feature = "std"))]
+mod test{
+    use crate::stream::std_impls::new_mpsc_channel;
+    use crate::stream::{SendStream, ReceiveStream};
+    use alloc::boxed::Box;
+    use alloc::sync::Arc;
+
+    #[test]
+    fn send_array_test(){
+        let (sender, receiver) = new_mpsc_channel();
+        sender.send_array([1u8, 2, 3]);
+        Arc::new(sender).send_array([4]);
+
+        assert_eq!(receiver.receive_vec(4), vec![1, 2, 3, 4]);
+        assert_eq!(receiver.try_receive(), None);
+    }
+
+    #[test]
+    fn dyn_send_stream_test(){
+        let (sender, receiver) = new_mpsc_channel();
+        let sender: Box<dyn SendStream<SData=u8>> = Box::new(sender);
+        sender.send(1);
+        sender.send_slice(&[2, 3]);
+        assert_eq!(receiver.receive_vec(3), vec![1, 2, 3]);
+    }
+}
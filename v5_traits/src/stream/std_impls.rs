@@ -1,4 +1,4 @@
-use crate::stream::{SendStream, ReceiveStream, MessageStreamCreator, SendTimeoutStream, ReceiveTimoutStream};
+use crate::stream::{SendStream, ReceiveStream, MessageStreamCreator, SendTimeoutStream, ReceiveTimoutStream, BoundedStream};
 use std::sync::mpsc::{Sender, Receiver, channel, RecvTimeoutError};
 use core::ops::{Deref, DerefMut};
 use core::time::Duration;
@@ -37,6 +37,7 @@ impl<T> SendTimeoutStream for Mutex<Sender<T>> where T: 'static + Send{
         }
     }
 }
+impl<T> BoundedStream for Mutex<Sender<T>> where T: 'static + Send{}
 
 impl<T> ReceiveStream for Mutex<Receiver<T>> where T: 'static + Send{
     type RData = T;
@@ -76,10 +77,13 @@ impl<T> ReceiveTimoutStream for Mutex<Receiver<T>> where T: 'static + Send{
     }
 }
 
+impl<T> BoundedStream for Mutex<Receiver<T>> where T: 'static + Send{}
+
 pub fn new_mpsc_channel<T>() -> (Mutex<Sender<T>>, Mutex<Receiver<T>>) where T: 'static + Send{
     let out = channel();
     (Mutex::new(out.0), Mutex::new(out.1))
 }
+#[derive(Copy, Clone, Debug)]
 pub struct MPSCMessageCreator();
 impl<T> MessageStreamCreator<T> for MPSCMessageCreator where T: 'static + Send{
     type Sender = Mutex<Sender<T>>;
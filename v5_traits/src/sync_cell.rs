@@ -1,33 +1,222 @@
 use alloc::boxed::Box;
 use core::ptr::null_mut;
+use core::hint::spin_loop;
+use core::mem::transmute;
 use core::sync::atomic::{AtomicPtr, Ordering};
+use core::time::Duration;
+use crate::stream::SendTimeoutStream;
+use crate::UniversalFunctions;
 
+/// Spins a cell that is busy is checked before calling the busy wait hook
+const BUSY_SPINS: u32 = 64;
+/// Its address is stored in a cell while clone_inner has the value out, no box can be allocated there
+static BUSY_MARKER: u8 = 0;
+/// The fn() called to wait for a busy cell, null to only spin
+static BUSY_WAIT_HOOK: AtomicPtr<()> = AtomicPtr::new(null_mut());
+
+/// Sets what a task waiting for a busy cell calls after spinning a while
+/// A cell is busy while a clone_inner on another task clones its value, on a priority scheduler the waiter must let
+/// lower priority tasks run or a higher priority task can wait forever on one it preempted, so this should delay.
+/// With std the default is thread::yield_now, otherwise waiters only spin
+pub fn set_busy_wait_hook(hook: fn()){
+    BUSY_WAIT_HOOK.store(hook as *mut (), Ordering::Release);
+}
+
+fn busy<T>() -> *mut T{
+    &BUSY_MARKER as *const u8 as *mut T
+}
+
+/// Waits a bit for a busy cell, spins counts the calls made for the current wait
+fn wait_busy(spins: &mut u32){
+    if *spins < BUSY_SPINS{
+        *spins += 1;
+        spin_loop();
+        return;
+    }
+    let hook = BUSY_WAIT_HOOK.load(Ordering::Acquire);
+    if !hook.is_null(){
+        unsafe { transmute::<*mut (), fn()>(hook)() }
+    }
+    else{
+        #[cfg(feature = "std")]
+        std::thread::yield_now();
+        #[cfg(not(feature = "std"))]
+        spin_loop();
+    }
+}
+
+fn into_ptr<T>(value: Option<Box<T>>) -> *mut T{
+    match value{
+        None => null_mut(),
+        Some(value) => Box::into_raw(value),
+    }
+}
+
+/// # Safety
+/// pointer must be null or from into_ptr and not owned by anything else
+unsafe fn from_ptr<T>(pointer: *mut T) -> Option<Box<T>>{
+    if pointer.is_null() { None } else { Some(Box::from_raw(pointer)) }
+}
+
+/// Stores value back into a busy cell when dropped, so a panic while busy can not leave the cell busy forever
+struct Restore<'a, T>{
+    data: &'a AtomicPtr<T>,
+    value: *mut T,
+}
+impl<'a, T> Drop for Restore<'a, T>{
+    fn drop(&mut self) {
+        self.data.store(self.value, Ordering::Release);
+    }
+}
+
+/// An atomically swappable optional box
+/// Every operation waits for a concurrent clone_inner to finish instead of seeing the cell as empty
 pub struct SyncCell<T>{
     data: AtomicPtr<T>,
 }
 impl<T> SyncCell<T>{
     pub fn new(value: Option<Box<T>>) -> Self{
         match value{
-            None => Self{ data: AtomicPtr::new(null_mut()) },
+            None => Self::empty(),
             Some(value) => Self{ data: AtomicPtr::new(Box::leak(value)) }
         }
     }
 
+    /// An empty cell, usable in statics
+    pub const fn empty() -> Self{
+        Self{ data: AtomicPtr::new(null_mut()) }
+    }
+
     pub fn swap(&self, new: Option<Box<T>>) -> Option<Box<T>>{
-        let new = match new{
-            None => null_mut(),
-            Some(new) => Box::leak(new),
-        };
-        let taken = self.data.swap(new, Ordering::SeqCst);
-        if taken.is_null(){
-            None
+        self.swap_ordered(new, Ordering::SeqCst)
+    }
+
+    /// Swaps like swap with ordering for the value, every ordering is valid here
+    /// Relaxed is enough when only one task touches the cell or the value carries no data other tasks depend on
+    pub fn swap_ordered(&self, new: Option<Box<T>>, ordering: Ordering) -> Option<Box<T>>{
+        let new = into_ptr(new);
+        let mut spins = 0;
+        loop {
+            let current = self.data.load(Ordering::Relaxed);
+            if current == busy(){
+                wait_busy(&mut spins);
+            }
+            else if self.data.compare_exchange_weak(current, new, ordering, Ordering::Relaxed).is_ok(){
+                return unsafe { from_ptr(current) };
+            }
+        }
+    }
+
+    /// Empties the cell and returns what it held
+    pub fn take(&self) -> Option<Box<T>>{
+        self.swap(None)
+    }
+
+    /// Stores value and returns what the cell held before
+    pub fn replace(&self, value: Box<T>) -> Option<Box<T>>{
+        self.swap(Some(value))
+    }
+
+    /// Stores new only if the cell holds the pointer current, null for empty
+    /// Returns Ok with the old value on success or Err with new given back if the cell held something else.
+    /// Only addresses are compared, if the value current pointed to was dropped and a new box was allocated at
+    /// the same address this still succeeds. Get current from as_ptr and never dereference it
+    pub fn compare_exchange(&self, current: *mut T, new: Option<Box<T>>) -> Result<Option<Box<T>>, Option<Box<T>>>{
+        self.compare_exchange_ordered(current, new, Ordering::SeqCst, Ordering::SeqCst)
+    }
+
+    /// Compare exchanges like compare_exchange with success ordering when new is stored and failure ordering when it is not
+    /// Panics before touching the cell if failure is Release or AcqRel, a failed exchange only loads so it can not release
+    pub fn compare_exchange_ordered(&self, current: *mut T, new: Option<Box<T>>, success: Ordering, failure: Ordering) -> Result<Option<Box<T>>, Option<Box<T>>>{
+        assert!(!matches!(failure, Ordering::Release | Ordering::AcqRel), "SyncCell compare exchange failure ordering can not be {:?}", failure);
+        let new = into_ptr(new);
+        let mut spins = 0;
+        loop {
+            match self.data.compare_exchange(current, new, success, failure){
+                Ok(taken) => return Ok(unsafe { from_ptr(taken) }),
+                Err(actual) if actual == busy() => wait_busy(&mut spins),
+                Err(_) => return Err(unsafe { from_ptr(new) }),
+            }
+        }
+    }
+
+    /// Replaces the held value with what f returns for it
+    /// The value is claimed with a compare and swap that leaves the cell busy, retried if another task swaps first,
+    /// and what f returns is stored in its place. Concurrent operations wait for f like they do for clone_inner so f should be short.
+    /// If f panics the cell is left empty
+    pub fn update<F>(&self, mut f: F) where F: FnMut(Option<Box<T>>) -> Option<Box<T>>{
+        let mut spins = 0;
+        loop {
+            let current = self.data.load(Ordering::Acquire);
+            if current == busy(){
+                wait_busy(&mut spins);
+            }
+            else if self.data.compare_exchange_weak(current, busy(), Ordering::Acquire, Ordering::Relaxed).is_ok(){
+                let mut restore = Restore{ data: &self.data, value: null_mut() };
+                restore.value = into_ptr(f(unsafe { from_ptr(current) }));
+                return;
+            }
         }
-        else{
-            let taken = unsafe {Box::from_raw(taken)};
-            Some(taken)
+    }
+
+    /// The address of the held value or null if empty, for compare_exchange
+    /// The value can be dropped by a concurrent swap at any time so this must not be dereferenced
+    pub fn as_ptr(&self) -> *mut T{
+        self.load_settled()
+    }
+
+    /// Loads the held pointer, waiting out a concurrent clone_inner
+    fn load_settled(&self) -> *mut T{
+        let mut spins = 0;
+        loop {
+            let current = self.data.load(Ordering::SeqCst);
+            if current != busy(){
+                return current;
+            }
+            wait_busy(&mut spins);
+        }
+    }
+
+    /// Swaps like swap but sends the old value to drop_queue instead of returning it
+    /// For values that must be dropped on a specific task, that task receives from drop_queue and drops what it gets.
+    /// Does not block, if drop_queue is full the old value is dropped on the current task and a warning is logged
+    pub fn swap_defer_drop(&self, new: Option<Box<T>>, drop_queue: &impl SendTimeoutStream<SData=Box<T>>, uf: &impl UniversalFunctions){
+        if let Some(old) = self.swap(new){
+            if let Some(old) = drop_queue.send_timeout(old, Duration::new(0, 0), uf){
+                uf.log_warn(|| "SyncCell drop queue full, dropping inline");
+                drop(old);
+            }
+        }
+    }
+
+    /// Clones the held value without removing it
+    /// The value is claimed with a compare and swap that leaves the cell busy while it is cloned and is then restored.
+    /// Concurrent operations on the cell wait for the clone instead of seeing it empty, if a concurrent swap lands
+    /// between the load and the claim the claim fails and is retried on the new value.
+    /// Waiting tasks spin then call the busy wait hook, so T's clone should be cheap like an Arc's
+    pub fn clone_inner(&self) -> Option<T> where T: Clone{
+        let mut spins = 0;
+        loop {
+            let current = self.data.load(Ordering::Acquire);
+            if current.is_null(){
+                return None;
+            }
+            if current == busy(){
+                wait_busy(&mut spins);
+            }
+            else if self.data.compare_exchange_weak(current, busy(), Ordering::Acquire, Ordering::Relaxed).is_ok(){
+                let _restore = Restore{ data: &self.data, value: current };
+                return Some(unsafe { (*current).clone() });
+            }
         }
     }
+
+    /// True if the cell holds a value, it may be taken by a concurrent swap right after
+    pub fn is_some(&self) -> bool{
+        !self.load_settled().is_null()
+    }
 }
+
 impl<T> Drop for SyncCell<T>{
     fn drop(&mut self) {
         self.swap(None);
@@ -54,3 +243,199 @@ impl<T> From<Option<Box<T>>> for SyncCell<T>{
         Self::new(from)
     }
 }
+
+#[cfg(all(test, feature = "std"))]
+mod test{
+    use crate::sync_cell::SyncCell;
+    use crate::stream::ReceiveStream;
+    use crate::stream::std_impls::new_mpsc_channel;
+    use crate::{UniversalFunctions, LogLevel};
+    use alloc::sync::Arc;
+    use alloc::boxed::Box;
+    use alloc::vec::Vec;
+    use core::fmt::Display;
+    use core::ptr::null_mut;
+    use core::sync::atomic::{AtomicUsize, Ordering};
+    use core::time::Duration;
+    use std::thread::{sleep, spawn};
+    use std::time::SystemTime;
+
+    #[derive(Debug, Clone)]
+    struct Uf();
+    impl UniversalFunctions for Uf{
+        fn delay(&self, duration: Duration) {
+            sleep(duration)
+        }
+
+        fn system_time(&self) -> Duration {
+            SystemTime::now().duration_since(SystemTime::UNIX_EPOCH).unwrap()
+        }
+
+        fn print(&self, out: impl Display) {
+            print!("{}", out)
+        }
+
+        fn eprint(&self, out: impl Display) {
+            eprint!("{}", out)
+        }
+
+        fn min_log_level(&self) -> LogLevel {
+            LogLevel::TRACE
+        }
+
+        fn log_intern(&self, message: impl Display, _level: LogLevel) {
+            eprintln!("{}", message)
+        }
+    }
+
+    #[test]
+    fn clone_inner_test(){
+        let cell = SyncCell::from(Arc::new(10));
+        assert_eq!(cell.clone_inner().as_deref(), Some(&10));
+        assert_eq!(cell.swap(None).as_deref().map(|val| **val), Some(10));
+        assert!(cell.clone_inner().is_none());
+    }
+
+    #[test]
+    fn clone_inner_swap_test(){
+        let cell = Arc::new(SyncCell::from(Arc::new(0)));
+        let swapper_cell = cell.clone();
+        let swapper = spawn(move ||{
+            for val in 1..=10000{
+                swapper_cell.swap(Some(Box::new(Arc::new(val))));
+            }
+        });
+        let mut last = 0;
+        while !swapper.is_finished(){
+            if let Some(val) = cell.clone_inner(){
+                assert!(*val >= last && *val <= 10000, "Clone went backwards from {} to {}", last, val);
+                last = *val;
+            }
+        }
+        swapper.join().unwrap();
+        assert_eq!(cell.clone_inner().as_deref(), Some(&10000));
+    }
+
+    #[test]
+    fn clone_inner_take_test(){
+        let cell = Arc::new(SyncCell::from(Arc::new(1)));
+        let clones = Arc::new(AtomicUsize::new(0));
+        let cloners: Vec<_> = (0..4).map(|_|{
+            let cell = cell.clone();
+            let clones = clones.clone();
+            spawn(move ||{
+                while let Some(val) = cell.clone_inner(){
+                    assert_eq!(*val, 1);
+                    clones.fetch_add(1, Ordering::SeqCst);
+                }
+            })
+        }).collect();
+        while clones.load(Ordering::SeqCst) < 1000{
+            assert!(cell.is_some(), "Full cell looked empty during a clone");
+        }
+        assert_eq!(cell.take().as_deref().map(|val| **val), Some(1));
+        for cloner in cloners{
+            cloner.join().unwrap();
+        }
+        assert!(!cell.is_some(), "Take was undone by a clone");
+    }
+
+    #[test]
+    fn take_test(){
+        let cell = SyncCell::<u32>::empty();
+        assert_eq!(cell.take(), None);
+        let cell = SyncCell::from(5);
+        assert_eq!(cell.take(), Some(Box::new(5)));
+        assert!(!cell.is_some());
+        assert_eq!(cell.take(), None);
+    }
+
+    #[test]
+    fn replace_test(){
+        let cell = SyncCell::empty();
+        assert_eq!(cell.replace(Box::new(1)), None);
+        assert_eq!(cell.replace(Box::new(2)), Some(Box::new(1)));
+        assert_eq!(cell.take(), Some(Box::new(2)));
+    }
+
+    #[test]
+    fn compare_exchange_test(){
+        let cell = Arc::new(SyncCell::empty());
+        let claimers: Vec<_> = (0..8).map(|id|{
+            let cell = cell.clone();
+            spawn(move || match cell.compare_exchange(null_mut(), Some(Box::new(id))){
+                Ok(old) => {
+                    assert_eq!(old, None);
+                    Some(id)
+                },
+                Err(rejected) => {
+                    assert_eq!(rejected, Some(Box::new(id)));
+                    None
+                },
+            })
+        }).collect();
+        let winners: Vec<_> = claimers.into_iter().filter_map(|claimer| claimer.join().unwrap()).collect();
+        assert_eq!(winners.len(), 1);
+        assert_eq!(cell.clone_inner(), Some(winners[0]));
+
+        let current = cell.as_ptr();
+        assert_eq!(cell.compare_exchange(current, None), Ok(Some(Box::new(winners[0]))));
+        assert_eq!(cell.compare_exchange(current, Some(Box::new(100))), Err(Some(Box::new(100))));
+    }
+
+    #[test]
+    fn ordered_test(){
+        let value = Arc::new(0);
+        let cell = SyncCell::empty();
+        for ordering in [Ordering::Relaxed, Ordering::Release, Ordering::Acquire, Ordering::AcqRel]{
+            drop(cell.swap_ordered(Some(Box::new(value.clone())), ordering));
+        }
+        assert_eq!(Arc::strong_count(&value), 2);
+        assert!(cell.compare_exchange_ordered(null_mut(), None, Ordering::Relaxed, Ordering::Relaxed).is_err());
+        let current = cell.as_ptr();
+        let taken = cell.compare_exchange_ordered(current, Some(Box::new(value.clone())), Ordering::AcqRel, Ordering::Acquire);
+        assert!(matches!(taken, Ok(Some(_))));
+        drop(taken);
+        let rejected = cell.compare_exchange_ordered(null_mut(), Some(Box::new(value.clone())), Ordering::Release, Ordering::Relaxed);
+        assert!(matches!(rejected, Err(Some(_))));
+        drop(rejected);
+        drop(cell.swap_ordered(None, Ordering::Relaxed));
+        assert_eq!(Arc::strong_count(&value), 1);
+    }
+
+    #[test]
+    #[should_panic(expected = "failure ordering can not be Release")]
+    fn ordered_invalid_failure_test(){
+        let cell = SyncCell::from(1);
+        let _ = cell.compare_exchange_ordered(cell.as_ptr(), None, Ordering::SeqCst, Ordering::Release);
+    }
+
+    #[test]
+    fn update_test(){
+        let cell = Arc::new(SyncCell::from(0));
+        let updaters: Vec<_> = (0..2).map(|_|{
+            let cell = cell.clone();
+            spawn(move ||{
+                for _ in 0..1000{
+                    cell.update(|count| count.map(|count| Box::new(*count + 1)));
+                }
+            })
+        }).collect();
+        for updater in updaters{
+            updater.join().unwrap();
+        }
+        assert_eq!(cell.clone_inner(), Some(2000));
+    }
+
+    #[test]
+    fn swap_defer_drop_test(){
+        let (sender, receiver) = new_mpsc_channel();
+        let value = Arc::new(1);
+        let cell = SyncCell::from(value.clone());
+        cell.swap_defer_drop(Some(Box::new(Arc::new(2))), &sender, &Uf());
+        let deferred = receiver.try_receive().unwrap();
+        assert!(Arc::ptr_eq(&deferred, &value));
+        assert_eq!(Arc::strong_count(&value), 2);
+        assert_eq!(cell.clone_inner().as_deref(), Some(&2));
+    }
+}
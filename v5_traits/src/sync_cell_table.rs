@@ -0,0 +1,117 @@
+use crate::sync_cell::SyncCell;
+use alloc::boxed::Box;
+use core::array::from_fn;
+
+/// A fixed table of N SyncCells, each slot is swapped atomically without locking the others
+pub struct SyncCellTable<T, const N: usize>{
+    slots: [SyncCell<T>; N],
+}
+impl<T, const N: usize> SyncCellTable<T, N>{
+    /// Creates a table with every slot empty
+    pub fn new() -> Self{
+        Self{ slots: from_fn(|_| SyncCell::empty()) }
+    }
+
+    /// The amount of slots
+    pub const fn capacity(&self) -> usize{
+        N
+    }
+
+    /// Panics if index is not less than N
+    pub fn slot(&self, index: usize) -> &SyncCell<T>{
+        &self.slots[index]
+    }
+
+    pub fn swap(&self, index: usize, new: Option<Box<T>>) -> Option<Box<T>>{
+        self.slot(index).swap(new)
+    }
+
+    /// Empties the slot at index and returns what it held
+    pub fn take(&self, index: usize) -> Option<Box<T>>{
+        self.swap(index, None)
+    }
+
+    /// Stores val in the slot at index and drops what it held
+    pub fn store(&self, index: usize, val: T){
+        self.swap(index, Some(Box::new(val)));
+    }
+
+    /// Clones the value in the slot at index, see SyncCell::clone_inner
+    pub fn get(&self, index: usize) -> Option<T> where T: Clone{
+        self.slot(index).clone_inner()
+    }
+
+    /// Counts occupied slots by scanning each, concurrent swaps can change the result before it returns
+    pub fn occupied_count(&self) -> usize{
+        self.slots.iter().filter(|slot| slot.is_some()).count()
+    }
+
+    /// Iterates over the index and a clone of the value of each occupied slot
+    /// Each slot is read when the iterator reaches it so this is not a snapshot of the whole table
+    pub fn iter_occupied(&self) -> impl Iterator<Item=(usize, T)> + '_ where T: Clone{
+        self.slots.iter().enumerate().filter_map(|(index, slot)| slot.clone_inner().map(|val| (index, val)))
+    }
+}
+impl<T, const N: usize> Default for SyncCellTable<T, N>{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod test{
+    use crate::sync_cell_table::SyncCellTable;
+    use alloc::sync::Arc;
+    use alloc::vec::Vec;
+    use std::thread::spawn;
+
+    #[test]
+    fn sync_cell_table_test(){
+        let table = SyncCellTable::<u32, 4>::new();
+        table.store(1, 10);
+        table.store(3, 30);
+        assert_eq!(table.occupied_count(), 2);
+        assert_eq!(table.iter_occupied().collect::<Vec<_>>(), vec![(1, 10), (3, 30)]);
+        assert_eq!(table.take(1).as_deref(), Some(&10));
+        assert_eq!(table.get(1), None);
+        assert_eq!(table.get(3), Some(30));
+    }
+
+    #[test]
+    fn sync_cell_table_stress_test(){
+        const THREADS: usize = 4;
+        const ITERATIONS: usize = 10000;
+
+        // Slot 8 is written once and never taken, concurrent readers must always find it
+        let table = Arc::new(SyncCellTable::<(usize, usize), 9>::new());
+        table.store(8, (THREADS, ITERATIONS));
+        let handles = (0..THREADS).map(|thread|{
+            let table = table.clone();
+            spawn(move ||{
+                for iteration in 0..ITERATIONS{
+                    table.store((thread + iteration) % 8, (thread, iteration));
+                    if let Some(taken) = table.take((thread + iteration + 3) % 8){
+                        assert!(taken.0 < THREADS && taken.1 < ITERATIONS, "Invalid value {:?} taken", taken);
+                    }
+                    assert_eq!(table.get(8), Some((THREADS, ITERATIONS)));
+                    let mut pinned_seen = false;
+                    for (index, val) in table.iter_occupied(){
+                        if index == 8{
+                            pinned_seen = true;
+                        }
+                        else{
+                            assert!(val.0 < THREADS && val.1 < ITERATIONS);
+                        }
+                    }
+                    assert!(pinned_seen, "Occupied slot 8 was skipped");
+                }
+            })
+        }).collect::<Vec<_>>();
+        for handle in handles{
+            handle.join().unwrap();
+        }
+        assert_eq!(table.occupied_count(), table.iter_occupied().count());
+        assert!(table.occupied_count() <= 9);
+        assert_eq!(table.get(8), Some((THREADS, ITERATIONS)));
+    }
+}